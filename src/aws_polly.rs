@@ -1,5 +1,10 @@
-use std::{fs::OpenOptions, io::Write};
+use std::sync::Arc;
 
+use crate::{
+    error::{Error, SdkResultExt, Service},
+    output::{report, OutputSink, TerminalSink},
+    pagination::{collect_items, items_of_pages, ItemStream},
+};
 use aws_config::SdkConfig;
 use aws_sdk_memorydb::primitives::DateTimeFormat;
 use aws_sdk_polly::{
@@ -10,15 +15,25 @@ use aws_sdk_polly::{
     Client as PollyClient,
 };
 use std::ops::Deref;
+use tokio_stream::StreamExt;
 
 use colored::Colorize;
 
-pub struct PollyOps<'a> {
-    config: &'a SdkConfig,
+#[derive(Debug, Clone)]
+pub struct PollyOps {
+    client: PollyClient,
+    sink: Arc<dyn OutputSink>,
 }
-impl<'a> PollyOps<'a> {
-    pub fn build(config: &'a SdkConfig) -> Self {
-        Self { config }
+impl PollyOps {
+    pub fn build(config: &SdkConfig) -> Self {
+        Self {
+            client: PollyClient::new(config),
+            sink: Arc::new(TerminalSink),
+        }
+    }
+    pub fn with_sink(mut self, sink: Arc<dyn OutputSink>) -> Self {
+        self.sink = sink;
+        self
     }
     pub async fn synthesize_speech(
         &self,
@@ -28,9 +43,7 @@ impl<'a> PollyOps<'a> {
         voice_id: &str,
         language_code: &str,
         text_type: &str,
-    ) -> SpeechOuputInfo {
-        let client = PollyClient::new(self.config);
-
+    ) -> Result<SpeechOuputInfo, Error> {
         let engine_builder = Engine::from(engine);
 
         let ouput_format_builder = OutputFormat::from(output_format);
@@ -42,7 +55,8 @@ impl<'a> PollyOps<'a> {
         let language_code_builder = LanguageCode::from(language_code);
 
         let language = LanguageCode::EnUs;
-        let output = client
+        let output = self
+            .client
             .synthesize_speech()
             .engine(engine_builder)
             .output_format(ouput_format_builder)
@@ -53,17 +67,17 @@ impl<'a> PollyOps<'a> {
             .language_code(language)
             .send()
             .await
-            .expect("Error while synthesizing speech\n");
+            .map_sdk_err(Service::Polly, "synthesize_speech")?;
 
         let speech_bytes = output.audio_stream;
         let character_synthesized = output.request_characters;
         let content_type = output.content_type;
 
-        SpeechOuputInfo::build_speech_output_info(
+        Ok(SpeechOuputInfo::build_speech_output_info(
             Some(speech_bytes),
             character_synthesized,
             content_type,
-        )
+        ))
     }
 
     pub async fn start_speech_synthesise_task(
@@ -75,9 +89,7 @@ impl<'a> PollyOps<'a> {
         text_to_synthesize: &str,
         output_format: &str,
         bucket_name: &str,
-    ) {
-        let client = PollyClient::new(self.config);
-
+    ) -> Result<(), Error> {
         let engine_builder = Engine::from(engine);
 
         let output_format_builder = OutputFormat::from(output_format);
@@ -88,7 +100,8 @@ impl<'a> PollyOps<'a> {
 
         let language_code_builder = LanguageCode::from(language_code);
 
-        let output = client
+        let output = self
+            .client
             .start_speech_synthesis_task()
             .engine(engine_builder)
             .voice_id(voice_id_builder)
@@ -100,9 +113,10 @@ impl<'a> PollyOps<'a> {
             .output_s3_key_prefix("speech_synthesis_task_outputs/")
             .send()
             .await
-            .expect("Error while start synthesize task\n");
+            .map_sdk_err(Service::Polly, "start_speech_synthesis_task")?;
         let key_prefix = "'speech_synthesis_task_outputs/'".green().bold();
-        println!(
+        report!(
+            self.sink,
             "The key prefix in the provided bucket is set to: {key_prefix}\n.
         "
         );
@@ -111,54 +125,55 @@ impl<'a> PollyOps<'a> {
         if let Some(synthesizeinfo) = synthesize_info {
             let task_id = synthesizeinfo.task_id;
             if let Some(task_id) = task_id {
-                println!(
+                report!(
+                    self.sink,
                     "The Task ID for initiating speech synthesis tasks is as follows: {}\n",
                     task_id.green().bold()
                 );
-                let mut file = OpenOptions::new()
-                    .create(true)
-                    .read(true)
-                    .write(true)
-                    .open("task_id.txt")
-                    .expect("Error while creating file\n");
                 let buf = format!("The speech synthesis task with ID {task_id} has been initiated for the bucket named {bucket_name}");
-                match file.write_all(buf.as_bytes()) {
-                    Ok(_) => println!("{}\n","The Task ID for the speech synthesis task has been successfully written to the current directory".green().bold()),
-                    Err(_) => println!("Error while writing data\n")
+                match self.sink.write_artifact("task_id.txt", buf.as_bytes()) {
+                    Ok(location) => {
+                        let msg = format!("The Task ID for the speech synthesis task has been successfully written to {location}");
+                        report!(self.sink, "{}\n", msg.green().bold())
+                    }
+                    Err(_) => report!(self.sink, "Error while writing data\n"),
                 }
             }
         }
+        Ok(())
     }
-    pub async fn get_speech_synthesis_result(&self, task_id: &str) -> Option<SynthesizeTask> {
-        let client = PollyClient::new(self.config);
-        let output = client
+    pub async fn get_speech_synthesis_result(
+        &self,
+        task_id: &str,
+    ) -> Result<Option<SynthesizeTask>, Error> {
+        let output = self
+            .client
             .get_speech_synthesis_task()
             .task_id(task_id)
             .send()
             .await
-            .expect("Error while getting speech synthesis outputs\n");
+            .map_sdk_err(Service::Polly, "get_speech_synthesis_task")?;
 
         let mut synthesis_task: Option<SynthesizeTask> = None;
         if let Some(task) = output.synthesis_task {
             let type_ = SynthesizeTask::wrap(task);
             synthesis_task = Some(type_);
         }
-        synthesis_task
+        Ok(synthesis_task)
     }
 
     /// Returns a tuple consisting of two vectors: one containing options for [`voice ID`](https://docs.rs/aws-sdk-polly/latest/aws_sdk_polly/types/struct.Voice.html#method.id) and the other containing options for [`language code`](https://docs.aws.amazon.com/polly/latest/dg/API_StartSpeechSynthesisTask.html#polly-StartSpeechSynthesisTask-request-LanguageCode), based on the engine name provided
     pub async fn get_voice_info_given_engine(
         &self,
         engine_name: &str,
-    ) -> (Vec<Option<VoiceId>>, Vec<Option<LanguageCode>>) {
-        let client = PollyClient::new(self.config);
-
-        let output = client
+    ) -> Result<(Vec<Option<VoiceId>>, Vec<Option<LanguageCode>>), Error> {
+        let output = self
+            .client
             .describe_voices()
             .set_engine(Some(engine_name.into()))
             .send()
             .await
-            .expect("Error while get information about voices\n");
+            .map_sdk_err(Service::Polly, "describe_voices")?;
         let voices = output.voices;
         let mut supported_voice_id = Vec::new();
         let mut supported_langauge_name = Vec::new();
@@ -169,7 +184,7 @@ impl<'a> PollyOps<'a> {
                 supported_langauge_name.push(voice.language_code);
             });
         }
-        (supported_voice_id, supported_langauge_name)
+        Ok((supported_voice_id, supported_langauge_name))
     }
     pub async fn generate_all_available_voices_in_mp3(
         &self,
@@ -177,8 +192,8 @@ impl<'a> PollyOps<'a> {
         language_code: &str,
         engine_name: &str,
         path_prefix: &str,
-    ) {
-        let (voices, _) = self.get_voice_info_given_engine(engine_name).await;
+    ) -> Result<(), Error> {
+        let (voices, _) = self.get_voice_info_given_engine(engine_name).await?;
         for voice_name in voices.into_iter() {
             if let Some(voice_name) = voice_name {
                 let voice_id_str = voice_name.as_str().to_string();
@@ -191,28 +206,22 @@ impl<'a> PollyOps<'a> {
                         language_code,
                         "ssml",
                     )
-                    .await;
+                    .await?;
                 output
-                    .generate_audio_with_path_name(path_prefix, &voice_id_str)
-                    .await;
+                    .generate_audio_with_path_name(path_prefix, &voice_id_str, self.sink.as_ref())
+                    .await?;
             }
         }
+        Ok(())
     }
     /// List the synthesis tasks. The status is hardcoded as 'Completed,' meaning it only returns tasks that are in the 'Completed' state. However, for other states, you need to obtain input from the caller and construct the [`TaskStatus`](https://docs.rs/aws-sdk-polly/latest/aws_sdk_polly/types/enum.TaskStatus.html) using the [`from`](https://docs.rs/aws-sdk-polly/latest/aws_sdk_polly/types/enum.TaskStatus.html#impl-From%3C%26str%3E-for-TaskStatus) method
-    pub async fn list_synthesise_speech(&self) {
-        let client = PollyClient::new(self.config);
-
+    pub async fn list_synthesise_speech(&self) -> Result<(), Error> {
         let status_builder = TaskStatus::Completed;
 
-        let output = client
-            .list_speech_synthesis_tasks()
-            .status(status_builder)
-            .send()
-            .await
-            .expect("Error while listing synthesise tasks\n");
-        let info = output.synthesis_tasks;
-        if let Some(vec_of_tasks) = info {
-            println!("Synthesize Task Details\n\n");
+        let vec_of_tasks =
+            collect_items(self.list_speech_synthesis_tasks_stream(Some(status_builder))).await?;
+        if !vec_of_tasks.is_empty() {
+            report!(self.sink, "Synthesize Task Details\n\n");
             vec_of_tasks.into_iter().for_each(|task| {
                 let creation_time = task
                     .creation_time
@@ -223,31 +232,46 @@ impl<'a> PollyOps<'a> {
                 let task_status = task.task_status.map(|fmt| fmt.as_str().to_string());
                 let output_uri = task.output_uri;
                 if let Some(time) = creation_time {
-                    println!("Creation Time: {}", time.green().bold());
+                    report!(self.sink, "Creation Time: {}", time.green().bold());
                 }
                 if let Some(task_id_) = task_id {
-                    println!("Task ID: {}", task_id_.green().bold());
+                    report!(self.sink, "Task ID: {}", task_id_.green().bold());
                 }
                 if let Some(reason) = status_reason {
-                    println!("Status Reason: {}", reason.green().bold());
+                    report!(self.sink, "Status Reason: {}", reason.green().bold());
                 }
                 if let Some(status) = task_status {
-                    println!("Task Status: {}", status.green().bold());
+                    report!(self.sink, "Task Status: {}", status.green().bold());
                 }
                 if let Some(uri) = output_uri {
-                    println!("Output URI: {}\n", uri.green().bold());
+                    report!(self.sink, "Output URI: {}\n", uri.green().bold());
                 }
             });
         }
+        Ok(())
     }
-    pub async fn describe_voices(&self) -> Vec<DescribeVoices> {
-        let client = PollyClient::new(self.config);
-
-        let output = client
+    /// Lists the synthesis tasks, optionally only those with the given status, fetching
+    /// further pages as the stream is consumed
+    pub fn list_speech_synthesis_tasks_stream(
+        &self,
+        status: Option<TaskStatus>,
+    ) -> ItemStream<SynthesisTask> {
+        let pages = self
+            .client
+            .list_speech_synthesis_tasks()
+            .set_status(status)
+            .into_paginator()
+            .send()
+            .map(|page| page.map_sdk_err(Service::Polly, "list_speech_synthesis_tasks"));
+        items_of_pages(pages, |page| page.synthesis_tasks.unwrap_or_default())
+    }
+    pub async fn describe_voices(&self) -> Result<Vec<DescribeVoices>, Error> {
+        let output = self
+            .client
             .describe_voices()
             .send()
             .await
-            .expect("Error while describing voices\n");
+            .map_sdk_err(Service::Polly, "describe_voices")?;
         let mut vec_of_voices = Vec::new();
 
         if let Some(voices) = output.voices {
@@ -255,7 +279,7 @@ impl<'a> PollyOps<'a> {
                 vec_of_voices.push(DescribeVoices::wrap(voice));
             });
         }
-        vec_of_voices
+        Ok(vec_of_voices)
     }
 }
 
@@ -401,8 +425,18 @@ impl SpeechOuputInfo {
             content_type,
         }
     }
-    pub async fn generate_audio_with_path_name(&mut self, path_prefix: &str, path_alias: &str) {
-        let bytestream = self.speech_bytes.take().unwrap();
+    /// The audio stream can only be written once; calling this again returns an
+    /// [`Error::InvalidInput`].
+    pub async fn generate_audio_with_path_name(
+        &mut self,
+        path_prefix: &str,
+        path_alias: &str,
+        sink: &dyn OutputSink,
+    ) -> Result<(), Error> {
+        let bytestream = self.speech_bytes.take().ok_or(Error::invalid_input(
+            "generate_audio_with_path_name",
+            "the synthesized audio has already been written",
+        ))?;
         let extenstion = if let Some(content_type) = self.content_type.as_deref() {
             Some(content_type.split('/').skip(1).collect::<String>())
         } else {
@@ -410,29 +444,32 @@ impl SpeechOuputInfo {
         };
         if let Some(extension_) = extenstion {
             let path_name = format!("{path_prefix}{path_alias}_voice_audio.{}", extension_);
-            let mut file = OpenOptions::new()
-                .create(true)
-                .write(true)
-                .read(true)
-                .open(&path_name)
-                .expect("Error while creating file in the current directory");
             let bytes = bytestream
                 .collect()
                 .await
-                .expect("Error while converting to bytes")
+                .map_err(|err| Error::io("generate_audio_with_path_name", &path_name, err.into()))?
                 .into_bytes();
-            match file.write_all(&bytes) {
+            match sink.write_artifact(&path_name, &bytes) {
                 Ok(_) => {
                     let colored_msg =format!("An audio file with the name {}/{}_voice_audio.{} has been successfully created\n",path_prefix.green().bold(),path_alias.green().bold(),extension_.green().bold());
-                    println!("{colored_msg}");
+                    report!(sink, "{colored_msg}");
+                }
+                Err(err) => {
+                    report!(sink, "Error while writing data..");
+                    return Err(Error::io("generate_audio_with_path_name", path_name, err));
                 }
-                Err(_) => println!("Error while writing data.."),
             }
         }
+        Ok(())
     }
 
-    pub async fn generate_audio(&mut self) {
-        let bytestream = self.speech_bytes.take().unwrap();
+    /// The audio stream can only be written once; calling this again returns an
+    /// [`Error::InvalidInput`].
+    pub async fn generate_audio(&mut self, sink: &dyn OutputSink) -> Result<(), Error> {
+        let bytestream = self.speech_bytes.take().ok_or(Error::invalid_input(
+            "generate_audio",
+            "the synthesized audio has already been written",
+        ))?;
         let extenstion = if let Some(content_type) = self.content_type.as_deref() {
             Some(content_type.split('/').skip(1).collect::<String>())
         } else {
@@ -440,25 +477,23 @@ impl SpeechOuputInfo {
         };
         if let Some(extension_) = extenstion {
             let path_name = format!("synthesized_audio.{}", extension_);
-            let mut file = OpenOptions::new()
-                .create(true)
-                .write(true)
-                .read(true)
-                .open(&path_name)
-                .expect("Error while creating file in the current directory");
             let bytes = bytestream
                 .collect()
                 .await
-                .expect("Error while converting to bytes")
+                .map_err(|err| Error::io("generate_audio", &path_name, err.into()))?
                 .into_bytes();
-            match file.write_all(&bytes) {
-                Ok(_) => {
-                    let colored_msg =format!("An audio file with the extension {} has been successfully written to the current directory\n",extension_.green().bold());
-                    println!("{colored_msg}");
+            match sink.write_artifact(&path_name, &bytes) {
+                Ok(location) => {
+                    let colored_msg =format!("An audio file with the extension {} has been successfully written to {location}\n",extension_.green().bold());
+                    report!(sink, "{colored_msg}");
+                }
+                Err(err) => {
+                    report!(sink, "Error while writing data..");
+                    return Err(Error::io("generate_audio", path_name, err));
                 }
-                Err(_) => println!("Error while writing data.."),
             }
         }
+        Ok(())
     }
     pub fn get_synthesized_count(&self) -> i32 {
         self.character_synthesized
@@ -0,0 +1,550 @@
+use std::{collections::BTreeMap, path::Path};
+
+use aws_sdk_s3::types::{
+    AbortIncompleteMultipartUpload, BucketVersioningStatus, CorsRule as SdkCorsRule,
+    ExpirationStatus, LifecycleExpiration, LifecycleRule as SdkLifecycleRule,
+    LifecycleRuleAndOperator, LifecycleRuleFilter, NoncurrentVersionExpiration,
+    PublicAccessBlockConfiguration, Tag, Transition as SdkTransition, TransitionStorageClass,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+
+/// The configuration of a bucket, as a document that can be kept next to the code and
+/// applied with [`S3Ops::apply_bucket_config`](crate::S3Ops::apply_bucket_config). Sections
+/// left out are not touched; an empty `lifecycle` or `cors` list removes those rules.
+///
+/// In TOML:
+///
+/// ```toml
+/// versioning = "Enabled"
+///
+/// [public_access_block]
+/// block_public_acls = true
+/// ignore_public_acls = true
+/// block_public_policy = true
+/// restrict_public_buckets = true
+///
+/// [[lifecycle]]
+/// id = "expire-logs"
+/// prefix = "logs/"
+/// expiration_days = 90
+/// abort_incomplete_multipart_days = 7
+/// transitions = [{ days = 30, storage_class = "STANDARD_IA" }]
+///
+/// [[cors]]
+/// allowed_origins = ["https://example.com"]
+/// allowed_methods = ["GET", "PUT"]
+/// max_age_seconds = 3000
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BucketConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub versioning: Option<Versioning>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lifecycle: Option<Vec<LifecycleRule>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cors: Option<Vec<CorsRule>>,
+    /// The policy document, either inline or as a JSON string.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub policy: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_access_block: Option<PublicAccessBlock>,
+}
+
+impl BucketConfig {
+    /// Reads a configuration from a `.json` or `.toml` file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .map_err(|err| Error::io("BucketConfig::from_file", path.display().to_string(), err))?;
+        match Format::of(path)? {
+            Format::Json => Self::from_json(&contents),
+            Format::Toml => Self::from_toml(&contents),
+        }
+    }
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        serde_json::from_str(json).map_err(|err| {
+            Error::invalid_input(
+                "BucketConfig::from_json",
+                format!("invalid configuration: {err}"),
+            )
+        })
+    }
+    pub fn from_toml(toml: &str) -> Result<Self, Error> {
+        toml::from_str(toml).map_err(|err| {
+            Error::invalid_input(
+                "BucketConfig::from_toml",
+                format!("invalid configuration: {err}"),
+            )
+        })
+    }
+    /// Writes the configuration to a `.json` or `.toml` file, such as one returned by
+    /// [`S3Ops::get_bucket_config`](crate::S3Ops::get_bucket_config), so another bucket can be
+    /// set up the same way.
+    pub fn to_file(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let path = path.as_ref();
+        let contents = match Format::of(path)? {
+            Format::Json => serde_json::to_string_pretty(self)
+                .map_err(|err| Error::other("BucketConfig::to_file", err))?,
+            Format::Toml => toml::to_string_pretty(self)
+                .map_err(|err| Error::other("BucketConfig::to_file", err))?,
+        };
+        std::fs::write(path, contents)
+            .map_err(|err| Error::io("BucketConfig::to_file", path.display().to_string(), err))
+    }
+    /// The policy as the JSON text S3 takes, after checking that a policy given as a string
+    /// is valid JSON.
+    pub(crate) fn policy_document(&self) -> Result<Option<String>, Error> {
+        match &self.policy {
+            Some(serde_json::Value::String(policy)) => {
+                serde_json::from_str::<serde_json::Value>(policy).map_err(|err| {
+                    Error::invalid_input("put_bucket_policy", format!("invalid policy: {err}"))
+                })?;
+                Ok(Some(policy.clone()))
+            }
+            Some(policy) => Ok(Some(policy.to_string())),
+            None => Ok(None),
+        }
+    }
+}
+
+enum Format {
+    Json,
+    Toml,
+}
+
+impl Format {
+    fn of(path: &Path) -> Result<Self, Error> {
+        let extension = path
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_ascii_lowercase());
+        match extension.as_deref() {
+            Some("json") => Ok(Self::Json),
+            Some("toml") => Ok(Self::Toml),
+            _ => Err(Error::invalid_input(
+                "BucketConfig",
+                format!("'{}' is neither a .json nor a .toml file", path.display()),
+            )),
+        }
+    }
+}
+
+/// Whether a bucket keeps every version of its objects. Once enabled, versioning can only be
+/// suspended, never turned off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Versioning {
+    Enabled,
+    Suspended,
+}
+
+impl Versioning {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Enabled => "Enabled",
+            Self::Suspended => "Suspended",
+        }
+    }
+    pub(crate) fn to_sdk(self) -> BucketVersioningStatus {
+        BucketVersioningStatus::from(self.as_str())
+    }
+    pub(crate) fn from_sdk(status: &BucketVersioningStatus) -> Option<Self> {
+        match status {
+            BucketVersioningStatus::Enabled => Some(Self::Enabled),
+            BucketVersioningStatus::Suspended => Some(Self::Suspended),
+            _ => None,
+        }
+    }
+}
+
+/// A lifecycle rule for the objects under a prefix and, optionally, with all of the given
+/// tags. Days are counted from an object's creation, or for `noncurrent_expiration_days`
+/// from when a newer version replaced it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LifecycleRule {
+    pub id: String,
+    #[serde(default = "enabled")]
+    pub enabled: bool,
+    /// An empty prefix applies the rule to the whole bucket.
+    #[serde(default)]
+    pub prefix: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tags: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expiration_days: Option<i32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub transitions: Vec<Transition>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub noncurrent_expiration_days: Option<i32>,
+    /// Aborts multipart uploads that are still unfinished this many days after they started,
+    /// which also frees the storage of their parts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub abort_incomplete_multipart_days: Option<i32>,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+impl LifecycleRule {
+    /// An enabled rule for the whole bucket that does nothing until an action is set.
+    pub fn new(id: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            enabled: true,
+            prefix: String::new(),
+            tags: BTreeMap::new(),
+            expiration_days: None,
+            transitions: Vec::new(),
+            noncurrent_expiration_days: None,
+            abort_incomplete_multipart_days: None,
+        }
+    }
+    pub(crate) fn to_sdk(&self) -> SdkLifecycleRule {
+        let tags = self
+            .tags
+            .iter()
+            .map(|(key, value)| Tag::builder().key(key).value(value).build())
+            .collect::<Vec<_>>();
+        let filter = match (self.prefix.is_empty(), tags.len()) {
+            (_, 0) => LifecycleRuleFilter::Prefix(self.prefix.clone()),
+            (true, 1) => LifecycleRuleFilter::Tag(tags[0].clone()),
+            _ => LifecycleRuleFilter::And(
+                LifecycleRuleAndOperator::builder()
+                    .set_prefix((!self.prefix.is_empty()).then(|| self.prefix.clone()))
+                    .set_tags(Some(tags))
+                    .build(),
+            ),
+        };
+        let status = match self.enabled {
+            true => ExpirationStatus::Enabled,
+            false => ExpirationStatus::Disabled,
+        };
+        let transitions = self
+            .transitions
+            .iter()
+            .map(|transition| {
+                SdkTransition::builder()
+                    .days(transition.days)
+                    .storage_class(TransitionStorageClass::from(
+                        transition.storage_class.as_str(),
+                    ))
+                    .build()
+            })
+            .collect::<Vec<_>>();
+        SdkLifecycleRule::builder()
+            .id(&self.id)
+            .filter(filter)
+            .status(status)
+            .set_expiration(
+                self.expiration_days
+                    .map(|days| LifecycleExpiration::builder().days(days).build()),
+            )
+            .set_transitions((!transitions.is_empty()).then_some(transitions))
+            .set_noncurrent_version_expiration(self.noncurrent_expiration_days.map(|days| {
+                NoncurrentVersionExpiration::builder()
+                    .noncurrent_days(days)
+                    .build()
+            }))
+            .set_abort_incomplete_multipart_upload(self.abort_incomplete_multipart_days.map(
+                |days| {
+                    AbortIncompleteMultipartUpload::builder()
+                        .days_after_initiation(days)
+                        .build()
+                },
+            ))
+            .build()
+    }
+    pub(crate) fn from_sdk(rule: &SdkLifecycleRule) -> Self {
+        let tag_pair = |tag: &Tag| {
+            (
+                tag.key().unwrap_or_default().to_string(),
+                tag.value().unwrap_or_default().to_string(),
+            )
+        };
+        //Rules written before filters existed keep their prefix on the rule itself
+        #[allow(deprecated)]
+        let legacy_prefix = rule.prefix().unwrap_or_default().to_string();
+        let (prefix, tags) = match rule.filter() {
+            Some(LifecycleRuleFilter::Prefix(prefix)) => (prefix.clone(), BTreeMap::new()),
+            Some(LifecycleRuleFilter::Tag(tag)) => (String::new(), BTreeMap::from([tag_pair(tag)])),
+            Some(LifecycleRuleFilter::And(and)) => (
+                and.prefix().unwrap_or_default().to_string(),
+                and.tags()
+                    .unwrap_or_default()
+                    .iter()
+                    .map(tag_pair)
+                    .collect(),
+            ),
+            _ => (legacy_prefix, BTreeMap::new()),
+        };
+        //The SDK reports days that weren't set as 0
+        let days = |days: i32| (days > 0).then_some(days);
+        Self {
+            id: rule.id().unwrap_or_default().to_string(),
+            enabled: rule.status() == Some(&ExpirationStatus::Enabled),
+            prefix,
+            tags,
+            expiration_days: rule
+                .expiration()
+                .and_then(|expiration| days(expiration.days())),
+            transitions: rule
+                .transitions()
+                .unwrap_or_default()
+                .iter()
+                .map(|transition| Transition {
+                    days: transition.days(),
+                    storage_class: transition
+                        .storage_class()
+                        .map(|class| class.as_str().to_string())
+                        .unwrap_or_default(),
+                })
+                .collect(),
+            noncurrent_expiration_days: rule
+                .noncurrent_version_expiration()
+                .and_then(|expiration| days(expiration.noncurrent_days())),
+            abort_incomplete_multipart_days: rule
+                .abort_incomplete_multipart_upload()
+                .and_then(|abort| days(abort.days_after_initiation())),
+        }
+    }
+}
+
+/// Moves objects to another storage class, such as `STANDARD_IA` or `GLACIER`, a number of
+/// days after they were created.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Transition {
+    pub days: i32,
+    pub storage_class: String,
+}
+
+/// Which web pages may call the bucket from a browser, and how.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CorsRule {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Origins such as `https://example.com`, or `*` for any.
+    pub allowed_origins: Vec<String>,
+    /// Any of `GET`, `PUT`, `POST`, `DELETE` and `HEAD`.
+    pub allowed_methods: Vec<String>,
+    /// Request headers a preflight request may ask for, or `*` for any.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_headers: Vec<String>,
+    /// Response headers scripts may read, such as `ETag`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub expose_headers: Vec<String>,
+    /// How long browsers may cache the preflight response.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_age_seconds: Option<i32>,
+}
+
+impl CorsRule {
+    pub(crate) fn to_sdk(&self) -> SdkCorsRule {
+        let list = |values: &Vec<String>| (!values.is_empty()).then(|| values.clone());
+        SdkCorsRule::builder()
+            .set_id(self.id.clone())
+            .set_allowed_origins(Some(self.allowed_origins.clone()))
+            .set_allowed_methods(Some(self.allowed_methods.clone()))
+            .set_allowed_headers(list(&self.allowed_headers))
+            .set_expose_headers(list(&self.expose_headers))
+            .set_max_age_seconds(self.max_age_seconds)
+            .build()
+    }
+    pub(crate) fn from_sdk(rule: &SdkCorsRule) -> Self {
+        let list = |values: Option<&[String]>| values.unwrap_or_default().to_vec();
+        Self {
+            id: rule.id().map(|id| id.to_string()),
+            allowed_origins: list(rule.allowed_origins()),
+            allowed_methods: list(rule.allowed_methods()),
+            allowed_headers: list(rule.allowed_headers()),
+            expose_headers: list(rule.expose_headers()),
+            max_age_seconds: (rule.max_age_seconds() > 0).then_some(rule.max_age_seconds()),
+        }
+    }
+}
+
+/// The settings that keep a bucket from being made public through ACLs or its policy.
+/// Settings left out of a configuration file are off.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PublicAccessBlock {
+    /// Rejects requests that add public ACLs.
+    pub block_public_acls: bool,
+    /// Ignores public ACLs that already exist.
+    pub ignore_public_acls: bool,
+    /// Rejects bucket policies that grant public access.
+    pub block_public_policy: bool,
+    /// Limits a public policy to AWS services and the bucket owner's account.
+    pub restrict_public_buckets: bool,
+}
+
+impl PublicAccessBlock {
+    /// Every setting on, which S3 also uses for new buckets.
+    pub fn block_all() -> Self {
+        Self {
+            block_public_acls: true,
+            ignore_public_acls: true,
+            block_public_policy: true,
+            restrict_public_buckets: true,
+        }
+    }
+    pub(crate) fn to_sdk(self) -> PublicAccessBlockConfiguration {
+        PublicAccessBlockConfiguration::builder()
+            .block_public_acls(self.block_public_acls)
+            .ignore_public_acls(self.ignore_public_acls)
+            .block_public_policy(self.block_public_policy)
+            .restrict_public_buckets(self.restrict_public_buckets)
+            .build()
+    }
+    pub(crate) fn from_sdk(configuration: &PublicAccessBlockConfiguration) -> Self {
+        Self {
+            block_public_acls: configuration.block_public_acls(),
+            ignore_public_acls: configuration.ignore_public_acls(),
+            block_public_policy: configuration.block_public_policy(),
+            restrict_public_buckets: configuration.restrict_public_buckets(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    //The example in the documentation of `BucketConfig`
+    const EXAMPLE: &str = r#"
+versioning = "Enabled"
+
+[public_access_block]
+block_public_acls = true
+ignore_public_acls = true
+block_public_policy = true
+restrict_public_buckets = true
+
+[[lifecycle]]
+id = "expire-logs"
+prefix = "logs/"
+expiration_days = 90
+abort_incomplete_multipart_days = 7
+transitions = [{ days = 30, storage_class = "STANDARD_IA" }]
+
+[[cors]]
+allowed_origins = ["https://example.com"]
+allowed_methods = ["GET", "PUT"]
+max_age_seconds = 3000
+"#;
+
+    #[test]
+    fn parses_the_documented_example() {
+        let config = BucketConfig::from_toml(EXAMPLE).unwrap();
+        assert_eq!(config.versioning, Some(Versioning::Enabled));
+        assert_eq!(
+            config.public_access_block,
+            Some(PublicAccessBlock::block_all())
+        );
+        let lifecycle = config.lifecycle.unwrap();
+        assert_eq!(lifecycle.len(), 1);
+        assert!(lifecycle[0].enabled);
+        assert_eq!(lifecycle[0].prefix, "logs/");
+        assert_eq!(lifecycle[0].expiration_days, Some(90));
+        assert_eq!(lifecycle[0].abort_incomplete_multipart_days, Some(7));
+        assert_eq!(
+            lifecycle[0].transitions,
+            [Transition {
+                days: 30,
+                storage_class: "STANDARD_IA".into()
+            }]
+        );
+        let cors = config.cors.unwrap();
+        assert_eq!(cors[0].allowed_methods, ["GET", "PUT"]);
+        assert_eq!(cors[0].max_age_seconds, Some(3000));
+        assert_eq!(config.policy, None);
+    }
+
+    #[test]
+    fn round_trips_with_a_policy() {
+        let mut config = BucketConfig::from_toml(EXAMPLE).unwrap();
+        let policy = json!({
+            "Version": "2012-10-17",
+            "Statement": [{
+                "Effect": "Deny",
+                "Principal": "*",
+                "Action": "s3:*",
+                "Resource": ["arn:aws:s3:::logs", "arn:aws:s3:::logs/*"],
+                "Condition": {"Bool": {"aws:SecureTransport": "false"}},
+            }],
+        });
+        config.policy = Some(policy.clone());
+        let json = serde_json::to_string_pretty(&config).unwrap();
+        assert_eq!(BucketConfig::from_json(&json).unwrap(), config);
+        let toml = toml::to_string_pretty(&config).unwrap();
+        assert_eq!(BucketConfig::from_toml(&toml).unwrap(), config);
+        assert_eq!(config.policy_document().unwrap(), Some(policy.to_string()));
+
+        config.policy = Some(serde_json::Value::String(policy.to_string()));
+        assert_eq!(config.policy_document().unwrap(), Some(policy.to_string()));
+        config.policy = Some(serde_json::Value::String("{not json".into()));
+        assert!(config.policy_document().is_err());
+    }
+
+    #[test]
+    fn rejects_misspelled_keys() {
+        assert!(BucketConfig::from_toml("versionning = \"Enabled\"").is_err());
+        assert!(BucketConfig::from_toml("[[lifecycle]]\nid = \"x\"\nexpiration_day = 1").is_err());
+        assert!(BucketConfig::from_json(
+            r#"{"cors": [{"allowed_origins": [], "allowed_methods": [], "max_age": 1}]}"#
+        )
+        .is_err());
+        assert!(
+            BucketConfig::from_json(r#"{"public_access_block": {"block_all": true}}"#).is_err()
+        );
+    }
+
+    #[test]
+    fn converts_lifecycle_rules_to_and_from_the_sdk() {
+        let mut tagged = LifecycleRule::new("archive");
+        tagged.prefix = "reports/".into();
+        tagged.tags = BTreeMap::from([
+            ("team".to_string(), "data".to_string()),
+            ("tier".to_string(), "cold".to_string()),
+        ]);
+        tagged.transitions = vec![Transition {
+            days: 30,
+            storage_class: "GLACIER".into(),
+        }];
+        tagged.noncurrent_expiration_days = Some(60);
+        let mut one_tag = LifecycleRule::new("one-tag");
+        one_tag.enabled = false;
+        one_tag.tags = BTreeMap::from([("team".to_string(), "data".to_string())]);
+        one_tag.expiration_days = Some(365);
+        one_tag.abort_incomplete_multipart_days = Some(7);
+        for rule in [tagged, one_tag, LifecycleRule::new("everything")] {
+            assert_eq!(LifecycleRule::from_sdk(&rule.to_sdk()), rule);
+        }
+    }
+
+    #[test]
+    fn reads_days_of_zero_as_unset() {
+        let rule = SdkLifecycleRule::builder()
+            .id("markers")
+            .filter(LifecycleRuleFilter::Prefix(String::new()))
+            .status(ExpirationStatus::Enabled)
+            .expiration(
+                LifecycleExpiration::builder()
+                    .expired_object_delete_marker(true)
+                    .build(),
+            )
+            .noncurrent_version_expiration(NoncurrentVersionExpiration::builder().build())
+            .abort_incomplete_multipart_upload(AbortIncompleteMultipartUpload::builder().build())
+            .build();
+        assert_eq!(
+            LifecycleRule::from_sdk(&rule),
+            LifecycleRule::new("markers")
+        );
+    }
+}
@@ -0,0 +1,578 @@
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BinaryHeap},
+    fmt::Write,
+    time::{Duration, SystemTime},
+};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::error::Error;
+
+const DAY: u64 = 24 * 60 * 60;
+//The upper bound of each age range in days; the last one is open
+const AGE_RANGES: &[(&str, Option<u64>)] = &[
+    ("under 30 days", Some(30)),
+    ("30 to 90 days", Some(90)),
+    ("90 to 180 days", Some(180)),
+    ("180 days to 1 year", Some(365)),
+    ("1 to 2 years", Some(730)),
+    ("over 2 years", None),
+];
+
+/// What [`S3Ops::bucket_usage`](crate::S3Ops::bucket_usage) looks at.
+///
+/// By default the whole bucket is analysed, objects are grouped by their first prefix
+/// component (`logs/`, `images/`, ...), the ten largest objects are listed and incomplete
+/// multipart uploads are included.
+#[derive(Debug, Clone)]
+pub struct UsageOptions {
+    prefix: String,
+    prefix_depth: usize,
+    largest: usize,
+    incomplete_uploads: bool,
+}
+
+impl Default for UsageOptions {
+    fn default() -> Self {
+        Self {
+            prefix: String::new(),
+            prefix_depth: 1,
+            largest: 10,
+            incomplete_uploads: true,
+        }
+    }
+}
+
+impl UsageOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Limits the analysis to the objects under a prefix. Prefix depths are counted from it.
+    pub fn prefix(mut self, prefix: &str) -> Self {
+        self.prefix = prefix.to_string();
+        self
+    }
+    /// How many `/`-separated components group objects, so with 2 `logs/2023/a.gz` counts
+    /// towards `logs/2023/`. Objects nested less deeply count towards the prefix they have;
+    /// 0 puts everything in one group.
+    pub fn prefix_depth(mut self, depth: usize) -> Self {
+        self.prefix_depth = depth;
+        self
+    }
+    /// How many of the largest objects the report lists.
+    pub fn largest(mut self, count: usize) -> Self {
+        self.largest = count;
+        self
+    }
+    /// Whether multipart uploads that were never completed or aborted are listed. Their
+    /// parts are billed like objects but don't show up in listings, and counting them takes
+    /// a request per upload.
+    pub fn incomplete_uploads(mut self, include: bool) -> Self {
+        self.incomplete_uploads = include;
+        self
+    }
+    pub(crate) fn scope(&self) -> &str {
+        &self.prefix
+    }
+    pub(crate) fn includes_incomplete_uploads(&self) -> bool {
+        self.incomplete_uploads
+    }
+}
+
+/// A number of objects and their combined size.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct UsageTotals {
+    objects: u64,
+    bytes: u64,
+}
+
+impl UsageTotals {
+    pub fn objects(&self) -> u64 {
+        self.objects
+    }
+    pub fn bytes(&self) -> u64 {
+        self.bytes
+    }
+    fn add(&mut self, bytes: u64) {
+        self.objects += 1;
+        self.bytes += bytes;
+    }
+}
+
+/// The objects under one prefix, by storage class.
+#[derive(Debug, Clone, Serialize)]
+pub struct PrefixUsage {
+    prefix: String,
+    totals: UsageTotals,
+    storage_classes: BTreeMap<String, UsageTotals>,
+}
+
+impl PrefixUsage {
+    /// The prefix with its trailing `/`; objects directly under the analysed prefix count
+    /// towards that prefix, which is empty for the top of the bucket.
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+    pub fn totals(&self) -> UsageTotals {
+        self.totals
+    }
+    pub fn storage_classes(&self) -> &BTreeMap<String, UsageTotals> {
+        &self.storage_classes
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LargeObject {
+    key: String,
+    size: u64,
+    storage_class: String,
+    last_modified: Option<String>,
+}
+
+impl LargeObject {
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+    pub fn storage_class(&self) -> &str {
+        &self.storage_class
+    }
+    /// When the object was last written, in RFC 3339.
+    pub fn last_modified(&self) -> Option<&str> {
+        self.last_modified.as_deref()
+    }
+}
+
+/// The objects last modified within an age range.
+#[derive(Debug, Clone, Serialize)]
+pub struct AgeRange {
+    label: &'static str,
+    max_age_days: Option<u64>,
+    totals: UsageTotals,
+}
+
+impl AgeRange {
+    /// A description such as `30 to 90 days`.
+    pub fn label(&self) -> &str {
+        self.label
+    }
+    /// The age the range goes up to, excluded; `None` for the oldest range.
+    pub fn max_age_days(&self) -> Option<u64> {
+        self.max_age_days
+    }
+    pub fn totals(&self) -> UsageTotals {
+        self.totals
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IncompleteUpload {
+    key: String,
+    upload_id: String,
+    initiated: Option<String>,
+    parts: u64,
+    bytes: u64,
+}
+
+impl IncompleteUpload {
+    pub(crate) fn new(
+        key: &str,
+        upload_id: &str,
+        initiated: Option<SystemTime>,
+        parts: u64,
+        bytes: u64,
+    ) -> Self {
+        Self {
+            key: key.to_string(),
+            upload_id: upload_id.to_string(),
+            initiated: initiated.map(rfc3339),
+            parts,
+            bytes,
+        }
+    }
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+    pub fn upload_id(&self) -> &str {
+        &self.upload_id
+    }
+    /// When the upload was started, in RFC 3339.
+    pub fn initiated(&self) -> Option<&str> {
+        self.initiated.as_deref()
+    }
+    pub fn parts(&self) -> u64 {
+        self.parts
+    }
+    /// The size of the parts uploaded so far, which is what S3 bills.
+    pub fn bytes(&self) -> u64 {
+        self.bytes
+    }
+}
+
+/// Where the storage of a bucket goes, from [`S3Ops::bucket_usage`](crate::S3Ops::bucket_usage).
+/// It can be rendered with [`create_bucket_usage_pdf`](crate::create_bucket_usage_pdf) or
+/// exported with [`to_json`](Self::to_json) and [`to_csv`](Self::to_csv).
+#[derive(Debug, Clone, Serialize)]
+pub struct BucketUsageReport {
+    bucket: String,
+    prefix: String,
+    prefix_depth: usize,
+    generated_at: String,
+    totals: UsageTotals,
+    storage_classes: BTreeMap<String, UsageTotals>,
+    prefixes: Vec<PrefixUsage>,
+    largest_objects: Vec<LargeObject>,
+    ages: Vec<AgeRange>,
+    incomplete_uploads: Vec<IncompleteUpload>,
+}
+
+impl BucketUsageReport {
+    pub fn bucket(&self) -> &str {
+        &self.bucket
+    }
+    /// The prefix the analysis was limited to, empty for the whole bucket.
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+    pub fn prefix_depth(&self) -> usize {
+        self.prefix_depth
+    }
+    /// When the objects were listed, in RFC 3339; ages are counted up to then.
+    pub fn generated_at(&self) -> &str {
+        &self.generated_at
+    }
+    /// All objects, not counting incomplete uploads.
+    pub fn totals(&self) -> UsageTotals {
+        self.totals
+    }
+    pub fn storage_classes(&self) -> &BTreeMap<String, UsageTotals> {
+        &self.storage_classes
+    }
+    /// The prefixes at the configured depth, largest first.
+    pub fn prefixes(&self) -> &[PrefixUsage] {
+        &self.prefixes
+    }
+    /// The largest objects, largest first.
+    pub fn largest_objects(&self) -> &[LargeObject] {
+        &self.largest_objects
+    }
+    /// Every age range from the youngest to the oldest, empty ones included.
+    pub fn ages(&self) -> &[AgeRange] {
+        &self.ages
+    }
+    pub fn incomplete_uploads(&self) -> &[IncompleteUpload] {
+        &self.incomplete_uploads
+    }
+    pub fn incomplete_upload_bytes(&self) -> u64 {
+        self.incomplete_uploads
+            .iter()
+            .map(|upload| upload.bytes)
+            .sum()
+    }
+    pub fn to_json(&self) -> Result<String, Error> {
+        serde_json::to_string_pretty(self).map_err(|err| Error::other("to_json", err))
+    }
+    /// The report as one CSV table with the columns `section,name,storage_class,objects,bytes`.
+    /// The sections are `total`, `storage_class`, `prefix` (a row per prefix, then one per
+    /// storage class in it), `age`, `largest` and `incomplete_upload`, whose `objects` column
+    /// holds the number of parts.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("section,name,storage_class,objects,bytes\n");
+        let mut row = |section: &str, name: &str, class: &str, totals: UsageTotals| {
+            let _ = writeln!(
+                csv,
+                "{section},{},{},{},{}",
+                csv_field(name),
+                csv_field(class),
+                totals.objects,
+                totals.bytes
+            );
+        };
+        row("total", &self.prefix, "", self.totals);
+        for (class, totals) in &self.storage_classes {
+            row("storage_class", "", class, *totals);
+        }
+        for prefix in &self.prefixes {
+            row("prefix", &prefix.prefix, "", prefix.totals);
+            for (class, totals) in &prefix.storage_classes {
+                row("prefix", &prefix.prefix, class, *totals);
+            }
+        }
+        for age in &self.ages {
+            row("age", age.label, "", age.totals);
+        }
+        for object in &self.largest_objects {
+            let totals = UsageTotals {
+                objects: 1,
+                bytes: object.size,
+            };
+            row("largest", &object.key, &object.storage_class, totals);
+        }
+        for upload in &self.incomplete_uploads {
+            let totals = UsageTotals {
+                objects: upload.parts,
+                bytes: upload.bytes,
+            };
+            let name = format!("{} ({})", upload.key, upload.upload_id);
+            row("incomplete_upload", &name, "", totals);
+        }
+        csv
+    }
+}
+
+//Size, key, storage class and last modified time, ordered by size first
+type Candidate = (u64, String, String, Option<SystemTime>);
+
+/// Adds up the objects of a listing one at a time, so a bucket of any size is analysed in
+/// constant memory apart from the prefixes.
+pub(crate) struct UsageAggregator {
+    scope: String,
+    prefix_depth: usize,
+    largest: usize,
+    now: SystemTime,
+    totals: UsageTotals,
+    storage_classes: BTreeMap<String, UsageTotals>,
+    prefixes: BTreeMap<String, PrefixUsage>,
+    //A min-heap, so the smallest of the largest objects is the one dropped
+    largest_objects: BinaryHeap<Reverse<Candidate>>,
+    ages: Vec<UsageTotals>,
+    incomplete_uploads: Vec<IncompleteUpload>,
+}
+
+impl UsageAggregator {
+    pub(crate) fn new(options: &UsageOptions, now: SystemTime) -> Self {
+        Self {
+            scope: options.prefix.clone(),
+            prefix_depth: options.prefix_depth,
+            largest: options.largest,
+            now,
+            totals: UsageTotals::default(),
+            storage_classes: BTreeMap::new(),
+            prefixes: BTreeMap::new(),
+            largest_objects: BinaryHeap::new(),
+            ages: vec![UsageTotals::default(); AGE_RANGES.len()],
+            incomplete_uploads: Vec::new(),
+        }
+    }
+    pub(crate) fn add_object(
+        &mut self,
+        key: &str,
+        size: u64,
+        storage_class: &str,
+        last_modified: Option<SystemTime>,
+    ) {
+        self.totals.add(size);
+        self.storage_classes
+            .entry(storage_class.to_string())
+            .or_default()
+            .add(size);
+        let prefix = self.group_of(key);
+        let usage = self
+            .prefixes
+            .entry(prefix.clone())
+            .or_insert_with(|| PrefixUsage {
+                prefix,
+                totals: UsageTotals::default(),
+                storage_classes: BTreeMap::new(),
+            });
+        usage.totals.add(size);
+        usage
+            .storage_classes
+            .entry(storage_class.to_string())
+            .or_default()
+            .add(size);
+
+        let age_days = last_modified
+            .and_then(|modified| self.now.duration_since(modified).ok())
+            .unwrap_or(Duration::ZERO)
+            .as_secs()
+            / DAY;
+        let range = AGE_RANGES
+            .iter()
+            .position(|(_, max)| max.map_or(true, |max| age_days < max))
+            .unwrap_or(AGE_RANGES.len() - 1);
+        self.ages[range].add(size);
+
+        if self.largest > 0 {
+            self.largest_objects.push(Reverse((
+                size,
+                key.to_string(),
+                storage_class.to_string(),
+                last_modified,
+            )));
+            if self.largest_objects.len() > self.largest {
+                self.largest_objects.pop();
+            }
+        }
+    }
+    pub(crate) fn add_incomplete_upload(&mut self, upload: IncompleteUpload) {
+        self.incomplete_uploads.push(upload);
+    }
+    pub(crate) fn finish(self, bucket: &str) -> BucketUsageReport {
+        let mut prefixes = self.prefixes.into_values().collect::<Vec<_>>();
+        prefixes.sort_by_key(|prefix| Reverse(prefix.totals.bytes));
+        let largest_objects = self
+            .largest_objects
+            .into_sorted_vec()
+            .into_iter()
+            .map(
+                |Reverse((size, key, storage_class, last_modified))| LargeObject {
+                    key,
+                    size,
+                    storage_class,
+                    last_modified: last_modified.map(rfc3339),
+                },
+            )
+            .collect();
+        let ages = AGE_RANGES
+            .iter()
+            .zip(self.ages)
+            .map(|((label, max_age_days), totals)| AgeRange {
+                label,
+                max_age_days: *max_age_days,
+                totals,
+            })
+            .collect();
+        BucketUsageReport {
+            bucket: bucket.to_string(),
+            prefix: self.scope,
+            prefix_depth: self.prefix_depth,
+            generated_at: rfc3339(self.now),
+            totals: self.totals,
+            storage_classes: self.storage_classes,
+            prefixes,
+            largest_objects,
+            ages,
+            incomplete_uploads: self.incomplete_uploads,
+        }
+    }
+    //The scope plus up to `prefix_depth` of the directories the key is in
+    fn group_of(&self, key: &str) -> String {
+        let rest = key.strip_prefix(self.scope.as_str()).unwrap_or(key);
+        let end = match self.prefix_depth {
+            0 => None,
+            depth => rest
+                .match_indices('/')
+                .nth(depth - 1)
+                .map(|(index, _)| index)
+                .or_else(|| rest.rfind('/')),
+        };
+        match end {
+            Some(end) => format!("{}{}", self.scope, &rest[..=end]),
+            None => self.scope.clone(),
+        }
+    }
+}
+
+fn rfc3339(time: SystemTime) -> String {
+    DateTime::<Utc>::from(time).to_rfc3339()
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aggregator(options: UsageOptions) -> UsageAggregator {
+        UsageAggregator::new(
+            &options,
+            SystemTime::UNIX_EPOCH + Duration::from_secs(1000 * DAY),
+        )
+    }
+
+    #[test]
+    fn groups_keys_by_prefix_depth() {
+        let keys = ["logs/2023/a.gz", "logs/b.gz", "top.txt"];
+        let groups = |depth| {
+            let usage = aggregator(UsageOptions::new().prefix_depth(depth));
+            keys.map(|key| usage.group_of(key))
+        };
+        assert_eq!(groups(0), ["", "", ""]);
+        assert_eq!(groups(1), ["logs/", "logs/", ""]);
+        assert_eq!(groups(2), ["logs/2023/", "logs/", ""]);
+        let scoped = aggregator(UsageOptions::new().prefix("data/").prefix_depth(1));
+        assert_eq!(scoped.group_of("data/x/y/z"), "data/x/");
+        assert_eq!(scoped.group_of("data/z"), "data/");
+    }
+
+    #[test]
+    fn sorts_objects_into_age_ranges() {
+        let mut usage = aggregator(UsageOptions::new());
+        let now = usage.now;
+        let days_ago = |days: u64| Some(now - Duration::from_secs(days * DAY));
+        usage.add_object("a", 1, "STANDARD", days_ago(0));
+        usage.add_object("b", 2, "STANDARD", days_ago(29));
+        usage.add_object("c", 4, "STANDARD", days_ago(30));
+        usage.add_object("d", 8, "GLACIER", days_ago(400));
+        usage.add_object("e", 16, "GLACIER", days_ago(800));
+        //Unknown and future times count as new
+        usage.add_object("f", 32, "STANDARD", None);
+        usage.add_object("g", 64, "STANDARD", Some(now + Duration::from_secs(DAY)));
+        let report = usage.finish("bucket");
+        let ages = report
+            .ages()
+            .iter()
+            .map(|age| (age.label(), age.totals().bytes()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ages,
+            [
+                ("under 30 days", 1 + 2 + 32 + 64),
+                ("30 to 90 days", 4),
+                ("90 to 180 days", 0),
+                ("180 days to 1 year", 0),
+                ("1 to 2 years", 8),
+                ("over 2 years", 16),
+            ]
+        );
+        assert_eq!(report.totals().objects(), 7);
+        assert_eq!(report.storage_classes()["GLACIER"].bytes(), 24);
+    }
+
+    #[test]
+    fn keeps_the_largest_objects_and_prefixes_first() {
+        let mut usage = aggregator(UsageOptions::new().largest(2));
+        for (key, size) in [("a/1", 5), ("b/2", 1), ("b/3", 9), ("a/4", 3)] {
+            usage.add_object(key, size, "STANDARD", None);
+        }
+        let report = usage.finish("bucket");
+        let largest = report
+            .largest_objects()
+            .iter()
+            .map(|object| (object.key(), object.size()))
+            .collect::<Vec<_>>();
+        assert_eq!(largest, [("b/3", 9), ("a/1", 5)]);
+        let prefixes = report
+            .prefixes()
+            .iter()
+            .map(|prefix| (prefix.prefix(), prefix.totals().bytes()))
+            .collect::<Vec<_>>();
+        assert_eq!(prefixes, [("b/", 10), ("a/", 8)]);
+        let none = aggregator(UsageOptions::new().largest(0)).finish("bucket");
+        assert!(none.largest_objects().is_empty());
+    }
+
+    #[test]
+    fn quotes_csv_fields() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+        let mut usage = aggregator(UsageOptions::new().prefix_depth(0));
+        usage.add_object("x,\"y\"", 7, "STANDARD", None);
+        let csv = usage.finish("bucket").to_csv();
+        assert!(csv.starts_with("section,name,storage_class,objects,bytes\ntotal,,,1,7\n"));
+        assert!(csv.contains("\nlargest,\"x,\"\"y\"\"\",STANDARD,1,7\n"));
+    }
+}
@@ -0,0 +1,192 @@
+use std::{
+    fs::File,
+    io::{BufReader, Read},
+    path::Path,
+};
+
+use aws_sdk_s3::types::ChecksumAlgorithm;
+use aws_smithy_types::base64;
+use sha2::{Digest, Sha256};
+
+/// A checksum S3 verifies on upload and stores with the object, so downloads can be checked
+/// against it later. Set it with [`UploadOptions::checksum`](crate::UploadOptions::checksum).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Checksum {
+    /// CRC32C, which is fast and catches accidental corruption.
+    Crc32c,
+    /// SHA-256, which also holds up against deliberate tampering.
+    Sha256,
+}
+
+impl Checksum {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Crc32c => "CRC32C",
+            Self::Sha256 => "SHA256",
+        }
+    }
+    pub(crate) fn algorithm(&self) -> ChecksumAlgorithm {
+        match self {
+            Self::Crc32c => ChecksumAlgorithm::Crc32C,
+            Self::Sha256 => ChecksumAlgorithm::Sha256,
+        }
+    }
+}
+
+pub(crate) enum ChecksumHasher {
+    Crc32c(u32),
+    Sha256(Sha256),
+}
+
+impl ChecksumHasher {
+    pub(crate) fn new(checksum: Checksum) -> Self {
+        match checksum {
+            Checksum::Crc32c => Self::Crc32c(0),
+            Checksum::Sha256 => Self::Sha256(Sha256::new()),
+        }
+    }
+    pub(crate) fn update(&mut self, bytes: &[u8]) {
+        match self {
+            Self::Crc32c(crc) => *crc = crc32c::crc32c_append(*crc, bytes),
+            Self::Sha256(hasher) => hasher.update(bytes),
+        }
+    }
+    /// The raw digest; a CRC32C is four big-endian bytes, as S3 encodes it.
+    pub(crate) fn finish(self) -> Vec<u8> {
+        match self {
+            Self::Crc32c(crc) => crc.to_be_bytes().to_vec(),
+            Self::Sha256(hasher) => hasher.finalize().to_vec(),
+        }
+    }
+}
+
+/// The base64 checksum of some bytes, in the form S3's checksum headers take.
+pub(crate) fn checksum_of(checksum: Checksum, bytes: &[u8]) -> String {
+    let mut hasher = ChecksumHasher::new(checksum);
+    hasher.update(bytes);
+    base64::encode(hasher.finish())
+}
+
+/// The checksum S3 reports for a multipart object: the checksum of the parts' raw checksums
+/// followed by `-` and the number of parts. `None` if a part checksum isn't valid base64.
+pub(crate) fn composite_checksum<'a>(
+    checksum: Checksum,
+    part_checksums: impl IntoIterator<Item = &'a str>,
+) -> Option<String> {
+    let mut hasher = ChecksumHasher::new(checksum);
+    let mut parts = 0;
+    for part in part_checksums {
+        hasher.update(&base64::decode(part).ok()?);
+        parts += 1;
+    }
+    Some(format!("{}-{parts}", base64::encode(hasher.finish())))
+}
+
+/// The checksum of a file in base64, or the composite checksum of its parts when a part
+/// size is given.
+pub(crate) fn checksum_of_file(
+    path: &Path,
+    checksum: Checksum,
+    part_size: Option<u64>,
+) -> std::io::Result<String> {
+    let mut file = BufReader::new(File::open(path)?);
+    let mut buffer = vec![0; 1024 * 1024];
+    let mut part_checksums = Vec::new();
+    let mut hasher = ChecksumHasher::new(checksum);
+    let mut in_part = 0;
+    loop {
+        let wanted = match part_size {
+            Some(part_size) => buffer.len().min((part_size - in_part) as usize),
+            None => buffer.len(),
+        };
+        let read = file.read(&mut buffer[..wanted])?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        in_part += read as u64;
+        if Some(in_part) == part_size {
+            let part = std::mem::replace(&mut hasher, ChecksumHasher::new(checksum));
+            part_checksums.push(base64::encode(part.finish()));
+            in_part = 0;
+        }
+    }
+    if part_size.is_none() {
+        return Ok(base64::encode(hasher.finish()));
+    }
+    if in_part > 0 || part_checksums.is_empty() {
+        part_checksums.push(base64::encode(hasher.finish()));
+    }
+    Ok(
+        composite_checksum(checksum, part_checksums.iter().map(|part| part.as_str()))
+            .unwrap_or_default(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use super::*;
+
+    //Uploaded in parts of 5 bytes: `hello`, ` worl` and `d`
+    const CONTENT: &[u8] = b"hello world";
+    const PART_SIZE: u64 = 5;
+    const CRC32C_PARTS: [&str; 3] = ["mnG7TA==", "DCQYQw==", "9CFXLA=="];
+    const SHA256_PARTS: [&str; 3] = [
+        "LPJNul+wow4m6DsqxbninhsWHlwfp0JecwQzYpOLmCQ=",
+        "Q8+JdyDMWmk7JQjJ6V6HEZQqlu2Y13L+NxI9MWA84g8=",
+        "GKw+c0PwFokMUQ6T+TUmEWnZ4/VlQ2Qpgw+vCTT0+OQ=",
+    ];
+    const CRC32C_COMPOSITE: &str = "XqP4uQ==-3";
+    const SHA256_COMPOSITE: &str = "pzSGO5U+k/TnIIwV9oZNNyf1DQbT37kZpa4hVAUTJ/A=-3";
+
+    fn content_file(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("aws_apis-{}-{name}", std::process::id()));
+        fs::write(&path, CONTENT).unwrap();
+        path
+    }
+
+    #[test]
+    fn matches_known_checksums() {
+        //The standard check value of CRC32C, 0xE3069283
+        assert_eq!(checksum_of(Checksum::Crc32c, b"123456789"), "4waSgw==");
+        assert_eq!(checksum_of(Checksum::Crc32c, CONTENT), "yZRlqg==");
+        assert_eq!(
+            checksum_of(Checksum::Sha256, CONTENT),
+            "uU0nuZNNPgilLlLX2n2r+sSE7+N6U4DukIj3rOLvzek="
+        );
+        for (index, part) in CONTENT.chunks(PART_SIZE as usize).enumerate() {
+            assert_eq!(checksum_of(Checksum::Crc32c, part), CRC32C_PARTS[index]);
+            assert_eq!(checksum_of(Checksum::Sha256, part), SHA256_PARTS[index]);
+        }
+    }
+
+    #[test]
+    fn combines_part_checksums() {
+        assert_eq!(
+            composite_checksum(Checksum::Crc32c, CRC32C_PARTS).as_deref(),
+            Some(CRC32C_COMPOSITE)
+        );
+        assert_eq!(
+            composite_checksum(Checksum::Sha256, SHA256_PARTS).as_deref(),
+            Some(SHA256_COMPOSITE)
+        );
+        assert_eq!(composite_checksum(Checksum::Crc32c, ["not base64!"]), None);
+    }
+
+    #[test]
+    fn checksums_files_whole_and_in_parts() {
+        let path = content_file("checksums");
+        let of_file = |checksum, part_size| checksum_of_file(&path, checksum, part_size).unwrap();
+        assert_eq!(of_file(Checksum::Crc32c, None), "yZRlqg==");
+        assert_eq!(of_file(Checksum::Crc32c, Some(PART_SIZE)), CRC32C_COMPOSITE);
+        assert_eq!(of_file(Checksum::Sha256, Some(PART_SIZE)), SHA256_COMPOSITE);
+        //A single part is still a composite of one
+        assert_eq!(
+            of_file(Checksum::Crc32c, Some(CONTENT.len() as u64)),
+            composite_checksum(Checksum::Crc32c, ["yZRlqg=="]).unwrap()
+        );
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,166 @@
+use std::{fs::File, io::Read, path::Path};
+
+//Lowercase extensions and the types browsers expect for them
+const EXTENSIONS: &[(&str, &str)] = &[
+    ("html", "text/html; charset=utf-8"),
+    ("htm", "text/html; charset=utf-8"),
+    ("css", "text/css; charset=utf-8"),
+    ("js", "text/javascript; charset=utf-8"),
+    ("mjs", "text/javascript; charset=utf-8"),
+    ("json", "application/json"),
+    ("map", "application/json"),
+    ("webmanifest", "application/manifest+json"),
+    ("xml", "application/xml"),
+    ("txt", "text/plain; charset=utf-8"),
+    ("md", "text/markdown; charset=utf-8"),
+    ("csv", "text/csv; charset=utf-8"),
+    ("tsv", "text/tab-separated-values; charset=utf-8"),
+    ("yaml", "application/yaml"),
+    ("yml", "application/yaml"),
+    ("toml", "application/toml"),
+    ("svg", "image/svg+xml"),
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("gif", "image/gif"),
+    ("webp", "image/webp"),
+    ("avif", "image/avif"),
+    ("bmp", "image/bmp"),
+    ("ico", "image/vnd.microsoft.icon"),
+    ("tif", "image/tiff"),
+    ("tiff", "image/tiff"),
+    ("woff", "font/woff"),
+    ("woff2", "font/woff2"),
+    ("ttf", "font/ttf"),
+    ("otf", "font/otf"),
+    ("mp3", "audio/mpeg"),
+    ("wav", "audio/wav"),
+    ("flac", "audio/flac"),
+    ("ogg", "audio/ogg"),
+    ("m4a", "audio/mp4"),
+    ("mp4", "video/mp4"),
+    ("webm", "video/webm"),
+    ("mov", "video/quicktime"),
+    ("pdf", "application/pdf"),
+    ("zip", "application/zip"),
+    ("gz", "application/gzip"),
+    ("tar", "application/x-tar"),
+    ("wasm", "application/wasm"),
+    ("parquet", "application/vnd.apache.parquet"),
+    (
+        "docx",
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    ),
+    (
+        "xlsx",
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+    ),
+    (
+        "pptx",
+        "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+    ),
+];
+
+//Signatures at the start of a file, for files whose name has no known extension
+const SIGNATURES: &[(&[u8], &str)] = &[
+    (b"\x89PNG\r\n\x1a\n", "image/png"),
+    (b"\xff\xd8\xff", "image/jpeg"),
+    (b"GIF87a", "image/gif"),
+    (b"GIF89a", "image/gif"),
+    (b"%PDF-", "application/pdf"),
+    (b"PK\x03\x04", "application/zip"),
+    (b"\x1f\x8b", "application/gzip"),
+    (b"\x00asm", "application/wasm"),
+    (b"wOFF", "font/woff"),
+    (b"wOF2", "font/woff2"),
+    (b"ID3", "audio/mpeg"),
+    (b"fLaC", "audio/flac"),
+    (b"OggS", "audio/ogg"),
+    (b"\x1a\x45\xdf\xa3", "video/webm"),
+    (b"PAR1", "application/vnd.apache.parquet"),
+];
+
+/// The content type for a file, taken from its extension or, when the extension is missing
+/// or unknown, from the first bytes of the file. `None` when neither tells.
+pub(crate) fn detect_content_type(path: &Path) -> Option<&'static str> {
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_ascii_lowercase());
+    if let Some((_, content_type)) = EXTENSIONS
+        .iter()
+        .find(|(known, _)| Some(*known) == extension.as_deref())
+    {
+        return Some(content_type);
+    }
+    let mut head = Vec::with_capacity(16);
+    File::open(path)
+        .and_then(|file| file.take(16).read_to_end(&mut head))
+        .ok()?;
+    sniff(&head)
+}
+
+fn sniff(head: &[u8]) -> Option<&'static str> {
+    if let Some((_, content_type)) = SIGNATURES
+        .iter()
+        .find(|(signature, _)| head.starts_with(signature))
+    {
+        return Some(content_type);
+    }
+    //RIFF containers name their format at offset 8
+    match (head.get(..4), head.get(8..12)) {
+        (Some(b"RIFF"), Some(b"WEBP")) => Some("image/webp"),
+        (Some(b"RIFF"), Some(b"WAVE")) => Some("audio/wav"),
+        _ if head.get(4..8) == Some(b"ftyp") => Some("video/mp4"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn looks_up_extensions_in_any_case() {
+        //Known extensions don't open the file, so these needn't exist
+        assert_eq!(
+            detect_content_type(Path::new("logo.png")),
+            Some("image/png")
+        );
+        assert_eq!(
+            detect_content_type(Path::new("LOGO.PNG")),
+            Some("image/png")
+        );
+        assert_eq!(
+            detect_content_type(Path::new("dist/app.Js")),
+            Some("text/javascript; charset=utf-8")
+        );
+        assert_eq!(detect_content_type(Path::new("missing.xyz")), None);
+        assert_eq!(detect_content_type(Path::new("missing")), None);
+    }
+
+    #[test]
+    fn sniffs_files_with_unknown_extensions() {
+        let path = std::env::temp_dir().join(format!("aws_apis-{}-sniff.bin", std::process::id()));
+        fs::write(&path, b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR").unwrap();
+        assert_eq!(detect_content_type(&path), Some("image/png"));
+        fs::write(&path, b"plain text").unwrap();
+        assert_eq!(detect_content_type(&path), None);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn recognizes_magic_bytes() {
+        assert_eq!(sniff(b"\x89PNG\r\n\x1a\n\0\0"), Some("image/png"));
+        assert_eq!(sniff(b"RIFF\x24\0\0\0WEBPVP8 "), Some("image/webp"));
+        assert_eq!(sniff(b"RIFF\x24\0\0\0WAVEfmt "), Some("audio/wav"));
+        assert_eq!(sniff(b"RIFF\x24\0\0\0AVI LIST"), None);
+        assert_eq!(sniff(b"\0\0\0\x20ftypisom"), Some("video/mp4"));
+        //Shorter than the magic
+        assert_eq!(sniff(b"\x89PN"), None);
+        assert_eq!(sniff(b"RIFF\x24\0\0\0WE"), None);
+        assert_eq!(sniff(b"\0\0\0\x20fty"), None);
+        assert_eq!(sniff(b""), None);
+    }
+}
@@ -1,68 +1,508 @@
+use crate::{
+    aws_polly::PollyOps,
+    dynamodb::DynamoDbOps,
+    endpoints::Endpoints,
+    error::{Error, SdkResultExt, Service},
+    memorydb_ops::MemDbOps,
+    output::{report, OutputSink},
+    pinpoint_ops::PinPointOps,
+    rds_ops::RdsOps,
+    rekognition_ops::RekognitionOps,
+    route53::Route53Ops,
+    s3_ops::S3Ops,
+    sesv2_ops::SesOps,
+    sns_ops::SnsOps,
+    transcribe_ops::TranscribeOps,
+    translate_ops::TranslateOps,
+};
+use aws_config::{
+    credential_process::CredentialProcessProvider,
+    meta::region::ProvideRegion,
+    profile::{ProfileFileCredentialsProvider, ProfileFileRegionProvider},
+    sts::AssumeRoleProvider,
+};
 pub use aws_config::{load_from_env, SdkConfig};
 use aws_credential_types::{
-    provider::{ProvideCredentials, SharedCredentialsProvider},
+    cache::CredentialsCache,
+    provider::{
+        self, error::CredentialsError, future, ProvideCredentials, SharedCredentialsProvider,
+    },
     Credentials,
 };
 use aws_types::region::Region;
 use colored::Colorize;
 use dotenv::dotenv;
-use std::env::var;
+use std::{
+    env::var,
+    fmt,
+    sync::Mutex,
+    time::{Duration, SystemTime},
+};
+use zeroize::Zeroizing;
 
-#[derive(Debug, Default)]
+/// Where [`CredentInitialize`] takes its credentials from.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum CredentialSource {
+    /// The access key and secret given to [`CredentInitialize::update`], together with the
+    /// session token of [`CredentInitialize::session_token`] when there is one.
+    #[default]
+    Static,
+    /// A named profile of `~/.aws/config` and `~/.aws/credentials`. SSO profiles, profiles
+    /// chaining `role_arn` and `source_profile`, and `credential_process` profiles all work.
+    Profile(String),
+    /// The JSON an external command prints, the same way `credential_process` works in a
+    /// profile.
+    CredentialProcess(String),
+}
+
+impl fmt::Display for CredentialSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CredentialSource::Static => f.write_str("static access keys"),
+            CredentialSource::Profile(name) => write!(f, "the profile '{name}'"),
+            //Only the program is shown, since the arguments may contain secrets
+            CredentialSource::CredentialProcess(command) => write!(
+                f,
+                "the credential process '{}'",
+                command.split_whitespace().next().unwrap_or_default()
+            ),
+        }
+    }
+}
+
+/// A role to assume with the credentials of the [`CredentialSource`], given to
+/// [`CredentInitialize::assume_role`].
+#[derive(Clone)]
+pub struct AssumeRole {
+    role_arn: String,
+    session_name: String,
+    external_id: Option<String>,
+    mfa: Option<(String, Zeroizing<String>)>,
+    duration: Option<Duration>,
+}
+
+impl fmt::Debug for AssumeRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AssumeRole")
+            .field("role_arn", &self.role_arn)
+            .field("session_name", &self.session_name)
+            .field("external_id", &self.external_id)
+            .field(
+                "mfa_serial_number",
+                &self.mfa.as_ref().map(|(serial_number, _)| serial_number),
+            )
+            .field("duration", &self.duration)
+            .finish()
+    }
+}
+
+impl AssumeRole {
+    pub fn new(role_arn: impl Into<String>) -> Self {
+        Self {
+            role_arn: role_arn.into(),
+            session_name: "aws_apis".into(),
+            external_id: None,
+            mfa: None,
+            duration: None,
+        }
+    }
+    pub fn session_name(mut self, session_name: impl Into<String>) -> Self {
+        self.session_name = session_name.into();
+        self
+    }
+    /// The external ID the role's trust policy asks for, which third parties are usually
+    /// given.
+    pub fn external_id(mut self, external_id: impl Into<String>) -> Self {
+        self.external_id = Some(external_id.into());
+        self
+    }
+    /// Assumes the role with the serial number (or ARN) of an MFA device and its current
+    /// code. A code can only be used once, so the session isn't refreshed when it expires;
+    /// build the config again with a new code instead.
+    pub fn mfa(mut self, serial_number: impl Into<String>, token_code: impl Into<String>) -> Self {
+        self.mfa = Some((serial_number.into(), Zeroizing::new(token_code.into())));
+        self
+    }
+    /// How long each role session lasts; STS uses one hour unless told otherwise.
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.duration = Some(duration);
+        self
+    }
+    pub fn role_arn(&self) -> &str {
+        &self.role_arn
+    }
+    fn provider(
+        &self,
+        base: SharedCredentialsProvider,
+        region: Region,
+    ) -> SharedCredentialsProvider {
+        match &self.mfa {
+            Some((serial_number, token_code)) => SharedCredentialsProvider::new(MfaRoleProvider {
+                role: self.clone(),
+                serial_number: serial_number.clone(),
+                token_code: token_code.clone(),
+                sts: aws_sdk_sts::Client::new(
+                    &SdkConfig::builder()
+                        .credentials_provider(base)
+                        .region(Some(region))
+                        .build(),
+                ),
+                session: Mutex::new(None),
+            }),
+            None => {
+                let mut builder = AssumeRoleProvider::builder(&self.role_arn)
+                    .session_name(&self.session_name)
+                    .region(region);
+                if let Some(external_id) = &self.external_id {
+                    builder = builder.external_id(external_id);
+                }
+                if let Some(duration) = self.duration {
+                    builder = builder.session_length(duration);
+                }
+                SharedCredentialsProvider::new(builder.build(base))
+            }
+        }
+    }
+}
+
+/// Assumes a role with a one-time MFA code and hands out that session until it expires,
+/// after which it reports the expiry rather than reusing the code.
+struct MfaRoleProvider {
+    role: AssumeRole,
+    serial_number: String,
+    token_code: Zeroizing<String>,
+    sts: aws_sdk_sts::Client,
+    session: Mutex<Option<Credentials>>,
+}
+
+impl fmt::Debug for MfaRoleProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MfaRoleProvider")
+            .field("role", &self.role)
+            .finish_non_exhaustive()
+    }
+}
+
+impl MfaRoleProvider {
+    async fn credentials(&self) -> provider::Result {
+        let session = self
+            .session
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone();
+        if let Some(credentials) = session {
+            return match credentials.expiry() {
+                Some(expiry) if expiry <= SystemTime::now() => {
+                    Err(CredentialsError::provider_error(format!(
+                        "the MFA session of the role '{}' has expired; assume it again with a new MFA code",
+                        self.role.role_arn
+                    )))
+                }
+                _ => Ok(credentials),
+            };
+        }
+        let output = self
+            .sts
+            .assume_role()
+            .role_arn(&self.role.role_arn)
+            .role_session_name(&self.role.session_name)
+            .set_external_id(self.role.external_id.clone())
+            .serial_number(&self.serial_number)
+            .token_code(self.token_code.as_str())
+            .set_duration_seconds(
+                self.role
+                    .duration
+                    .map(|duration| duration.as_secs().min(i32::MAX as u64) as i32),
+            )
+            .send()
+            .await
+            .map_sdk_err(Service::Sts, "assume_role")
+            .map_err(CredentialsError::provider_error)?;
+        let role_credentials = output.credentials.ok_or_else(|| {
+            CredentialsError::provider_error(Error::missing(
+                Service::Sts,
+                "assume_role",
+                "credentials",
+            ))
+        })?;
+        let credentials = Credentials::new(
+            role_credentials.access_key_id.unwrap_or_default(),
+            role_credentials.secret_access_key.unwrap_or_default(),
+            role_credentials.session_token,
+            role_credentials
+                .expiration
+                .and_then(|expiration| SystemTime::try_from(expiration).ok()),
+            "AssumeRoleWithMfa",
+        );
+        *self
+            .session
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(credentials.clone());
+        Ok(credentials)
+    }
+}
+
+impl ProvideCredentials for MfaRoleProvider {
+    fn provide_credentials<'a>(&'a self) -> future::ProvideCredentials<'a>
+    where
+        Self: 'a,
+    {
+        future::ProvideCredentials::new(self.credentials())
+    }
+}
+
+/// The secret access key and session token are zeroed when dropped or replaced, and neither
+/// `Debug` nor `Display` shows them; the access key ID only appears masked. Read them back
+/// with [`reveal_secret_key`](Self::reveal_secret_key) and
+/// [`reveal_session_token`](Self::reveal_session_token).
+#[derive(Default)]
 pub struct CredentInitialize {
-    access_id: Option<String>,
-    secret_key: Option<String>,
+    access_id: Option<Zeroizing<String>>,
+    secret_key: Option<Zeroizing<String>>,
+    session_token: Option<Zeroizing<String>>,
+    expiry: Option<SystemTime>,
     region: Option<String>,
     provider_name: Option<String>,
+    source: CredentialSource,
+    assume_role: Option<AssumeRole>,
+    endpoints: Endpoints,
+}
+impl fmt::Debug for CredentInitialize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let redacted = |secret: &Option<Zeroizing<String>>| secret.as_ref().map(|_| "<redacted>");
+        f.debug_struct("CredentInitialize")
+            .field("access_id", &self.access_id.as_deref().map(|id| mask(id)))
+            .field("secret_key", &redacted(&self.secret_key))
+            .field("session_token", &redacted(&self.session_token))
+            .field("expiry", &self.expiry)
+            .field("region", &self.region)
+            .field("provider_name", &self.provider_name)
+            .field("source", &self.source)
+            .field("assume_role", &self.assume_role)
+            .field("endpoints", &self.endpoints)
+            .finish()
+    }
+}
+
+impl fmt::Display for CredentInitialize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "credentials from {}", self.describe_source())?;
+        if let Some(access_id) = self.access_id.as_deref() {
+            write!(f, ", access key {}", mask(access_id))?;
+        }
+        if let Some(region) = self.region.as_deref() {
+            write!(f, ", region {region}")?;
+        }
+        Ok(())
+    }
 }
+
+/// Keeps only the last four characters of an access key ID, which is enough to tell keys
+/// apart in the IAM console.
+fn mask(access_id: &str) -> String {
+    let length = access_id.chars().count();
+    if length <= 4 {
+        return "****".into();
+    }
+    let tail: String = access_id.chars().skip(length - 4).collect();
+    format!("****{tail}")
+}
+
 ///You can initialize the credentials inside the 'update' function if you don't want to configure
 /// them later.
 impl CredentInitialize {
     pub fn update(&mut self, access_id: &str, secret_key: &str, region: Option<&str>) {
-        self.access_id = Some(access_id.into());
-        self.secret_key = Some(secret_key.into());
+        self.access_id = Some(Zeroizing::new(access_id.into()));
+        self.secret_key = Some(Zeroizing::new(secret_key.into()));
         self.region = match region {
             Some(region) => Some(region.to_string()),
             None => Some(self.get_region_name()),
         };
         self.provider_name = Some("aws".into());
+        self.source = CredentialSource::Static;
+    }
+    /// Adds the session token of temporary credentials, such as the ones STS or an SSO
+    /// portal hands out, to the keys given to [`update`](Self::update), along with when they
+    /// expire.
+    pub fn session_token(&mut self, session_token: &str, expires_at: Option<SystemTime>) {
+        self.session_token = Some(Zeroizing::new(session_token.into()));
+        self.expiry = expires_at;
+    }
+    /// Takes the credentials from a named profile, and the region too unless one is given.
+    /// Only when the profile has no region does it fall back to the `REGION` environment
+    /// variable, as with [`update`](Self::update).
+    pub async fn profile(&mut self, profile_name: &str, region: Option<&str>) {
+        let region = match region {
+            Some(region) => Some(region.to_string()),
+            None => {
+                let provider = ProfileFileRegionProvider::builder()
+                    .profile_name(profile_name)
+                    .build();
+                ProvideRegion::region(&provider)
+                    .await
+                    .map(|region| region.to_string())
+            }
+        };
+        self.region = Some(region.unwrap_or_else(|| self.get_region_name()));
+        self.source = CredentialSource::Profile(profile_name.into());
+    }
+    /// Takes the credentials from the output of an external command, which must print the
+    /// JSON format described for `credential_process` in the AWS CLI documentation.
+    pub fn credential_process(&mut self, command: &str, region: Option<&str>) {
+        self.region = match region {
+            Some(region) => Some(region.to_string()),
+            None => Some(self.get_region_name()),
+        };
+        self.source = CredentialSource::CredentialProcess(command.into());
+    }
+    /// Uses the credentials of the source only to assume the given role.
+    pub fn assume_role(&mut self, role: AssumeRole) {
+        self.assume_role = Some(role);
+    }
+    /// The secret access key given to [`update`](Self::update). Nothing else in this type
+    /// exposes it, so every place the secret leaves the struct goes through here.
+    pub fn reveal_secret_key(&self) -> Option<&str> {
+        self.secret_key.as_ref().map(|secret| secret.as_str())
+    }
+    /// The session token given to [`session_token`](Self::session_token), unmasked.
+    pub fn reveal_session_token(&self) -> Option<&str> {
+        self.session_token.as_ref().map(|token| token.as_str())
+    }
+    pub fn source(&self) -> &CredentialSource {
+        &self.source
+    }
+    /// Describes where the credentials come from, for example "the role 'arn:...' assumed
+    /// with the profile 'dev'".
+    pub fn describe_source(&self) -> String {
+        match &self.assume_role {
+            Some(role) => format!("the role '{}' assumed with {}", role.role_arn, self.source),
+            None => self.source.to_string(),
+        }
     }
 
+    /// The configuration of every service, sent to the global endpoint if one is set. The
+    /// constructors below, such as [`s3_ops`](Self::s3_ops), also honour the endpoints set
+    /// for single services.
     pub fn credential(&mut self) -> SdkConfig {
-        let access_id = self.access_id.clone().take().unwrap_or_default();
-
-        let secret_key = self.secret_key.clone().take().unwrap_or_default();
-
-        let credential = Credentials::new(
-            access_id.to_owned(),
-            secret_key.to_owned(),
-            None,
-            None,
-            "aws",
-        );
-        let shared = SharedCredentialsProvider::new(credential);
-        let region = self.region.clone().take().unwrap_or_default();
-        let region = Region::new(region);
-        SdkConfig::builder()
-            .credentials_provider(shared)
-            .region(Some(region))
-            .build()
+        self.sdk_config(self.endpoints.global_url())
     }
     pub fn build(&mut self) -> SdkConfig {
         self.credential()
     }
-    pub fn print_credentials(&self) {
+    /// Like [`build`](Self::build), but uses the endpoint set for the given service, if any,
+    /// in place of the global one.
+    pub fn build_for(&mut self, service: Service) -> SdkConfig {
+        self.sdk_config(self.endpoints.url_for(service))
+    }
+    /// An [`S3Ops`] sent to the S3 endpoint, addressing buckets by path when
+    /// [`Endpoints::s3_path_style`] is on.
+    pub fn s3_ops(&mut self) -> S3Ops {
+        let path_style = self.endpoints.is_s3_path_style();
+        S3Ops::build(&self.build_for(Service::S3)).with_path_style(path_style)
+    }
+    pub fn ses_ops(&mut self) -> SesOps {
+        SesOps::build(&self.build_for(Service::SesV2))
+    }
+    pub fn rds_ops(&mut self) -> RdsOps {
+        RdsOps::build(&self.build_for(Service::Rds))
+    }
+    pub fn memorydb_ops(&mut self) -> MemDbOps {
+        MemDbOps::build(&self.build_for(Service::MemoryDb))
+    }
+    pub fn polly_ops(&mut self) -> PollyOps {
+        PollyOps::build(&self.build_for(Service::Polly))
+    }
+    pub fn rekognition_ops(&mut self) -> RekognitionOps {
+        RekognitionOps::build(&self.build_for(Service::Rekognition))
+    }
+    pub fn transcribe_ops(&mut self) -> TranscribeOps {
+        TranscribeOps::build(&self.build_for(Service::Transcribe))
+    }
+    pub fn translate_ops(&mut self) -> TranslateOps {
+        TranslateOps::build(&self.build_for(Service::Translate))
+    }
+    pub fn sns_ops(&mut self) -> SnsOps {
+        SnsOps::build(&self.build_for(Service::Sns))
+    }
+    pub fn pinpoint_ops(&mut self) -> PinPointOps {
+        PinPointOps::build(&self.build_for(Service::Pinpoint))
+    }
+    pub fn dynamodb_ops(&mut self) -> DynamoDbOps {
+        DynamoDbOps::build(&self.build_for(Service::DynamoDb))
+    }
+    pub fn route53_ops(&mut self) -> Route53Ops {
+        Route53Ops::build(&self.build_for(Service::Route53))
+    }
+    /// Points the clients at other endpoints, such as LocalStack or MinIO. The URLs are
+    /// checked here, so a plain `http://` URL for a remote host is rejected up front.
+    pub fn set_endpoints(&mut self, endpoints: Endpoints) -> Result<(), Error> {
+        endpoints.validate()?;
+        self.endpoints = endpoints;
+        Ok(())
+    }
+    pub fn endpoints(&self) -> &Endpoints {
+        &self.endpoints
+    }
+    fn sdk_config(&self, endpoint_url: Option<&str>) -> SdkConfig {
+        let region = self.region.clone().unwrap_or_default();
+        let region = Region::new(region);
+        let shared = match &self.assume_role {
+            Some(role) => role.provider(self.source_provider(), region.clone()),
+            None => self.source_provider(),
+        };
+        //The lazy cache loads credentials on first use and again shortly before they expire
+        let mut config = SdkConfig::builder()
+            .credentials_provider(shared)
+            .credentials_cache(CredentialsCache::lazy())
+            .region(Some(region));
+        config.set_endpoint_url(endpoint_url.map(|url| url.to_string()));
+        config.build()
+    }
+    fn source_provider(&self) -> SharedCredentialsProvider {
+        match &self.source {
+            CredentialSource::Static => {
+                //Credentials zeroes its own copies when dropped
+                let credential = Credentials::new(
+                    self.access_id
+                        .as_deref()
+                        .map(String::as_str)
+                        .unwrap_or_default(),
+                    self.reveal_secret_key().unwrap_or_default(),
+                    self.reveal_session_token().map(str::to_string),
+                    self.expiry,
+                    "aws",
+                );
+                SharedCredentialsProvider::new(credential)
+            }
+            CredentialSource::Profile(profile_name) => SharedCredentialsProvider::new(
+                ProfileFileCredentialsProvider::builder()
+                    .profile_name(profile_name)
+                    .build(),
+            ),
+            CredentialSource::CredentialProcess(command) => {
+                SharedCredentialsProvider::new(CredentialProcessProvider::new(command.clone()))
+            }
+        }
+    }
+    pub fn print_credentials(&self, sink: &dyn OutputSink) {
+        report!(
+            sink,
+            "Credential Source: {}",
+            self.describe_source().green().bold()
+        );
         match (self.access_id.as_deref(), self.secret_key.as_deref()) {
-            (Some(access_id), Some(secret_key)) => {
-                println!("Access Key Id: {}", access_id.green().bold());
-                println!("Secret AccessKey Id: {}", secret_key.green().bold());
+            (Some(access_id), Some(_)) => {
+                report!(sink, "Access Key Id: {}", mask(access_id).green().bold());
+                report!(sink, "Secret Access Key: {}", "<hidden>".green().bold());
             }
             _ => {
-                println!(
+                report!(
+                    sink,
                     "{}",
                     "No Access key and Secret Access Key found".red().bold()
                 );
-                println!(
+                report!(
+                    sink,
                     "{}\n",
                     "Please try the 'Verify Credentials' option first"
                         .yellow()
@@ -71,10 +511,10 @@ impl CredentInitialize {
             }
         }
         if let Some(region) = self.region.as_deref() {
-            println!("Region: {}\n", region.green().bold());
+            report!(sink, "Region: {}\n", region.green().bold());
         } else {
-            println!("{}", "No region is found".red().bold());
-            println!("{}\n","Please try the 'Verify Credentials' option first, and ensure that the credentials include the 'region' environment variable".yellow().bold());
+            report!(sink, "{}", "No region is found".red().bold());
+            report!(sink, "{}\n","Please try the 'Verify Credentials' option first, and ensure that the credentials include the 'region' environment variable".yellow().bold());
         }
     }
     pub fn get_region_name(&self) -> String {
@@ -86,26 +526,47 @@ impl CredentInitialize {
         self.secret_key = None;
         self.region = None;
         self.provider_name = None;
+        self.session_token = None;
+        self.expiry = None;
+        self.source = CredentialSource::Static;
+        self.assume_role = None;
+        self.endpoints = Endpoints::default();
     }
 }
 
 /// Returns the [`Credentials`](https://docs.rs/aws-credential-types/0.56.1/aws_credential_types/struct.Credentials.html?search=sdkconfig#) types to retrieve access_id and secret_key, as well as
 /// the region name, from the configuration.
-pub async fn load_credential_from_env() -> (Credentials, Option<String>) {
-    println!("{}\n",r#"The configuration path is "$HOME/.aws/credentials" on Linux and macOS, and "%USER_PROFILE%/.aws/credentials" on Windows"#.green().bold());
+pub async fn load_credential_from_env(
+    sink: &dyn OutputSink,
+) -> Result<(Credentials, Option<String>), Error> {
+    report!(sink, "{}\n",r#"The configuration path is "$HOME/.aws/credentials" on Linux and macOS, and "%USER_PROFILE%/.aws/credentials" on Windows"#.green().bold());
 
-    println!("Attempting to retrieve credentials from the configuration file\n");
+    report!(
+        sink,
+        "Attempting to retrieve credentials from the configuration file\n"
+    );
 
     let config = aws_config::load_from_env().await;
-    let shared_credential = config.credentials_provider().unwrap();
-    let credentials = shared_credential.provide_credentials().await.unwrap();
-    println!(
+    let shared_credential = config.credentials_provider().ok_or(Error::other(
+        "load_credential_from_env",
+        "no credentials provider was found in the environment",
+    ))?;
+    let credentials = shared_credential
+        .provide_credentials()
+        .await
+        .map_err(|err| Error::other("load_credential_from_env", err))?;
+    report!(
+        sink,
+        "Credential Source: {}\n",
+        "the default chain (environment, profile, SSO or instance metadata)"
+            .green()
+            .bold()
+    );
+    report!(
+    sink,
     "{}\n",
     "The region value is read from the .env file in the current directory if it is not provided in the credential file".blue().bold());
-    let region = match config.region() {
-        Some(region) => Some(region.to_string()),
-        None => None,
-    };
+    let region = config.region().map(|region| region.to_string());
 
-    (credentials, region)
+    Ok((credentials, region))
 }
@@ -1,3 +1,7 @@
+use crate::{
+    error::{Error, SdkResultExt, Service},
+    output::{report, OutputSink, TerminalSink},
+};
 use aws_config::SdkConfig;
 use aws_sdk_dynamodb::{
     types::{
@@ -7,29 +11,37 @@ use aws_sdk_dynamodb::{
 };
 use colored::Colorize;
 use serde_json::Value;
-use std::{fs::File, io::BufReader};
-pub struct DynamoDbOps<'a> {
-    config: &'a SdkConfig,
+use std::{fs::File, io::BufReader, sync::Arc};
+#[derive(Debug, Clone)]
+pub struct DynamoDbOps {
+    client: DynClient,
+    sink: Arc<dyn OutputSink>,
 }
-impl<'a> DynamoDbOps<'a> {
-    pub fn build(config: &'a SdkConfig) -> Self {
-        Self { config }
+impl DynamoDbOps {
+    pub fn build(config: &SdkConfig) -> Self {
+        Self {
+            client: DynClient::new(config),
+            sink: Arc::new(TerminalSink),
+        }
+    }
+    pub fn with_sink(mut self, sink: Arc<dyn OutputSink>) -> Self {
+        self.sink = sink;
+        self
     }
     pub async fn create_table(
         &self,
         table_name: &str,
         attribute_definition_json_path: &str,
         key_schema_json_path: &str,
-    ) {
-        let client = DynClient::new(self.config);
-
-        let attribute_definitions = parse_attribute_defintion_json(attribute_definition_json_path);
-        let key_schema_defintions = parse_key_schema_defintion_json(key_schema_json_path);
+    ) -> Result<(), Error> {
+        let attribute_definitions = parse_attribute_defintion_json(attribute_definition_json_path)?;
+        let key_schema_defintions = parse_key_schema_defintion_json(key_schema_json_path)?;
         let throughput = ProvisionedThroughput::builder()
             .read_capacity_units(10)
             .write_capacity_units(10)
             .build();
-        let outputs = client
+        let outputs = self
+            .client
             .create_table()
             .table_name(table_name)
             .set_attribute_definitions(Some(attribute_definitions))
@@ -37,38 +49,45 @@ impl<'a> DynamoDbOps<'a> {
             .provisioned_throughput(throughput)
             .send()
             .await
-            .expect("Error while creating DynamoDB Table\n");
+            .map_sdk_err(Service::DynamoDb, "create_table")?;
         if let Some(table_description) = outputs.table_description {
             if let Some(time) = table_description.creation_date_time {
                 let convert = time
                     .fmt(aws_sdk_dynamodb::primitives::DateTimeFormat::HttpDate)
                     .ok();
                 if let Some(data_time) = convert {
-                    println!("Creation Time Of the Table: {}", data_time.green().bold());
+                    report!(
+                        self.sink,
+                        "Creation Time Of the Table: {}",
+                        data_time.green().bold()
+                    );
                 }
             }
             if let Some(table_status) = table_description.table_status {
                 let convert_to_str = table_status.as_str();
-                println!(
+                report!(
+                    self.sink,
                     "Current Status of '{}' table: {}",
                     table_name.green().bold(),
                     convert_to_str.green().bold()
                 );
             }
             if let Some(table_size) = table_description.table_size_bytes {
-                println!("Table Size In Bytes: {}", table_size);
+                report!(self.sink, "Table Size In Bytes: {}", table_size);
             }
             if let Some(table_id) = table_description.table_id {
-                println!("Table Identifier: {}", table_id.green().bold());
+                report!(self.sink, "Table Identifier: {}", table_id.green().bold());
             }
             if let Some(table_arn) = table_description.table_arn {
-                println!(
+                report!(
+                    self.sink,
                     "Amazon Resource Name(ARN) for the Table: {}",
                     table_arn.green().bold()
                 );
             }
             if let Some(item_counts) = table_description.item_count {
-                println!(
+                report!(
+                    self.sink,
                     "Number of Items in the Table: {}",
                     item_counts.to_string().green().bold()
                 );
@@ -78,17 +97,22 @@ impl<'a> DynamoDbOps<'a> {
                     "whether deletion protection is enabled: {}",
                     delete_protection
                 );
-                println!("{}\n", format);
+                report!(self.sink, "{}\n", format);
             }
         }
+        Ok(())
     }
 }
 /// ['AttributeDefinition`](https://docs.aws.amazon.com/amazondynamodb/latest/APIReference/API_AttributeDefinition.html)
-fn parse_attribute_defintion_json(json_path: &str) -> Vec<AttributeDefinition> {
-    let file = File::open(json_path).expect("Error while Opening the Json path you specified\n");
+fn parse_attribute_defintion_json(json_path: &str) -> Result<Vec<AttributeDefinition>, Error> {
+    let file = File::open(json_path).map_err(|err| Error::io("create_table", json_path, err))?;
     let buffer = BufReader::new(file);
-    let load_json_data: Value =
-        serde_json::from_reader(buffer).expect("Error Parsing the Json File\n");
+    let load_json_data: Value = serde_json::from_reader(buffer).map_err(|err| {
+        Error::invalid_input(
+            "create_table",
+            format!("{json_path} is not valid JSON: {err}"),
+        )
+    })?;
     let read_json = load_json_data.as_object();
     let mut vec_attribute_definitions = Vec::new();
     if let Some(json_data) = read_json {
@@ -106,14 +130,18 @@ fn parse_attribute_defintion_json(json_path: &str) -> Vec<AttributeDefinition> {
             }
         }
     }
-    vec_attribute_definitions
+    Ok(vec_attribute_definitions)
 }
 
-fn parse_key_schema_defintion_json(json_path: &str) -> Vec<KeySchemaElement> {
-    let file = File::open(json_path)
-        .expect("Error while opening key schema json the path you specified\n");
+fn parse_key_schema_defintion_json(json_path: &str) -> Result<Vec<KeySchemaElement>, Error> {
+    let file = File::open(json_path).map_err(|err| Error::io("create_table", json_path, err))?;
     let buffer = BufReader::new(file);
-    let load_json_data: Value = serde_json::from_reader(buffer).expect("Error opening json File\n");
+    let load_json_data: Value = serde_json::from_reader(buffer).map_err(|err| {
+        Error::invalid_input(
+            "create_table",
+            format!("{json_path} is not valid JSON: {err}"),
+        )
+    })?;
     let read_json = load_json_data.as_object();
     let mut vec_key_schema_definitions = Vec::new();
     if let Some(json_data) = read_json {
@@ -131,5 +159,5 @@ fn parse_key_schema_defintion_json(json_path: &str) -> Vec<KeySchemaElement> {
             }
         }
     }
-    vec_key_schema_definitions
+    Ok(vec_key_schema_definitions)
 }
@@ -0,0 +1,103 @@
+use std::{collections::HashMap, net::IpAddr};
+
+use crate::error::{Error, Service};
+
+/// Endpoint overrides for running the crate against local stand-ins such as LocalStack,
+/// MinIO or DynamoDB Local instead of AWS. Hand it to
+/// [`CredentInitialize::set_endpoints`](crate::CredentInitialize::set_endpoints), then build
+/// the service clients with constructors such as
+/// [`CredentInitialize::s3_ops`](crate::CredentInitialize::s3_ops), which apply every setting.
+///
+/// URLs must use `https://`. Plain `http://` is accepted only for loopback hosts, and only
+/// once [`allow_http_for_localhost`](Endpoints::allow_http_for_localhost) is turned on.
+#[derive(Debug, Clone, Default)]
+pub struct Endpoints {
+    global: Option<String>,
+    services: HashMap<Service, String>,
+    s3_path_style: bool,
+    http_for_localhost: bool,
+}
+
+impl Endpoints {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Sends the requests of every service to this URL, unless the service has its own.
+    pub fn endpoint_url(mut self, url: impl Into<String>) -> Self {
+        self.global = Some(url.into());
+        self
+    }
+    /// Sends the requests of one service to this URL, for example DynamoDB Local on
+    /// `http://localhost:8000` while everything else goes to LocalStack.
+    pub fn service_endpoint_url(mut self, service: Service, url: impl Into<String>) -> Self {
+        self.services.insert(service, url.into());
+        self
+    }
+    /// Addresses buckets as `endpoint/bucket/key` instead of `bucket.endpoint/key`, which
+    /// MinIO and LocalStack expect.
+    pub fn s3_path_style(mut self, enabled: bool) -> Self {
+        self.s3_path_style = enabled;
+        self
+    }
+    /// Accepts `http://` URLs whose host is `localhost` or a loopback address.
+    pub fn allow_http_for_localhost(mut self, allowed: bool) -> Self {
+        self.http_for_localhost = allowed;
+        self
+    }
+    /// The URL the given service should use, falling back to the global one.
+    pub fn url_for(&self, service: Service) -> Option<&str> {
+        self.services
+            .get(&service)
+            .or(self.global.as_ref())
+            .map(|url| url.as_str())
+    }
+    pub fn global_url(&self) -> Option<&str> {
+        self.global.as_deref()
+    }
+    pub fn is_s3_path_style(&self) -> bool {
+        self.s3_path_style
+    }
+    /// Checks every configured URL against the rules above.
+    pub fn validate(&self) -> Result<(), Error> {
+        self.global
+            .iter()
+            .chain(self.services.values())
+            .try_for_each(|url| check_url(url, self.http_for_localhost))
+    }
+}
+
+fn check_url(url: &str, http_for_localhost: bool) -> Result<(), Error> {
+    let invalid = |message: String| Error::invalid_input("set_endpoints", message);
+    let (scheme, rest) = url
+        .split_once("://")
+        .ok_or_else(|| invalid(format!("'{url}' has no scheme such as https://")))?;
+    //The host ends where the port, path or query begins; IPv6 hosts are bracketed
+    let host = match rest.strip_prefix('[') {
+        Some(bracketed) => bracketed.split(']').next().unwrap_or_default(),
+        None => rest.split([':', '/', '?']).next().unwrap_or_default(),
+    };
+    if host.is_empty() {
+        return Err(invalid(format!("'{url}' has no host")));
+    }
+    match scheme.to_ascii_lowercase().as_str() {
+        "https" => Ok(()),
+        "http" if is_loopback(host) && http_for_localhost => Ok(()),
+        "http" if is_loopback(host) => Err(invalid(format!(
+            "'{url}' doesn't use TLS; call allow_http_for_localhost(true) to permit it"
+        ))),
+        "http" => Err(invalid(format!(
+            "'{url}' doesn't use TLS, which is only allowed for localhost"
+        ))),
+        _ => Err(invalid(format!("'{url}' must use https:// or http://"))),
+    }
+}
+
+fn is_loopback(host: &str) -> bool {
+    let host = host.to_ascii_lowercase();
+    host == "localhost"
+        || host.ends_with(".localhost")
+        || host
+            .parse::<IpAddr>()
+            .map(|ip| ip.is_loopback())
+            .unwrap_or(false)
+}
@@ -0,0 +1,363 @@
+use std::collections::HashMap;
+
+use aws_smithy_types::base64;
+use futures_util::future::BoxFuture;
+use ring::{
+    aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN},
+    rand::{SecureRandom, SystemRandom},
+};
+use zeroize::Zeroizing;
+
+use crate::error::Error;
+
+//Objects are sealed in chunks of this many plaintext bytes, each followed by its GCM tag
+pub(crate) const CHUNK_SIZE: u64 = 64 * 1024;
+const TAG_LEN: u64 = 16;
+pub(crate) const SEALED_CHUNK_SIZE: u64 = CHUNK_SIZE + TAG_LEN;
+
+const CIPHER: &str = "AES256-GCM-CHUNKED";
+//S3 stores these as x-amz-meta-* headers, which it lowercases
+const CIPHER_KEY: &str = "envelope-cipher";
+const CHUNK_SIZE_KEY: &str = "envelope-chunk-size";
+const WRAP_ALGORITHM_KEY: &str = "envelope-wrap-algorithm";
+const WRAPPED_KEY_KEY: &str = "envelope-wrapped-key";
+const IV_KEY: &str = "envelope-iv";
+
+/// Protects the data keys of objects uploaded with
+/// [`S3Ops::upload_encrypted`](crate::S3Ops::upload_encrypted). The wrapped key is stored in
+/// the object's metadata next to the ciphertext, so whoever can unwrap it can read the object.
+///
+/// A KMS-backed wrapper calls `Encrypt` with its key ID in [`wrap_key`](Self::wrap_key) and
+/// `Decrypt` in [`unwrap_key`](Self::unwrap_key); the ciphertext blob names the key itself.
+/// [`LocalKeyWrapper`] keeps the key in memory, which suits tests and offline tools.
+pub trait KeyWrapper: Send + Sync {
+    /// A name for the wrapping scheme, stored with the object and checked before a key is
+    /// unwrapped, such as `kms` or `AES256-GCM-local`.
+    fn algorithm(&self) -> &str;
+    fn wrap_key<'a>(&'a self, data_key: &'a [u8]) -> BoxFuture<'a, Result<Vec<u8>, Error>>;
+    fn unwrap_key<'a>(
+        &'a self,
+        wrapped_key: &'a [u8],
+    ) -> BoxFuture<'a, Result<Zeroizing<Vec<u8>>, Error>>;
+}
+
+/// Wraps data keys with AES-256-GCM under a 256-bit key held in memory. The key is wiped
+/// when dropped and never printed.
+#[derive(Clone)]
+pub struct LocalKeyWrapper {
+    key: Zeroizing<[u8; 32]>,
+}
+
+impl LocalKeyWrapper {
+    pub fn new(key: [u8; 32]) -> Self {
+        Self {
+            key: Zeroizing::new(key),
+        }
+    }
+    /// A wrapper with a fresh random key, which is lost along with every object it wrapped
+    /// once the wrapper is dropped.
+    pub fn generate() -> Result<Self, Error> {
+        let mut key = [0; 32];
+        random_bytes(&mut key)?;
+        let wrapper = Self::new(key);
+        zeroize::Zeroize::zeroize(&mut key);
+        Ok(wrapper)
+    }
+    fn sealing_key(&self) -> Result<LessSafeKey, Error> {
+        UnboundKey::new(&AES_256_GCM, self.key.as_slice())
+            .map(LessSafeKey::new)
+            .map_err(|_| Error::invalid_input("LocalKeyWrapper", "the key must have 32 bytes"))
+    }
+}
+
+impl std::fmt::Debug for LocalKeyWrapper {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("LocalKeyWrapper(<redacted>)")
+    }
+}
+
+impl KeyWrapper for LocalKeyWrapper {
+    fn algorithm(&self) -> &str {
+        "AES256-GCM-local"
+    }
+    //The wrapped key is the nonce followed by the sealed data key
+    fn wrap_key<'a>(&'a self, data_key: &'a [u8]) -> BoxFuture<'a, Result<Vec<u8>, Error>> {
+        Box::pin(async move {
+            let mut nonce = [0; NONCE_LEN];
+            random_bytes(&mut nonce)?;
+            let mut sealed = data_key.to_vec();
+            self.sealing_key()?
+                .seal_in_place_append_tag(
+                    Nonce::assume_unique_for_key(nonce),
+                    Aad::empty(),
+                    &mut sealed,
+                )
+                .map_err(|_| Error::other("wrap_key", "the data key couldn't be sealed"))?;
+            let mut wrapped = nonce.to_vec();
+            wrapped.extend(sealed);
+            Ok(wrapped)
+        })
+    }
+    fn unwrap_key<'a>(
+        &'a self,
+        wrapped_key: &'a [u8],
+    ) -> BoxFuture<'a, Result<Zeroizing<Vec<u8>>, Error>> {
+        Box::pin(async move {
+            let invalid = || {
+                Error::other(
+                    "unwrap_key",
+                    "the data key wasn't wrapped with this key or was altered",
+                )
+            };
+            if wrapped_key.len() < NONCE_LEN {
+                return Err(invalid());
+            }
+            let (nonce, sealed) = wrapped_key.split_at(NONCE_LEN);
+            let nonce = Nonce::try_assume_unique_for_key(nonce).map_err(|_| invalid())?;
+            let mut data_key = Zeroizing::new(sealed.to_vec());
+            let length = self
+                .sealing_key()?
+                .open_in_place(nonce, Aad::empty(), &mut data_key)
+                .map_err(|_| invalid())?
+                .len();
+            data_key.truncate(length);
+            Ok(data_key)
+        })
+    }
+}
+
+/// Seals and opens the chunks of one object. Chunk `n` is sealed under the object's IV with
+/// `n` XORed into its last eight bytes, and its index and whether it's the last chunk are
+/// authenticated along with it, so chunks can't be reordered, dropped or cut off at the end
+/// without failing a tag check.
+pub(crate) struct ChunkCipher {
+    key: LessSafeKey,
+    iv: [u8; NONCE_LEN],
+    chunk_count: u64,
+}
+
+impl ChunkCipher {
+    pub(crate) fn for_plaintext(
+        data_key: &[u8],
+        iv: [u8; NONCE_LEN],
+        plaintext_len: u64,
+    ) -> Result<Self, Error> {
+        Self::new(data_key, iv, (plaintext_len + CHUNK_SIZE - 1) / CHUNK_SIZE)
+    }
+    pub(crate) fn for_ciphertext(
+        data_key: &[u8],
+        iv: [u8; NONCE_LEN],
+        ciphertext_len: u64,
+    ) -> Result<Self, Error> {
+        Self::new(
+            data_key,
+            iv,
+            (ciphertext_len + SEALED_CHUNK_SIZE - 1) / SEALED_CHUNK_SIZE,
+        )
+    }
+    fn new(data_key: &[u8], iv: [u8; NONCE_LEN], chunk_count: u64) -> Result<Self, Error> {
+        let key = UnboundKey::new(&AES_256_GCM, data_key).map_err(|_| {
+            Error::invalid_input("envelope encryption", "the data key must have 32 bytes")
+        })?;
+        Ok(Self {
+            key: LessSafeKey::new(key),
+            iv,
+            //An empty object is still one sealed, empty chunk
+            chunk_count: chunk_count.max(1),
+        })
+    }
+    pub(crate) fn chunk_count(&self) -> u64 {
+        self.chunk_count
+    }
+    /// Seals plaintext that starts at chunk `first_chunk`. Every chunk but the object's last
+    /// must be complete.
+    pub(crate) fn seal(&self, first_chunk: u64, plaintext: &[u8]) -> Result<Vec<u8>, Error> {
+        let chunks = match plaintext.is_empty() {
+            true => vec![plaintext],
+            false => plaintext.chunks(CHUNK_SIZE as usize).collect(),
+        };
+        let mut sealed = Vec::with_capacity(plaintext.len() + chunks.len() * TAG_LEN as usize);
+        for (index, chunk) in (first_chunk..).zip(chunks) {
+            if index >= self.chunk_count {
+                return Err(Error::invalid_input(
+                    "envelope encryption",
+                    "the plaintext is longer than the object",
+                ));
+            }
+            let mut buffer = chunk.to_vec();
+            self.key
+                .seal_in_place_append_tag(
+                    self.nonce(index),
+                    Aad::from(self.aad(index)),
+                    &mut buffer,
+                )
+                .map_err(|_| Error::other("envelope encryption", "a chunk couldn't be sealed"))?;
+            sealed.extend(buffer);
+        }
+        Ok(sealed)
+    }
+    /// Opens chunk `index` in place, returning the length of its plaintext, or `None` when
+    /// the tag doesn't match.
+    pub(crate) fn open(&self, index: u64, chunk: &mut [u8]) -> Option<usize> {
+        if index >= self.chunk_count {
+            return None;
+        }
+        self.key
+            .open_in_place(self.nonce(index), Aad::from(self.aad(index)), chunk)
+            .ok()
+            .map(|plaintext| plaintext.len())
+    }
+    fn nonce(&self, index: u64) -> Nonce {
+        let mut nonce = self.iv;
+        for (byte, counter) in nonce[NONCE_LEN - 8..].iter_mut().zip(index.to_be_bytes()) {
+            *byte ^= counter;
+        }
+        Nonce::assume_unique_for_key(nonce)
+    }
+    fn aad(&self, index: u64) -> [u8; 9] {
+        let mut aad = [0; 9];
+        aad[..8].copy_from_slice(&index.to_be_bytes());
+        aad[8] = u8::from(index + 1 == self.chunk_count);
+        aad
+    }
+}
+
+/// The metadata that lets a download find and unwrap the data key.
+pub(crate) fn envelope_metadata(
+    wrap_algorithm: &str,
+    wrapped_key: &[u8],
+    iv: &[u8],
+) -> HashMap<String, String> {
+    HashMap::from([
+        (CIPHER_KEY.to_string(), CIPHER.to_string()),
+        (CHUNK_SIZE_KEY.to_string(), CHUNK_SIZE.to_string()),
+        (WRAP_ALGORITHM_KEY.to_string(), wrap_algorithm.to_string()),
+        (WRAPPED_KEY_KEY.to_string(), base64::encode(wrapped_key)),
+        (IV_KEY.to_string(), base64::encode(iv)),
+    ])
+}
+
+/// The wrapped data key and IV stored by [`envelope_metadata`], after checking that the
+/// object was sealed the way this module seals and with the given wrapping scheme.
+pub(crate) fn read_envelope_metadata(
+    operation: &'static str,
+    metadata: Option<&HashMap<String, String>>,
+    wrap_algorithm: &str,
+) -> Result<(Vec<u8>, [u8; NONCE_LEN]), Error> {
+    let value = |name: &str| metadata.and_then(|metadata| metadata.get(name));
+    if value(CIPHER_KEY).map(|cipher| cipher.as_str()) != Some(CIPHER)
+        || value(CHUNK_SIZE_KEY) != Some(&CHUNK_SIZE.to_string())
+    {
+        return Err(Error::invalid_input(
+            operation,
+            "the object wasn't uploaded with upload_encrypted",
+        ));
+    }
+    let stored_algorithm = value(WRAP_ALGORITHM_KEY).map(|algorithm| algorithm.as_str());
+    if stored_algorithm != Some(wrap_algorithm) {
+        return Err(Error::invalid_input(
+            operation,
+            format!(
+                "the data key was wrapped with '{}', not '{wrap_algorithm}'",
+                stored_algorithm.unwrap_or_default()
+            ),
+        ));
+    }
+    let decode = |name: &str| {
+        value(name)
+            .and_then(|encoded| base64::decode(encoded).ok())
+            .ok_or_else(|| {
+                Error::invalid_input(operation, format!("the {name} metadata is invalid"))
+            })
+    };
+    let iv = <[u8; NONCE_LEN]>::try_from(decode(IV_KEY)?.as_slice()).map_err(|_| {
+        Error::invalid_input(operation, format!("the {IV_KEY} metadata is invalid"))
+    })?;
+    Ok((decode(WRAPPED_KEY_KEY)?, iv))
+}
+
+pub(crate) fn random_bytes(buffer: &mut [u8]) -> Result<(), Error> {
+    SystemRandom::new()
+        .fill(buffer)
+        .map_err(|_| Error::other("envelope encryption", "the system's random source failed"))
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::FutureExt;
+
+    use super::*;
+
+    const DATA_KEY: [u8; 32] = [7; 32];
+    const IV: [u8; NONCE_LEN] = [3; NONCE_LEN];
+
+    fn plaintext(len: usize) -> Vec<u8> {
+        (0..len).map(|index| (index % 251) as u8).collect()
+    }
+
+    fn seal(plaintext: &[u8]) -> Vec<u8> {
+        ChunkCipher::for_plaintext(&DATA_KEY, IV, plaintext.len() as u64)
+            .unwrap()
+            .seal(0, plaintext)
+            .unwrap()
+    }
+
+    fn open(mut sealed: Vec<u8>) -> Option<Vec<u8>> {
+        let cipher = ChunkCipher::for_ciphertext(&DATA_KEY, IV, sealed.len() as u64).unwrap();
+        let mut opened = Vec::new();
+        for (index, chunk) in (0..).zip(sealed.chunks_mut(SEALED_CHUNK_SIZE as usize)) {
+            let length = cipher.open(index, chunk)?;
+            opened.extend_from_slice(&chunk[..length]);
+        }
+        Some(opened)
+    }
+
+    #[test]
+    fn round_trips_at_chunk_boundaries() {
+        for len in [0, CHUNK_SIZE as usize, CHUNK_SIZE as usize + 1] {
+            let plaintext = plaintext(len);
+            let sealed = seal(&plaintext);
+            let chunks = ChunkCipher::for_plaintext(&DATA_KEY, IV, len as u64)
+                .unwrap()
+                .chunk_count();
+            assert_eq!(sealed.len() as u64, len as u64 + chunks * TAG_LEN);
+            assert_eq!(open(sealed).as_deref(), Some(plaintext.as_slice()));
+        }
+    }
+
+    #[test]
+    fn rejects_a_flipped_tag_byte() {
+        let mut sealed = seal(&plaintext(100));
+        *sealed.last_mut().unwrap() ^= 1;
+        assert_eq!(open(sealed), None);
+    }
+
+    #[test]
+    fn rejects_truncation_after_the_last_full_chunk() {
+        let mut sealed = seal(&plaintext(2 * CHUNK_SIZE as usize + 10));
+        sealed.truncate(2 * SEALED_CHUNK_SIZE as usize);
+        assert_eq!(open(sealed), None);
+    }
+
+    #[test]
+    fn rejects_swapped_chunks() {
+        let sealed = seal(&plaintext(3 * CHUNK_SIZE as usize));
+        let (first, rest) = sealed.split_at(SEALED_CHUNK_SIZE as usize);
+        let (second, third) = rest.split_at(SEALED_CHUNK_SIZE as usize);
+        assert_eq!(open([second, first, third].concat()), None);
+    }
+
+    #[test]
+    fn rejects_a_wrong_wrapping_key() {
+        let wrapper = LocalKeyWrapper::new([1; 32]);
+        let wrapped = wrapper.wrap_key(&DATA_KEY).now_or_never().unwrap().unwrap();
+        let unwrapped = wrapper
+            .unwrap_key(&wrapped)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+        assert_eq!(unwrapped.as_slice(), DATA_KEY.as_slice());
+        let other = LocalKeyWrapper::new([2; 32]);
+        assert!(other.unwrap_key(&wrapped).now_or_never().unwrap().is_err());
+    }
+}
@@ -0,0 +1,370 @@
+use aws_sdk_s3::error::{ProvideErrorMetadata, SdkError};
+use std::{error::Error as StdError, fmt};
+
+/// The boxed form of the underlying error kept inside [`Error`], so that callers can
+/// still `downcast_ref` it back into the concrete
+/// [`SdkError`](https://docs.rs/aws-smithy-http/0.56.1/aws_smithy_http/result/enum.SdkError.html) of the operation.
+pub type BoxError = Box<dyn StdError + Send + Sync + 'static>;
+
+/// The AWS service an operation was sent to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Service {
+    S3,
+    SesV2,
+    Rds,
+    MemoryDb,
+    Polly,
+    Rekognition,
+    Transcribe,
+    Translate,
+    Sns,
+    Pinpoint,
+    DynamoDb,
+    Route53,
+    Sts,
+}
+impl Service {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Service::S3 => "S3",
+            Service::SesV2 => "SESv2",
+            Service::Rds => "RDS",
+            Service::MemoryDb => "MemoryDB",
+            Service::Polly => "Polly",
+            Service::Rekognition => "Rekognition",
+            Service::Transcribe => "Transcribe",
+            Service::Translate => "Translate",
+            Service::Sns => "SNS",
+            Service::Pinpoint => "Pinpoint",
+            Service::DynamoDb => "DynamoDB",
+            Service::Route53 => "Route53",
+            Service::Sts => "STS",
+        }
+    }
+}
+impl fmt::Display for Service {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Mirrors the variants of the SDK's `SdkError`, telling where the request failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SdkErrorKind {
+    /// The request could not be built; it was never sent.
+    ConstructionFailure,
+    /// The request timed out.
+    Timeout,
+    /// The request could not be dispatched, e.g. a connection or DNS failure.
+    DispatchFailure,
+    /// A response was received but could not be parsed.
+    ResponseError,
+    /// The service answered with an error, see [`Error::code`].
+    ServiceError,
+}
+
+/// The error returned by every operation of this crate instead of panicking.
+///
+/// Use [`Error::is_retryable`] to decide whether an operation is worth another attempt
+/// (throttling, timeouts, 5xx responses) and [`Error::code`] to match on the error code the
+/// service returned, such as `NoSuchBucket`.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// The request to an AWS service failed.
+    Sdk {
+        service: Service,
+        operation: &'static str,
+        kind: SdkErrorKind,
+        code: Option<String>,
+        message: Option<String>,
+        status: Option<u16>,
+        retryable: bool,
+        source: BoxError,
+    },
+    /// Reading or writing a local file failed.
+    Io {
+        operation: &'static str,
+        path: String,
+        source: std::io::Error,
+    },
+    /// The arguments given to an operation can't be used.
+    InvalidInput {
+        operation: &'static str,
+        message: String,
+    },
+    /// The service answered successfully but a value the operation relies on was absent.
+    MissingData {
+        service: Service,
+        operation: &'static str,
+        what: &'static str,
+    },
+    /// Transferred data doesn't match the size, ETag or checksum the service reported for it.
+    Integrity {
+        operation: &'static str,
+        path: String,
+        expected: String,
+        actual: String,
+    },
+    /// Any other failure, such as generating a QR code or a PDF.
+    Other {
+        operation: &'static str,
+        source: BoxError,
+    },
+}
+
+impl Error {
+    pub(crate) fn io(
+        operation: &'static str,
+        path: impl Into<String>,
+        source: std::io::Error,
+    ) -> Self {
+        Error::Io {
+            operation,
+            path: path.into(),
+            source,
+        }
+    }
+    pub(crate) fn invalid_input(operation: &'static str, message: impl Into<String>) -> Self {
+        Error::InvalidInput {
+            operation,
+            message: message.into(),
+        }
+    }
+    pub(crate) fn missing(service: Service, operation: &'static str, what: &'static str) -> Self {
+        Error::MissingData {
+            service,
+            operation,
+            what,
+        }
+    }
+    pub(crate) fn integrity(
+        operation: &'static str,
+        path: impl Into<String>,
+        expected: impl Into<String>,
+        actual: impl Into<String>,
+    ) -> Self {
+        Error::Integrity {
+            operation,
+            path: path.into(),
+            expected: expected.into(),
+            actual: actual.into(),
+        }
+    }
+    pub(crate) fn other(operation: &'static str, source: impl Into<BoxError>) -> Self {
+        Error::Other {
+            operation,
+            source: source.into(),
+        }
+    }
+
+    /// The service the failed operation belongs to, if the failure came from one.
+    pub fn service(&self) -> Option<Service> {
+        match self {
+            Error::Sdk { service, .. } | Error::MissingData { service, .. } => Some(*service),
+            _ => None,
+        }
+    }
+    /// The name of the SDK operation or crate method that failed, e.g. `create_bucket`.
+    pub fn operation(&self) -> &'static str {
+        match self {
+            Error::Sdk { operation, .. }
+            | Error::Io { operation, .. }
+            | Error::InvalidInput { operation, .. }
+            | Error::MissingData { operation, .. }
+            | Error::Integrity { operation, .. }
+            | Error::Other { operation, .. } => operation,
+        }
+    }
+    /// Where the SDK request failed; `None` for failures that never reached the SDK.
+    pub fn kind(&self) -> Option<SdkErrorKind> {
+        match self {
+            Error::Sdk { kind, .. } => Some(*kind),
+            _ => None,
+        }
+    }
+    /// The error code returned by the service, such as `NoSuchKey` or `ThrottlingException`.
+    pub fn code(&self) -> Option<&str> {
+        match self {
+            Error::Sdk { code, .. } => code.as_deref(),
+            _ => None,
+        }
+    }
+    /// The HTTP status of the error response, when a response was received.
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            Error::Sdk { status, .. } => *status,
+            _ => None,
+        }
+    }
+    /// Whether sending the same request again may succeed.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::Sdk { retryable, .. } => *retryable,
+            _ => false,
+        }
+    }
+    /// The underlying `SdkError`, which can be downcast into the operation's concrete error type.
+    pub fn sdk_error(&self) -> Option<&(dyn StdError + Send + Sync + 'static)> {
+        match self {
+            Error::Sdk { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Sdk {
+                service,
+                operation,
+                kind,
+                code,
+                message,
+                ..
+            } => {
+                write!(f, "{service} {operation} failed: ")?;
+                match (code, message) {
+                    (Some(code), Some(message)) => write!(f, "{code}: {message}"),
+                    (Some(code), None) => write!(f, "{code}"),
+                    (None, Some(message)) => write!(f, "{message}"),
+                    (None, None) => write!(f, "{kind:?}"),
+                }
+            }
+            Error::Io {
+                operation,
+                path,
+                source,
+            } => write!(f, "{operation} failed on '{path}': {source}"),
+            Error::InvalidInput { operation, message } => {
+                write!(f, "invalid input for {operation}: {message}")
+            }
+            Error::MissingData {
+                service,
+                operation,
+                what,
+            } => write!(f, "{service} {operation} returned no {what}"),
+            Error::Integrity {
+                operation,
+                path,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "{operation} failed to verify '{path}': expected {expected} but got {actual}"
+            ),
+            Error::Other { operation, source } => write!(f, "{operation} failed: {source}"),
+        }
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Error::Sdk { source, .. } | Error::Other { source, .. } => Some(source.as_ref()),
+            Error::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+//Error codes the AWS SDKs themselves treat as throttling or transient
+const RETRYABLE_CODES: &[&str] = &[
+    "Throttling",
+    "ThrottlingException",
+    "ThrottledException",
+    "RequestThrottledException",
+    "TooManyRequestsException",
+    "ProvisionedThroughputExceededException",
+    "TransactionInProgressException",
+    "RequestLimitExceeded",
+    "BandwidthLimitExceeded",
+    "LimitExceededException",
+    "RequestThrottled",
+    "SlowDown",
+    "PriorRequestNotComplete",
+    "RequestTimeout",
+    "RequestTimeoutException",
+    "InternalError",
+    "ServiceUnavailable",
+];
+
+/// Converts the `SdkError` of any service crate into [`Error`], recording which service and
+/// operation it came from. All service crates share the same `SdkError` type, so a single
+/// implementation covers them.
+pub(crate) trait SdkResultExt<T> {
+    fn map_sdk_err(self, service: Service, operation: &'static str) -> Result<T, Error>;
+}
+
+impl<T, E> SdkResultExt<T> for Result<T, SdkError<E>>
+where
+    E: ProvideErrorMetadata + StdError + Send + Sync + 'static,
+{
+    fn map_sdk_err(self, service: Service, operation: &'static str) -> Result<T, Error> {
+        self.map_err(|error| {
+            let status = error
+                .raw_response()
+                .map(|response| response.status().as_u16());
+            sdk_error(error, status, service, operation)
+        })
+    }
+}
+
+/// [`SdkResultExt`] for the errors received on event streams, such as the results of S3
+/// Select. Their raw responses are event messages rather than HTTP responses, so there's no
+/// status to record.
+pub(crate) trait StreamResultExt<T> {
+    fn map_stream_err(self, service: Service, operation: &'static str) -> Result<T, Error>;
+}
+
+impl<T, E, R> StreamResultExt<T> for Result<T, SdkError<E, R>>
+where
+    E: ProvideErrorMetadata + StdError + Send + Sync + 'static,
+    R: fmt::Debug + Send + Sync + 'static,
+{
+    fn map_stream_err(self, service: Service, operation: &'static str) -> Result<T, Error> {
+        self.map_err(|error| sdk_error(error, None, service, operation))
+    }
+}
+
+fn sdk_error<E, R>(
+    error: SdkError<E, R>,
+    status: Option<u16>,
+    service: Service,
+    operation: &'static str,
+) -> Error
+where
+    E: ProvideErrorMetadata + StdError + Send + Sync + 'static,
+    R: fmt::Debug + Send + Sync + 'static,
+{
+    let code = error.code().map(|code| code.to_string());
+    let message = error.message().map(|message| message.to_string());
+    let (kind, retryable) = match &error {
+        SdkError::ConstructionFailure(_) => (SdkErrorKind::ConstructionFailure, false),
+        SdkError::TimeoutError(_) => (SdkErrorKind::Timeout, true),
+        SdkError::DispatchFailure(failure) => (SdkErrorKind::DispatchFailure, !failure.is_user()),
+        SdkError::ResponseError(_) => (SdkErrorKind::ResponseError, true),
+        _ => {
+            let retryable_code = code
+                .as_deref()
+                .map(|code| RETRYABLE_CODES.contains(&code))
+                .unwrap_or(false);
+            let retryable_status = matches!(status, Some(status) if status == 429 || status >= 500);
+            (
+                SdkErrorKind::ServiceError,
+                retryable_code || retryable_status,
+            )
+        }
+    };
+    Error::Sdk {
+        service,
+        operation,
+        kind,
+        code,
+        message,
+        status,
+        retryable,
+        source: Box::new(error),
+    }
+}
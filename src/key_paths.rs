@@ -0,0 +1,293 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+//Names Windows reserves for devices, with or without an extension
+const RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// What [`KeyPathMapper::map`] does when the path a key maps to already exists.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    /// Leaves the existing file alone and doesn't map the key.
+    Skip,
+    /// Replaces the existing file.
+    #[default]
+    Overwrite,
+    /// Picks a free name by adding ` (1)`, ` (2)` and so on before the extension.
+    Rename,
+}
+
+/// Maps object keys to local paths under a root, mirroring the prefix hierarchy, so
+/// `photos/2023/a.jpg` becomes `<root>/photos/2023/a.jpg`.
+///
+/// Keys are sanitized so they can't leave the root or produce invalid file names. Empty,
+/// `.` and `..` components are dropped, so neither `../x` nor `/etc/x` escapes. The
+/// characters `< > : " \ | ? *` and control characters are replaced with `_`, trailing dots
+/// and spaces are removed, and device names such as `CON` or `nul.txt` get a leading `_`.
+#[derive(Debug, Clone, Default)]
+pub struct KeyPathMapper {
+    root: PathBuf,
+    strip_prefix: Option<String>,
+    conflict: ConflictPolicy,
+}
+
+impl KeyPathMapper {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            ..Default::default()
+        }
+    }
+    /// Removes a prefix from keys before they are mapped, so with `reports/` the key
+    /// `reports/2023/q1.csv` ends up at `<root>/2023/q1.csv`.
+    pub fn strip_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.strip_prefix = Some(prefix.into());
+        self
+    }
+    pub fn on_conflict(mut self, policy: ConflictPolicy) -> Self {
+        self.conflict = policy;
+        self
+    }
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+    pub fn conflict_policy(&self) -> ConflictPolicy {
+        self.conflict
+    }
+    /// The sanitized path under the root for a key, without looking at the file system.
+    /// Returns `None` when nothing usable is left of the key, as with folder placeholders
+    /// ending in `/` or keys made only of `.` and `..`.
+    pub fn local_path(&self, key: &str) -> Option<PathBuf> {
+        let key = match &self.strip_prefix {
+            Some(prefix) => key.strip_prefix(prefix.as_str()).unwrap_or(key),
+            None => key,
+        };
+        if key.ends_with('/') {
+            return None;
+        }
+        let components = key
+            .split('/')
+            .filter_map(sanitize_component)
+            .collect::<Vec<_>>();
+        if components.is_empty() {
+            return None;
+        }
+        Some(
+            components
+                .iter()
+                .fold(self.root.clone(), |path, component| path.join(component)),
+        )
+    }
+    /// Applies the conflict policy to a path: `None` when it exists and the policy is
+    /// [`ConflictPolicy::Skip`], a free variation of it for [`ConflictPolicy::Rename`], and
+    /// the path itself otherwise. A key that is both an object and a prefix, such as `a/b`
+    /// next to `a/b/c`, can't have both: a directory is never overwritten by a file, and
+    /// `None` is returned under every policy when a file stands where a parent directory of
+    /// the path should be.
+    pub fn resolve(&self, path: PathBuf) -> Option<PathBuf> {
+        self.resolve_among(path, &HashSet::new())
+    }
+    //The paths in `given` are files about to be written, so they count as existing
+    fn resolve_among(&self, path: PathBuf, given: &HashSet<PathBuf>) -> Option<PathBuf> {
+        let taken = |path: &Path| path.exists() || given.contains(path);
+        if path
+            .ancestors()
+            .skip(1)
+            .take_while(|ancestor| *ancestor != self.root)
+            .any(|ancestor| ancestor.is_file() || given.contains(ancestor))
+        {
+            return None;
+        }
+        if !taken(&path) {
+            return Some(path);
+        }
+        match self.conflict {
+            ConflictPolicy::Skip => None,
+            //Another key of the batch is never overwritten, as the two would race
+            ConflictPolicy::Overwrite => (path.is_file() && !given.contains(&path)).then_some(path),
+            ConflictPolicy::Rename => {
+                let stem = path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                let extension = path
+                    .extension()
+                    .map(|extension| format!(".{}", extension.to_string_lossy()))
+                    .unwrap_or_default();
+                (1..)
+                    .map(|number| path.with_file_name(format!("{stem} ({number}){extension}")))
+                    .find(|candidate| !taken(candidate))
+            }
+        }
+    }
+    /// The local path for a key with the conflict policy applied, or `None` when the key
+    /// has no usable name or the file exists and is to be skipped.
+    pub fn map(&self, key: &str) -> Option<PathBuf> {
+        self.local_path(key).and_then(|path| self.resolve(path))
+    }
+    /// Like [`map`](Self::map) for one key of a batch, where the paths already `given` to
+    /// other keys of the batch count as existing files too, so keys that sanitize to the same
+    /// path, such as `a:b` and `a_b`, don't overwrite each other. The path is added to
+    /// `given`. A key mapped below a path given before, as `a/b/c` is below `a/b`, is left
+    /// out, which covers keys that are both an object and a prefix when they're mapped in
+    /// sorted order.
+    pub fn map_unique(&self, key: &str, given: &mut HashSet<PathBuf>) -> Option<PathBuf> {
+        let path = self.resolve_among(self.local_path(key)?, given)?;
+        given.insert(path.clone());
+        Some(path)
+    }
+}
+
+fn sanitize_component(component: &str) -> Option<String> {
+    let replaced = component
+        .chars()
+        .map(|ch| match ch {
+            '<' | '>' | ':' | '"' | '\\' | '|' | '?' | '*' => '_',
+            ch if ch.is_control() => '_',
+            ch => ch,
+        })
+        .collect::<String>();
+    //Windows drops trailing dots and spaces, which also turns `..` into nothing
+    let trimmed = replaced.trim_end_matches(['.', ' ']);
+    if trimmed.is_empty() {
+        return None;
+    }
+    let stem = trimmed.split('.').next().unwrap_or_default();
+    if RESERVED_NAMES
+        .iter()
+        .any(|reserved| reserved.eq_ignore_ascii_case(stem))
+    {
+        return Some(format!("_{trimmed}"));
+    }
+    Some(trimmed.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    fn temp_root(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("aws_apis-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    #[test]
+    fn drops_dot_dot_segments() {
+        let mapper = KeyPathMapper::new("root");
+        assert_eq!(
+            mapper.local_path("a/../../b"),
+            Some(Path::new("root").join("a").join("b"))
+        );
+        assert_eq!(mapper.local_path("../.."), None);
+        assert_eq!(mapper.local_path("a/b/"), None);
+    }
+
+    #[test]
+    fn keeps_keys_with_a_leading_slash_under_the_root() {
+        let mapper = KeyPathMapper::new("root");
+        assert_eq!(
+            mapper.local_path("/etc/passwd"),
+            Some(Path::new("root").join("etc").join("passwd"))
+        );
+        assert_eq!(
+            mapper.strip_prefix("logs/").local_path("logs//x"),
+            Some(Path::new("root").join("x"))
+        );
+    }
+
+    #[test]
+    fn renames_windows_reserved_names() {
+        assert_eq!(sanitize_component("CON").as_deref(), Some("_CON"));
+        assert_eq!(sanitize_component("aux.txt").as_deref(), Some("_aux.txt"));
+        assert_eq!(sanitize_component("nul. ").as_deref(), Some("_nul"));
+        assert_eq!(sanitize_component("CONSOLE").as_deref(), Some("CONSOLE"));
+        assert_eq!(sanitize_component("a:b?.txt").as_deref(), Some("a_b_.txt"));
+        assert_eq!(
+            KeyPathMapper::new("root").local_path("com1/aux.txt"),
+            Some(Path::new("root").join("_com1").join("_aux.txt"))
+        );
+    }
+
+    #[test]
+    fn resolves_a_key_that_is_also_a_prefix() {
+        let root = temp_root("prefix-conflict");
+        //`a/b/c` was downloaded first, so `a/b` is a directory
+        fs::create_dir_all(root.join("a").join("b")).unwrap();
+        fs::write(root.join("a").join("b").join("c"), "c").unwrap();
+        //and `x` first, so `x/y` has a file where its directory should be
+        fs::write(root.join("x"), "x").unwrap();
+        for policy in [
+            ConflictPolicy::Skip,
+            ConflictPolicy::Overwrite,
+            ConflictPolicy::Rename,
+        ] {
+            let mapper = KeyPathMapper::new(&root).on_conflict(policy);
+            let renamed = (policy == ConflictPolicy::Rename).then(|| root.join("a").join("b (1)"));
+            assert_eq!(mapper.map("a/b"), renamed, "{policy:?}");
+            assert_eq!(mapper.map("x/y"), None, "{policy:?}");
+            assert_eq!(
+                mapper.map("a/b/d"),
+                Some(root.join("a").join("b").join("d"))
+            );
+        }
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn keeps_keys_of_a_batch_from_sharing_a_path() {
+        let root = temp_root("batch-conflict");
+        for policy in [
+            ConflictPolicy::Skip,
+            ConflictPolicy::Overwrite,
+            ConflictPolicy::Rename,
+        ] {
+            let mapper = KeyPathMapper::new(&root).on_conflict(policy);
+            let mut given = HashSet::new();
+            assert_eq!(mapper.map_unique("a:b", &mut given), Some(root.join("a_b")));
+            let renamed = (policy == ConflictPolicy::Rename).then(|| root.join("a_b (1)"));
+            assert_eq!(mapper.map_unique("a_b", &mut given), renamed, "{policy:?}");
+            assert_eq!(
+                mapper.map_unique("a|b", &mut given).is_some(),
+                renamed.is_some()
+            );
+            assert_eq!(mapper.map_unique("a_b/c", &mut given), None, "{policy:?}");
+        }
+        let mapper = KeyPathMapper::new(&root).on_conflict(ConflictPolicy::Rename);
+        let mut given = HashSet::new();
+        let first = mapper.map_unique("x?", &mut given);
+        let second = mapper.map_unique("x*", &mut given);
+        let third = mapper.map_unique("x_", &mut given);
+        assert_eq!(
+            [first, second, third],
+            [
+                Some(root.join("x_")),
+                Some(root.join("x_ (1)")),
+                Some(root.join("x_ (2)"))
+            ]
+        );
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn applies_the_policy_to_an_existing_file() {
+        let root = temp_root("file-conflict");
+        fs::write(root.join("report.csv"), "old").unwrap();
+        let mapper = KeyPathMapper::new(&root);
+        assert_eq!(mapper.map("report.csv"), Some(root.join("report.csv")));
+        let mapper = mapper.on_conflict(ConflictPolicy::Skip);
+        assert_eq!(mapper.map("report.csv"), None);
+        let mapper = mapper.on_conflict(ConflictPolicy::Rename);
+        assert_eq!(mapper.map("report.csv"), Some(root.join("report (1).csv")));
+        fs::write(root.join("report (1).csv"), "older").unwrap();
+        assert_eq!(mapper.map("report.csv"), Some(root.join("report (2).csv")));
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
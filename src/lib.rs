@@ -1,5 +1,50 @@
+mod error;
+pub use error::{BoxError, Error, SdkErrorKind, Service};
+
+mod output;
+pub use output::{strip_colors, MemorySink, OutputDirSink, OutputSink, SilentSink, TerminalSink};
+
+mod pagination;
+pub use pagination::ItemStream;
+
+mod endpoints;
+pub use endpoints::Endpoints;
+
+mod key_paths;
+pub use key_paths::{ConflictPolicy, KeyPathMapper};
+
+mod bucket_config;
+pub use bucket_config::{
+    BucketConfig, CorsRule, LifecycleRule, PublicAccessBlock, Transition, Versioning,
+};
+
+mod bucket_usage;
+pub use bucket_usage::{
+    AgeRange, BucketUsageReport, IncompleteUpload, LargeObject, PrefixUsage, UsageOptions,
+    UsageTotals,
+};
+
+mod checksums;
+pub use checksums::Checksum;
+
+mod content_type;
+
+mod progress;
+pub use progress::{
+    ChannelProgress, ProgressListener, TerminalProgress, TransferDirection, TransferProgress,
+};
+
+mod object_lock;
+pub use object_lock::{DefaultRetention, ObjectRetention, RetentionMode, RetentionPeriod};
+
+mod envelope;
+pub use envelope::{KeyWrapper, LocalKeyWrapper};
+
 mod credentials;
-pub use credentials::{load_credential_from_env, load_from_env, CredentInitialize, SdkConfig};
+pub use credentials::{
+    load_credential_from_env, load_from_env, AssumeRole, CredentInitialize, CredentialSource,
+    SdkConfig,
+};
 
 mod memorydb_ops;
 pub use memorydb_ops::{MemDbClusterInfo, MemDbOps};
@@ -8,7 +53,19 @@ mod rds_ops;
 pub use rds_ops::{DbClusterInfo, DbInstanceInfo, RdsOps};
 
 mod s3_ops;
-pub use s3_ops::S3Ops;
+pub use s3_ops::{
+    BulkDeleteReport, CopyOptions, CustomerKey, DeleteFailure, MultipartConfig, ObjectInfo,
+    PresignOptions, PresignedPost, PresignedUrl, RenameReport, S3Ops, ServerSideEncryption,
+    SyncOptions, SyncReport, TransferChecksum, TransferResult, UploadOptions,
+};
+
+mod s3_select;
+pub use s3_select::{
+    CsvHeader, SelectCompression, SelectInput, SelectOutput, SelectRow, SelectStats, SelectStream,
+};
+
+mod static_site;
+pub use static_site::{RedirectRule, SiteDeployment, SiteOptions};
 
 mod sesv2_ops;
 pub use sesv2_ops::{
@@ -43,8 +100,8 @@ pub use dynamodb::DynamoDbOps;
 
 mod pdf_writer;
 pub use pdf_writer::{
-    create_celebrity_pdf, create_celebrity_single_pdf, create_detect_face_image_pdf,
-    create_email_identities_pdf, create_email_pdf, create_face_result_pdf,
-    create_polly_voice_info_pdf, create_text_only_pdf, create_text_result_pdf,
-    create_translated_text_pdf, create_translation_language_details_pdf,
+    create_bucket_usage_pdf, create_celebrity_pdf, create_celebrity_single_pdf,
+    create_detect_face_image_pdf, create_email_identities_pdf, create_email_pdf,
+    create_face_result_pdf, create_polly_voice_info_pdf, create_text_only_pdf,
+    create_text_result_pdf, create_translated_text_pdf, create_translation_language_details_pdf,
 };
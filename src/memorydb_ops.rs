@@ -1,15 +1,32 @@
+use std::sync::Arc;
+
+use crate::{
+    error::{Error, SdkResultExt, Service},
+    output::{report, OutputSink, TerminalSink},
+    pagination::{collect_items, items_of_pages, ItemStream},
+};
 use aws_config::SdkConfig;
 use aws_sdk_memorydb::{
-    types::{Authentication, AuthenticationMode, Endpoint, InputAuthenticationType, Snapshot},
+    types::{Acl, Authentication, AuthenticationMode, Endpoint, InputAuthenticationType, Snapshot},
     Client as MemDbClient,
 };
 use colored::Colorize;
-pub struct MemDbOps<'a> {
-    config: &'a SdkConfig,
+use tokio_stream::StreamExt;
+#[derive(Debug, Clone)]
+pub struct MemDbOps {
+    client: MemDbClient,
+    sink: Arc<dyn OutputSink>,
 }
-impl<'a> MemDbOps<'a> {
-    pub fn build(config: &'a SdkConfig) -> Self {
-        Self { config }
+impl MemDbOps {
+    pub fn build(config: &SdkConfig) -> Self {
+        Self {
+            client: MemDbClient::new(config),
+            sink: Arc::new(TerminalSink),
+        }
+    }
+    pub fn with_sink(mut self, sink: Arc<dyn OutputSink>) -> Self {
+        self.sink = sink;
+        self
     }
     //node type =The compute and memory capacity of the nodes in the cluster
     //possible node values = vec!["db.t4g.small","db.r6g.large","db.r6g.xlarge,"db.r6g.2xlarge]
@@ -19,9 +36,8 @@ impl<'a> MemDbOps<'a> {
         node_type: &str,
         cluster_name: &str,
         access_control_list_name: &str,
-    ) {
-        let client = MemDbClient::new(self.config);
-        client.create_cluster()
+    ) -> Result<(), Error> {
+        self.client.create_cluster()
                     .acl_name(access_control_list_name)
                     .cluster_name(cluster_name)
                     .node_type(node_type)
@@ -29,7 +45,7 @@ impl<'a> MemDbOps<'a> {
                     .await
                     .map(|output|{
                         let colored_msg = format!("The cluster with the name {cluster_name} has been created, and the process of starting it is now underway").green().bold();
-                        println!("{colored_msg}");
+                        report!(self.sink, "{colored_msg}");
                         let status =if let Some(cluster)= output.cluster{
                             if let Some(status_) = cluster.status{
                                  Some(status_)
@@ -41,11 +57,11 @@ impl<'a> MemDbOps<'a> {
                         };
                         if let Some(status) = status {
                             let colored_status = status.green().bold();
-                            println!("The Present State of the MemDb Cluster: {colored_status}\n");
+                            report!(self.sink, "The Present State of the MemDb Cluster: {colored_status}\n");
                           }
                        
                     })
-                    .expect("Error while creating memory db cluster");
+                    .map_sdk_err(Service::MemoryDb, "create_cluster")
     }
 
     pub async fn create_memdb_user(
@@ -54,13 +70,16 @@ impl<'a> MemDbOps<'a> {
         access_string: &str,
         authenticate_type: &str,
         authenticate_passwords: &str,
-    ) {
-        let client = MemDbClient::new(self.config);
-
+    ) -> Result<(), Error> {
         let authenticate_type = match authenticate_type {
             "iam" | "Iam" => InputAuthenticationType::Iam,
             "password" | "Password" => InputAuthenticationType::Password,
-            _ => panic!("Wrong authentication types: {}\n", authenticate_type),
+            _ => {
+                return Err(Error::invalid_input(
+                    "create_memdb_user",
+                    format!("Wrong authentication types: {}", authenticate_type),
+                ))
+            }
         };
         let get_passwords = authenticate_passwords
             .split_whitespace()
@@ -72,52 +91,63 @@ impl<'a> MemDbOps<'a> {
             .set_passwords(Some(get_passwords))
             .build();
 
-        let create_user_output = client
+        let create_user_output = self
+            .client
             .create_user()
             .set_access_string(Some(access_string.into()))
             .set_user_name(Some(username.into()))
             .set_authentication_mode(Some(build_auth_type))
             .send()
             .await
-            .expect("Error while creating user in MemoryDB\n");
+            .map_sdk_err(Service::MemoryDb, "create_user")?;
         let user = create_user_output.user;
         if let Some(user) = user {
             if let Some(status) = user.status {
                 let colored_status = status.green().bold();
-                println!("The status of user: {}\n", colored_status);
+                report!(self.sink, "The status of user: {}\n", colored_status);
             } else {
-                println!("The satus of user: None\n")
+                report!(self.sink, "The satus of user: None\n")
             }
         }
+        Ok(())
     }
 
-    pub async fn create_acl(&self, acl_name: &str) {
-        let client = MemDbClient::new(self.config);
-        let output = client
+    pub async fn create_acl(&self, acl_name: &str) -> Result<(), Error> {
+        let output = self
+            .client
             .create_acl()
             .acl_name(acl_name)
             .send()
             .await
-            .expect("Error while creating Access Control List\n");
-        println!("The Access Control List name {acl_name} has been created..");
+            .map_sdk_err(Service::MemoryDb, "create_acl")?;
+        report!(
+            self.sink,
+            "The Access Control List name {acl_name} has been created.."
+        );
 
         if let Some(aclinfo) = output.acl {
             if let Some(status) = aclinfo.status {
                 let colored_status = status.green().bold();
-                println!("The current Status of ACL Name {acl_name} is: {colored_status}\n");
+                report!(
+                    self.sink,
+                    "The current Status of ACL Name {acl_name} is: {colored_status}\n"
+                );
             }
         }
+        Ok(())
     }
 
-    pub async fn describe_memdb_cluster(&self, cluster_name: &str) -> Vec<MemDbClusterInfo> {
-        let client = MemDbClient::new(self.config);
-
-        let cluster_info = client
+    pub async fn describe_memdb_cluster(
+        &self,
+        cluster_name: &str,
+    ) -> Result<Vec<MemDbClusterInfo>, Error> {
+        let cluster_info = self
+            .client
             .describe_clusters()
             .cluster_name(cluster_name)
             .send()
             .await
-            .expect("Error while Describing the memdb clusters");
+            .map_sdk_err(Service::MemoryDb, "describe_clusters")?;
         let cluster_info = cluster_info.clusters;
 
         let mut vec_of_memdbclusterinfo = Vec::new();
@@ -137,24 +167,22 @@ impl<'a> MemDbOps<'a> {
                 vec_of_memdbclusterinfo.push(memdbinfo);
             });
         }
-        vec_of_memdbclusterinfo
+        Ok(vec_of_memdbclusterinfo)
     }
 
     ///Only returns the single insatnce of user instead of vector of user.
-    pub async fn describe_memdb_user(&self, username: &str) -> Vec<MemDBUser> {
-        let client = MemDbClient::new(self.config);
-
-        let output = client
+    pub async fn describe_memdb_user(&self, username: &str) -> Result<Vec<MemDBUser>, Error> {
+        let output = self
+            .client
             .describe_users()
             .user_name(username)
             .send()
             .await
-            .expect("Error while describing memdb cluster");
+            .map_sdk_err(Service::MemoryDb, "describe_users")?;
         let user = output.users;
         let mut single_user_info = Vec::new();
-        if let Some(mut vec_of_users) = user {
-            let single_user_ = vec_of_users.drain(..1);
-            single_user_.into_iter().for_each(|user| {
+        if let Some(vec_of_users) = user {
+            vec_of_users.into_iter().take(1).for_each(|user| {
                 let user_name = user.name;
                 let status = user.status;
                 let access_string = user.access_string;
@@ -168,18 +196,17 @@ impl<'a> MemDbOps<'a> {
             });
         }
 
-        single_user_info
+        Ok(single_user_info)
     }
 
-    pub async fn describe_acl(&self, acl_name: &str) -> AclInfo {
-        let client = MemDbClient::new(self.config);
-
-        let output = client
+    pub async fn describe_acl(&self, acl_name: &str) -> Result<AclInfo, Error> {
+        let output = self
+            .client
             .describe_ac_ls()
             .acl_name(acl_name)
             .send()
             .await
-            .expect("Error while describing the acl\n");
+            .map_sdk_err(Service::MemoryDb, "describe_ac_ls")?;
         let mut acl_info = AclInfo::default();
         if let Some(aclinfos) = output.ac_ls {
             aclinfos.into_iter().for_each(|aclinfo| {
@@ -190,62 +217,62 @@ impl<'a> MemDbOps<'a> {
                 acl_info = AclInfo::build_aclinfo(acl_name, status, user_names, clusters);
             });
         }
-        acl_info
+        Ok(acl_info)
     }
 
-    pub async fn describe_acls(&self) -> Vec<AclInfo> {
-        let client = MemDbClient::new(self.config);
-        let ouput = client
-            .describe_ac_ls()
-            .send()
-            .await
-            .expect("Error while describing the ACL\n");
+    pub async fn describe_acls(&self) -> Result<Vec<AclInfo>, Error> {
+        let vecofacl = collect_items(self.describe_acls_stream()).await?;
         let mut vec_of_acl = Vec::new();
 
-        if let Some(vecofacl) = ouput.ac_ls {
-            vecofacl.into_iter().for_each(|acl_infos| {
-                let acl_name = acl_infos.name;
-                let status = acl_infos.status;
-                let user_names = acl_infos.user_names;
-                let clusters = acl_infos.clusters;
-                vec_of_acl.push(AclInfo::build_aclinfo(
-                    acl_name, status, user_names, clusters,
-                ))
-            });
-        }
-        vec_of_acl
+        vecofacl.into_iter().for_each(|acl_infos| {
+            let acl_name = acl_infos.name;
+            let status = acl_infos.status;
+            let user_names = acl_infos.user_names;
+            let clusters = acl_infos.clusters;
+            vec_of_acl.push(AclInfo::build_aclinfo(
+                acl_name, status, user_names, clusters,
+            ))
+        });
+        Ok(vec_of_acl)
+    }
+    /// Describes every ACL in the account and region, fetching further pages as the stream
+    /// is consumed
+    pub fn describe_acls_stream(&self) -> ItemStream<Acl> {
+        let pages = self
+            .client
+            .describe_ac_ls()
+            .into_paginator()
+            .send()
+            .map(|page| page.map_sdk_err(Service::MemoryDb, "describe_ac_ls"));
+        items_of_pages(pages, |page| page.ac_ls.unwrap_or_default())
     }
 
-    pub async fn describe_snapshots(&self, cluster_name: &str) -> Vec<Snapshot> {
-        let client = MemDbClient::new(self.config);
-
-        let snapshots = client
+    pub async fn describe_snapshots(&self, cluster_name: &str) -> Result<Vec<Snapshot>, Error> {
+        let pages = self
+            .client
             .describe_snapshots()
             .cluster_name(cluster_name)
+            .into_paginator()
             .send()
-            .await
-            .expect("Error while describing snapshots of memdb");
-        let mut vec_of_snapshots = Vec::new();
-        let snapshots = snapshots.snapshots;
-
-        if let Some(vec_of_snapshot) = snapshots {
-            vec_of_snapshot.into_iter().for_each(|snapshot| {
-                vec_of_snapshots.push(snapshot);
-            })
-        }
-        vec_of_snapshots
+            .map(|page| page.map_sdk_err(Service::MemoryDb, "describe_snapshots"));
+        collect_items(items_of_pages(pages, |page| {
+            page.snapshots.unwrap_or_default()
+        }))
+        .await
     }
 
-    pub async fn delete_memdb_cluster(&self, cluster_name: &str, final_snapshot_name: &str) {
-        let client = MemDbClient::new(self.config);
-
-        client.delete_cluster()
+    pub async fn delete_memdb_cluster(
+        &self,
+        cluster_name: &str,
+        final_snapshot_name: &str,
+    ) -> Result<(), Error> {
+        self.client.delete_cluster()
                 .cluster_name(cluster_name)
                 .final_snapshot_name(final_snapshot_name)
                 .send()
                 .await
                 .map(|output|{
-                    println!("The MemDB cluster named {cluster_name} has initiated the cluster deletion process.");
+                    report!(self.sink, "The MemDB cluster named {cluster_name} has initiated the cluster deletion process.");
                     let status = if let Some(cluster) = output.cluster  {
                         if let Some(status) = cluster.status{
                             Some(status)
@@ -257,39 +284,41 @@ impl<'a> MemDbOps<'a> {
               };
               if let Some(status) = status {
                 let colored_status = status.green().bold();
-                println!("The Present State of the MemDb Cluster: {colored_status}\n");
+                report!(self.sink, "The Present State of the MemDb Cluster: {colored_status}\n");
               }
                 })
-                .expect("Error while deleteing memdb cluster");
+                .map_sdk_err(Service::MemoryDb, "delete_cluster")
     }
 
-    pub async fn delete_memdb_user(&self, username: &str) {
-        let client = MemDbClient::new(self.config);
-
-        let ouput = client
+    pub async fn delete_memdb_user(&self, username: &str) -> Result<(), Error> {
+        let ouput = self
+            .client
             .delete_user()
             .user_name(username)
             .send()
             .await
-            .expect("Error while deleting memdb user");
+            .map_sdk_err(Service::MemoryDb, "delete_user")?;
         if let Some(user) = ouput.user {
             if let (Some(status), Some(name)) = (user.status, user.name) {
                 let colored_name = name.green().bold();
                 let colored_status = status.green().bold();
-                println!("The Name of User: {colored_name}\nThe current status: {colored_status}\n")
+                report!(
+                    self.sink,
+                    "The Name of User: {colored_name}\nThe current status: {colored_status}\n"
+                )
             }
         }
+        Ok(())
     }
 
-    pub async fn delete_acl(&self, acl_name: &str) -> AclInfo {
-        let client = MemDbClient::new(self.config);
-
-        let delete_acl = client
+    pub async fn delete_acl(&self, acl_name: &str) -> Result<AclInfo, Error> {
+        let delete_acl = self
+            .client
             .delete_acl()
             .acl_name(acl_name)
             .send()
             .await
-            .expect("Error while deleting acl name\n");
+            .map_sdk_err(Service::MemoryDb, "delete_acl")?;
         let mut acl_info = AclInfo::default();
 
         if let Some(aclinfo) = delete_acl.acl {
@@ -300,7 +329,7 @@ impl<'a> MemDbOps<'a> {
 
             acl_info = AclInfo::build_aclinfo(acl_name, status, user_names, clusters);
         }
-        acl_info
+        Ok(acl_info)
     }
 }
 
@@ -332,15 +361,15 @@ impl MemDBUser {
         self.user_name.as_deref()
     }
 
-    pub fn print_auth_info(&self) {
+    pub fn print_auth_info(&self, sink: &dyn OutputSink) {
         if let Some(authentication) = self.authentication.as_ref() {
             if let Some(auth_type) = authentication.r#type() {
                 let colored_auth_type = auth_type.as_str().green().bold();
-                println!("Authentication Type: {colored_auth_type}\n");
+                report!(sink, "Authentication Type: {colored_auth_type}\n");
             }
             if let Some(pass_count) = authentication.password_count {
                 let colored_count = pass_count.to_string().green().bold();
-                println!("Password count: {colored_count}\n");
+                report!(sink, "Password count: {colored_count}\n");
             }
         }
     }
@@ -394,8 +423,6 @@ impl MemDbClusterInfo {
     }
 
     pub fn get_endpoint_with_port(&self) -> Option<String> {
-        let status = self.get_status();
-        println!("Current Status of MemDbInstance: {status:?}\n");
         let connection_url = if let Some(endpoint) = self.cluster_end_point.as_ref() {
             if let Some(database_url) = endpoint.address() {
                 let mut url = database_url.to_string();
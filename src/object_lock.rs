@@ -0,0 +1,156 @@
+use std::time::{Duration, SystemTime};
+
+use aws_sdk_s3::{
+    primitives::DateTime,
+    types::{
+        DefaultRetention as SdkDefaultRetention, ObjectLockConfiguration, ObjectLockEnabled,
+        ObjectLockLegalHoldStatus, ObjectLockMode, ObjectLockRetention, ObjectLockRetentionMode,
+        ObjectLockRule,
+    },
+};
+
+/// How strictly Object Lock keeps an object version from being overwritten or deleted
+/// before its retention ends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetentionMode {
+    /// Users with the `s3:BypassGovernanceRetention` permission can still shorten the
+    /// retention or delete the version.
+    Governance,
+    /// Nobody can shorten the retention or delete the version until it ends, the root user
+    /// included.
+    Compliance,
+}
+
+impl RetentionMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Governance => "GOVERNANCE",
+            Self::Compliance => "COMPLIANCE",
+        }
+    }
+    pub(crate) fn to_sdk(self) -> ObjectLockRetentionMode {
+        ObjectLockRetentionMode::from(self.as_str())
+    }
+    pub(crate) fn to_object_mode(self) -> ObjectLockMode {
+        ObjectLockMode::from(self.as_str())
+    }
+    pub(crate) fn from_sdk(mode: &ObjectLockRetentionMode) -> Option<Self> {
+        Self::from_name(mode.as_str())
+    }
+    pub(crate) fn from_object_mode(mode: &ObjectLockMode) -> Option<Self> {
+        Self::from_name(mode.as_str())
+    }
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "GOVERNANCE" => Some(Self::Governance),
+            "COMPLIANCE" => Some(Self::Compliance),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetentionPeriod {
+    Days(u32),
+    Years(u32),
+}
+
+/// The retention S3 gives every new object version in a bucket with Object Lock, unless
+/// the upload sets its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DefaultRetention {
+    mode: RetentionMode,
+    period: RetentionPeriod,
+}
+
+impl DefaultRetention {
+    pub fn days(mode: RetentionMode, days: u32) -> Self {
+        Self {
+            mode,
+            period: RetentionPeriod::Days(days),
+        }
+    }
+    pub fn years(mode: RetentionMode, years: u32) -> Self {
+        Self {
+            mode,
+            period: RetentionPeriod::Years(years),
+        }
+    }
+    pub fn mode(&self) -> RetentionMode {
+        self.mode
+    }
+    pub fn period(&self) -> RetentionPeriod {
+        self.period
+    }
+    /// The configuration that turns Object Lock on with this default retention.
+    pub(crate) fn to_sdk(self) -> ObjectLockConfiguration {
+        let retention = SdkDefaultRetention::builder().mode(self.mode.to_sdk());
+        let retention = match self.period {
+            RetentionPeriod::Days(days) => retention.days(days as i32),
+            RetentionPeriod::Years(years) => retention.years(years as i32),
+        };
+        ObjectLockConfiguration::builder()
+            .object_lock_enabled(ObjectLockEnabled::Enabled)
+            .rule(
+                ObjectLockRule::builder()
+                    .default_retention(retention.build())
+                    .build(),
+            )
+            .build()
+    }
+    //The SDK reads a missing number as 0
+    pub(crate) fn from_sdk(retention: &SdkDefaultRetention) -> Option<Self> {
+        let mode = RetentionMode::from_sdk(retention.mode()?)?;
+        match (retention.days(), retention.years()) {
+            (days, _) if days > 0 => Some(Self::days(mode, days as u32)),
+            (_, years) if years > 0 => Some(Self::years(mode, years as u32)),
+            _ => None,
+        }
+    }
+}
+
+/// The retention of one object version: it can't be overwritten or deleted before
+/// `retain_until`, which can be extended later but only shortened in governance mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ObjectRetention {
+    mode: RetentionMode,
+    retain_until: SystemTime,
+}
+
+impl ObjectRetention {
+    pub fn new(mode: RetentionMode, retain_until: SystemTime) -> Self {
+        Self { mode, retain_until }
+    }
+    /// Retains the object for a while from now.
+    pub fn for_duration(mode: RetentionMode, duration: Duration) -> Self {
+        Self::new(mode, SystemTime::now() + duration)
+    }
+    pub fn mode(&self) -> RetentionMode {
+        self.mode
+    }
+    pub fn retain_until(&self) -> SystemTime {
+        self.retain_until
+    }
+    pub(crate) fn retain_until_date(&self) -> DateTime {
+        DateTime::from(self.retain_until)
+    }
+    pub(crate) fn to_sdk(self) -> ObjectLockRetention {
+        ObjectLockRetention::builder()
+            .mode(self.mode.to_sdk())
+            .retain_until_date(self.retain_until_date())
+            .build()
+    }
+    pub(crate) fn from_sdk(retention: &ObjectLockRetention) -> Option<Self> {
+        Some(Self {
+            mode: RetentionMode::from_sdk(retention.mode()?)?,
+            retain_until: SystemTime::try_from(*retention.retain_until_date()?).ok()?,
+        })
+    }
+}
+
+pub(crate) fn legal_hold_status(on: bool) -> ObjectLockLegalHoldStatus {
+    match on {
+        true => ObjectLockLegalHoldStatus::On,
+        false => ObjectLockLegalHoldStatus::Off,
+    }
+}
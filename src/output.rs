@@ -0,0 +1,196 @@
+use std::{
+    collections::BTreeMap,
+    fmt,
+    fs::{create_dir_all, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+/// Receives everything an operation reports: the console messages and the files it generates,
+/// such as `uri.txt`, `sns_topic_arn.txt` or `EmailsList.pdf`.
+///
+/// The Ops structs use a [`TerminalSink`] unless another one is given through their
+/// `with_sink` method. Implement this trait to route the output elsewhere, for example into a
+/// logger or an object store.
+pub trait OutputSink: fmt::Debug + Send + Sync {
+    /// Reports a line of text. Messages are formatted for the terminal and may contain ANSI
+    /// color codes; use [`strip_colors`] to get the plain text.
+    fn message(&self, text: &str);
+
+    /// Stores a generated file under the given name and returns a description of where it
+    /// ended up, which is used in the messages that follow the write. Names are relative paths
+    /// and may contain directories, such as `TranscribeOutputs/job.srt`.
+    fn write_artifact(&self, name: &str, bytes: &[u8]) -> io::Result<String>;
+
+    /// The file an artifact with the given name would be written to. Operations that stream
+    /// large downloads straight to disk write there instead of calling
+    /// [`write_artifact`](Self::write_artifact). Sinks that don't keep files on disk return
+    /// `None`, and such operations then buffer the content and hand it to `write_artifact`.
+    fn artifact_path(&self, _name: &str) -> Option<PathBuf> {
+        None
+    }
+}
+
+/// Prints messages to stdout and writes files into the current directory, which is how the
+/// crate has always behaved.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TerminalSink;
+
+impl OutputSink for TerminalSink {
+    fn message(&self, text: &str) {
+        println!("{text}");
+    }
+    fn write_artifact(&self, name: &str, bytes: &[u8]) -> io::Result<String> {
+        write_file(Path::new(name), bytes)?;
+        Ok("the current directory".into())
+    }
+    fn artifact_path(&self, name: &str) -> Option<PathBuf> {
+        Some(PathBuf::from(name))
+    }
+}
+
+/// Discards messages and files alike.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SilentSink;
+
+impl OutputSink for SilentSink {
+    fn message(&self, _text: &str) {}
+    fn write_artifact(&self, _name: &str, _bytes: &[u8]) -> io::Result<String> {
+        Ok("nowhere (output is discarded)".into())
+    }
+}
+
+/// Keeps messages and files in memory so they can be inspected after the operation, which is
+/// useful in servers and tests.
+///
+/// Writing an artifact whose name is already taken replaces the earlier content.
+#[derive(Debug, Default)]
+pub struct MemorySink {
+    messages: Mutex<Vec<String>>,
+    artifacts: Mutex<BTreeMap<String, Vec<u8>>>,
+}
+
+impl MemorySink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// The reported messages without color codes, in the order they were reported.
+    pub fn messages(&self) -> Vec<String> {
+        self.messages
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+    /// The content of the artifact with the given name, if one was written.
+    pub fn artifact(&self, name: &str) -> Option<Vec<u8>> {
+        self.artifacts
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(name)
+            .cloned()
+    }
+    /// The names of all written artifacts, sorted.
+    pub fn artifact_names(&self) -> Vec<String> {
+        self.artifacts
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .keys()
+            .cloned()
+            .collect()
+    }
+}
+
+impl OutputSink for MemorySink {
+    fn message(&self, text: &str) {
+        self.messages
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .push(strip_colors(text));
+    }
+    fn write_artifact(&self, name: &str, bytes: &[u8]) -> io::Result<String> {
+        self.artifacts
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(name.to_string(), bytes.to_vec());
+        Ok("memory".into())
+    }
+}
+
+/// Writes files into a chosen directory, creating it when needed, and hands messages to
+/// another sink, a [`TerminalSink`] by default.
+#[derive(Debug, Clone)]
+pub struct OutputDirSink {
+    dir: PathBuf,
+    messages: Arc<dyn OutputSink>,
+}
+
+impl OutputDirSink {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            messages: Arc::new(TerminalSink),
+        }
+    }
+    /// Sends the messages to the given sink instead of the terminal.
+    pub fn with_messages_to(mut self, sink: Arc<dyn OutputSink>) -> Self {
+        self.messages = sink;
+        self
+    }
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+impl OutputSink for OutputDirSink {
+    fn message(&self, text: &str) {
+        self.messages.message(text);
+    }
+    fn write_artifact(&self, name: &str, bytes: &[u8]) -> io::Result<String> {
+        write_file(&self.dir.join(name), bytes)?;
+        Ok(format!("the directory '{}'", self.dir.display()))
+    }
+    fn artifact_path(&self, name: &str) -> Option<PathBuf> {
+        Some(self.dir.join(name))
+    }
+}
+
+fn write_file(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        create_dir_all(parent)?;
+    }
+    File::create(path)?.write_all(bytes)
+}
+
+/// Removes the ANSI escape sequences `colored` adds to a message.
+pub fn strip_colors(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(ch) = chars.next() {
+        if ch == '\u{1b}' {
+            //Skip the CSI sequence up to and including its final byte
+            for next in chars.by_ref() {
+                if next.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            plain.push(ch);
+        }
+    }
+    plain
+}
+
+/// Formats a message the way `println!` does and hands it to an [`OutputSink`].
+macro_rules! report {
+    ($sink:expr) => {
+        $sink.message("")
+    };
+    ($sink:expr, $($arg:tt)*) => {
+        $sink.message(&format!($($arg)*))
+    };
+}
+pub(crate) use report;
@@ -0,0 +1,35 @@
+use std::pin::Pin;
+
+use futures_util::stream::{self, Stream, StreamExt, TryStreamExt};
+
+use crate::error::Error;
+
+/// The items of a list or describe call, fetched page by page as the stream is polled.
+///
+/// Continuation tokens are followed until the service stops returning one. A failed page
+/// is yielded as an `Err` and ends the stream. Use [`futures_util::StreamExt`] or
+/// [`tokio_stream::StreamExt`] to consume it, for example with `next().await`.
+pub type ItemStream<T> = Pin<Box<dyn Stream<Item = Result<T, Error>> + Send>>;
+
+/// Turns the page stream of an SDK paginator into a stream of the items on each page.
+pub(crate) fn items_of_pages<P, T, I>(
+    pages: impl Stream<Item = Result<P, Error>> + Send + 'static,
+    mut items_of: impl FnMut(P) -> I + Send + 'static,
+) -> ItemStream<T>
+where
+    I: IntoIterator<Item = T>,
+    T: Send + 'static,
+{
+    Box::pin(pages.flat_map(move |page| {
+        let items: Vec<Result<T, Error>> = match page {
+            Ok(page) => items_of(page).into_iter().map(Ok).collect(),
+            Err(err) => vec![Err(err)],
+        };
+        stream::iter(items)
+    }))
+}
+
+/// Drains an [`ItemStream`], returning the first error it yields.
+pub(crate) async fn collect_items<T>(items: ItemStream<T>) -> Result<Vec<T>, Error> {
+    items.try_collect().await
+}
@@ -7,10 +7,14 @@ use genpdf::{
 };
 use printpdf;
 use printpdf::types::plugins::graphics::two_dimensional::font::BuiltinFont;
-use regex::Regex;
-use std::{
-    fs::{create_dir, read_dir, remove_dir_all, OpenOptions},
-    io::Write,
+use std::{fs::read_dir, io::Cursor, path::Path};
+
+use crate::{
+    bucket_usage::{BucketUsageReport, UsageTotals},
+    error::{Error, SdkResultExt, Service},
+    key_paths::KeyPathMapper,
+    output::{report, OutputSink},
+    progress::format_bytes,
 };
 pub fn build_document() -> Document {
     let builtin_font = Some(BuiltinFont::HelveticaBold);
@@ -50,6 +54,30 @@ pub fn document_configuration(document: &mut Document, title: &str, page_title:
     document.set_page_decorator(page_decorator);
     document.set_paper_size(PaperSize::A4);
 }
+/// Renders the document in memory and hands it to the sink, returning where it was written.
+fn render_pdf(
+    document: Document,
+    pdf_name: &str,
+    sink: &dyn OutputSink,
+    operation: &'static str,
+) -> Result<String, Error> {
+    let mut bytes = Vec::new();
+    document
+        .render(&mut bytes)
+        .map_err(|err| Error::other(operation, err))?;
+    sink.write_artifact(pdf_name, &bytes)
+        .map_err(|err| Error::io(operation, pdf_name, err))
+}
+//genpdf decodes images with its own version of the image crate, so the resized image is
+//handed over as PNG bytes
+fn image_element(image: image::DynamicImage, operation: &'static str) -> Result<Image, Error> {
+    let mut png = Cursor::new(Vec::new());
+    image
+        .write_to(&mut png, image::ImageOutputFormat::Png)
+        .map_err(|err| Error::other(operation, err))?;
+    png.set_position(0);
+    Image::from_reader(png).map_err(|err| Error::other(operation, err))
+}
 pub fn create_table(key: &str, value: &str) -> TableLayout {
     let mut table = TableLayout::new(vec![1, 1]);
     table.set_cell_decorator(FrameCellDecorator::new(true, true, false));
@@ -79,7 +107,8 @@ pub fn create_email_pdf(
     emails_with_status: Vec<String>,
     contact_list_name: &str,
     region_name: &str,
-) {
+    sink: &dyn OutputSink,
+) -> Result<(), Error> {
     let mut table = create_table("Identity Info", "Identity Values");
     push_table_data_emails(headers, emails_with_status, &mut table);
     let mut document = build_document();
@@ -97,19 +126,14 @@ pub fn create_email_pdf(
     );
     document.push(Break::new(1.0));
     document.push(table);
-    match document.render_to_file("EmailsList.pdf") {
-        Ok(_) => println!(
-            "The '{}' is also generated with the name {} in the current directory\n",
-            "PDF".green().bold(),
-            "'EmailsList.pdf'".green().bold()
-        ),
-        Err(_) => println!(
-            "{}\n",
-            "Error while generating Email List 'PDF'"
-                .bright_red()
-                .bold()
-        ),
-    }
+    let location = render_pdf(document, "EmailsList.pdf", sink, "create_email_pdf")?;
+    report!(
+        sink,
+        "The '{}' is also generated with the name {} in {location}\n",
+        "PDF".green().bold(),
+        "'EmailsList.pdf'".green().bold()
+    );
+    Ok(())
 }
 fn push_table_data_emails(
     headers: &Vec<&str>,
@@ -146,13 +170,14 @@ fn push_table_data_emails(
         }
     }
 }
-//create tempdir/ before calling this function.
 pub async fn create_celebrity_pdf(
     face_info: Vec<String>,
     predictions: Vec<String>,
     local_image_path: Option<&str>,
     (bucket_name, key_image_name): (Option<&str>, Option<&str>),
-) {
+    sink: &dyn OutputSink,
+) -> Result<(), Error> {
+    let image_err = |err| Error::other("create_celebrity_pdf", err);
     let mut table = create_table("Celebrity Information", "Predictions");
     push_table_data_celebrity_results(face_info, predictions, &mut table);
     let mut document = build_document();
@@ -183,7 +208,7 @@ pub async fn create_celebrity_pdf(
                         .styled(Style::new().with_color(Color::Rgb(0, 128, 0)).bold()),
                 );
             }
-            _ => println!("This shouldn't have happened\n"),
+            _ => report!(sink, "This shouldn't have happened\n"),
         },
     }
 
@@ -191,52 +216,36 @@ pub async fn create_celebrity_pdf(
     match local_image_path {
         Some(local_image) => {
             let image = image::open(&local_image)
-                .expect("Error while opening the image\n")
+                .map_err(image_err)?
                 .resize_to_fill(800, 800, image::imageops::FilterType::Gaussian);
-            create_dir("tempdir/").expect("Error while creating tempdir for resizing the image");
-            image
-                .save("tempdir/image.png")
-                .expect("Error while writing Image to tempdir\n");
             document.push(
-                Image::from_path("tempdir/image.png")
-                    .expect("Error reading the image path\n")
-                    .with_alignment(Alignment::Center),
+                image_element(image, "create_celebrity_pdf")?.with_alignment(Alignment::Center),
             );
             document.push(table);
-            let file_name_pattern =
-                Regex::new(r#"[^\\/:\*\?"<>\|]+$"#).expect("Error while parsing Regex Syntax\n");
-            let file_name = file_name_pattern
-                .find_iter(local_image)
-                .map(|as_str| as_str.as_str())
-                .collect::<Vec<&str>>();
-            let mut file_name = file_name.join("");
-            if file_name.starts_with("/") {
-                file_name.remove(0);
-            };
+            let file_name = Path::new(local_image)
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
             let pdf_name = format!("CelebrityResults_{}.pdf", file_name);
-            match document.render_to_file(&pdf_name) {
-                Ok(_) => println!(
-                    "The '{}' is generated with the name {} in the current directory\n",
+            match render_pdf(document, &pdf_name, sink, "create_celebrity_pdf") {
+                Ok(location) => report!(
+                    sink,
+                    "The '{}' is generated with the name {} in {location}\n",
                     "PDF".green().bold(),
                     pdf_name.green().bold()
                 ),
-                Err(_) => {
-                    println!(
+                Err(err) => {
+                    report!(
+                        sink,
                         "{}",
                         "The file name below is causing problems during the PDF generation process"
                             .yellow()
                             .bold()
                     );
-                    println!("{}\n", file_name.blue().bold());
-                    println!(
-                        "{}\n",
-                        "Error while generating Celebrity Results 'PDF'"
-                            .bright_red()
-                            .bold()
-                    );
+                    report!(sink, "{}\n", file_name.blue().bold());
+                    return Err(err);
                 }
             }
-            remove_dir_all("tempdir/").expect("Error while deleting tempdir/");
         }
         None => {
             if let (Some(bucket_name), Some(key_image_name)) = (bucket_name, key_image_name) {
@@ -248,95 +257,67 @@ pub async fn create_celebrity_pdf(
                     .key(key_image_name)
                     .send()
                     .await
-                    .expect("Error while Getting Object for specified Bucket and Key Name\n");
-                let have_slash_and_dot_pattern =
-                    Regex::new(r#"([^./]+)\.([^/]+)"#).expect("Error while parsing Regex Syntax\n");
-                let have_slash_but_no_extension_pattern =
-                    Regex::new(r#"/([^/]+)$"#).expect("Error while parsing regex syntax");
-                let no_slash_no_dot_pattern =
-                    Regex::new(r#"^[^./]*$"#).expect("Error while parsing Regex syntax");
-                let file_name: Vec<String> = if have_slash_and_dot_pattern.is_match(key_image_name)
-                {
-                    have_slash_and_dot_pattern
-                        .find_iter(key_image_name)
-                        .map(|string| string.as_str().to_string())
-                        .collect()
-                } else if have_slash_but_no_extension_pattern.is_match(key_image_name) {
-                    have_slash_but_no_extension_pattern
-                        .find_iter(key_image_name)
-                        .map(|string| string.as_str().to_string())
-                        .collect()
-                } else {
-                    no_slash_no_dot_pattern
-                        .find_iter(key_image_name)
-                        .map(|string| string.as_str().to_string())
-                        .collect()
-                };
-                let mut file_name = file_name.join("");
-                if file_name.starts_with("/") {
-                    file_name.remove(0);
-                };
-                let mut file = OpenOptions::new()
-                    .create(true)
-                    .read(true)
-                    .write(true)
-                    .open(&file_name)
-                    .expect("Error while creating file\n");
+                    .map_sdk_err(Service::S3, "get_object")?;
+                let image_path = KeyPathMapper::new("")
+                    .local_path(key_image_name)
+                    .ok_or_else(|| {
+                        Error::invalid_input(
+                            "create_celebrity_pdf",
+                            format!("'{key_image_name}' doesn't map to a file name"),
+                        )
+                    })?;
+                let file_name = image_path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                let image_path = image_path.to_string_lossy().into_owned();
                 let bytes = output
                     .body
                     .collect()
                     .await
-                    .expect("Error While Getting Bytes\n")
+                    .map_err(|err| Error::io("create_celebrity_pdf", key_image_name, err.into()))?
                     .into_bytes();
-                file.write_all(&*bytes)
-                    .expect("Error while writing bytes\n");
-                let image = image::open(&file_name)
-                    .expect("Error while opening the image\n")
+                sink.write_artifact(&image_path, &bytes)
+                    .map_err(|err| Error::io("create_celebrity_pdf", &image_path, err))?;
+                let image = image::load_from_memory(&bytes)
+                    .map_err(image_err)?
                     .resize_to_fill(800, 800, image::imageops::FilterType::Gaussian);
-                create_dir("tempdir/")
-                    .expect("Error while creating tempdir for resizing the image");
-                image
-                    .save("tempdir/image.png")
-                    .expect("Error while writing Image to tempdir\n");
                 document.push(
-                    Image::from_path("tempdir/image.png")
-                        .expect("Error reading the image path\n")
-                        .with_alignment(Alignment::Center),
+                    image_element(image, "create_celebrity_pdf")?.with_alignment(Alignment::Center),
                 );
-                remove_dir_all("tempdir/").expect("Error while deleting tempdir/");
                 document.push(table);
                 let pdf_name = format!("CelebrityResults_{}.pdf", file_name);
-                match document.render_to_file(&pdf_name) {
-                    Ok(_) => println!(
-                        "The '{}' is generated with the name {} in the current directory\n",
+                match render_pdf(document, &pdf_name, sink, "create_celebrity_pdf") {
+                    Ok(location) => report!(
+                        sink,
+                        "The '{}' is generated with the name {} in {location}\n",
                         "PDF".green().bold(),
                         pdf_name.green().bold()
                     ),
-                    Err(_) => {
-                        println!(
+                    Err(err) => {
+                        report!(
+                        sink,
                         "{}",
                         "The file name below is causing problems during the PDF generation process"
                             .yellow()
                             .bold()
                     );
-                        println!("{}\n", file_name.blue().bold());
-                        println!(
-                            "{}\n",
-                            "Error while generating Celebrity Results 'PDF'"
-                                .bright_red()
-                                .bold()
-                        );
+                        report!(sink, "{}\n", file_name.blue().bold());
+                        return Err(err);
                     }
                 }
             }
         }
     }
+    Ok(())
 }
 pub async fn create_celebrity_single_pdf(
     local_image_dir: Option<&str>,
     entries: Option<Vec<String>>,
     bucket_name: Option<&str>,
-) {
+    sink: &dyn OutputSink,
+) -> Result<(), Error> {
+    let image_err = |err| Error::other("create_celebrity_single_pdf", err);
     let mut document = build_document();
     document_configuration(
         &mut document,
@@ -347,10 +328,10 @@ pub async fn create_celebrity_single_pdf(
 
     match local_image_dir {
         Some(directory) => {
-            let entries = read_dir(directory).expect("Error finding the directory\n");
-            create_dir("tempdir/").expect("Error while creating tempdir/ for resizing images\n");
+            let read_err = |err| Error::io("create_celebrity_single_pdf", directory, err);
+            let entries = read_dir(directory).map_err(read_err)?;
             for entry in entries {
-                let local_image_path = entry.unwrap().file_name();
+                let local_image_path = entry.map_err(read_err)?.file_name();
                 match local_image_path.to_str() {
                     Some(image_path) => {
                         let full_image_path = format!("{directory}/{image_path}");
@@ -360,20 +341,17 @@ pub async fn create_celebrity_single_pdf(
                                 .styled(Style::new().with_color(Color::Rgb(0, 128, 0)).bold()),
                         );
                         let image = image::open(&full_image_path)
-                            .expect("Error while opening the image\n")
+                            .map_err(image_err)?
                             .resize_to_fill(800, 600, image::imageops::FilterType::Gaussian);
-                        image
-                            .save("tempdir/image.png")
-                            .expect("Error while writing Image to tempdir\n");
                         document.push(Break::new(1.0));
                         document.push(
-                            Image::from_path("tempdir/image.png")
-                                .expect("Error reading the image path\n")
+                            image_element(image, "create_celebrity_single_pdf")?
                                 .with_alignment(Alignment::Center),
                         );
 
                         let table_data =
-                            recognize_celebrities(Some(&full_image_path), None, None, 0).await;
+                            recognize_celebrities(Some(&full_image_path), None, None, 0, sink)
+                                .await?;
                         let table = create_table("Celebrity Information", "Predictions");
                         push_each_table_data_celebrity_results_to_a_document(
                             table_data.0,
@@ -382,26 +360,15 @@ pub async fn create_celebrity_single_pdf(
                             &mut document,
                         );
                     }
-                    None => println!("{}\n", "No Image File Name is Found".red().bold()),
+                    None => report!(sink, "{}\n", "No Image File Name is Found".red().bold()),
                 }
             }
-            remove_dir_all("tempdir/").expect("Error while deleting tempdir/");
         }
         None => match bucket_name {
             Some(bucket_name) => {
-                let have_slash_and_dot_pattern =
-                    Regex::new(r#"([^./]+)\.([^/]+)"#).expect("Error while parsing Regex Syntax\n");
-                let have_slash_but_no_extension_pattern =
-                    Regex::new(r#"/([^/]+)$"#).expect("Error while parsing regex syntax");
-                let no_slash_no_dot_pattern =
-                    Regex::new(r#"^[^./]*$"#).expect("Error while parsing Regex syntax");
-                create_dir("tempdir/")
-                    .expect("Error while creating tempdir/ for resizing images\n");
-                create_dir("DownloadedImages/").expect(
-                    "Error while creating DownloadedImages/ for writing images from S3 bucket\n",
-                );
+                let mapper = KeyPathMapper::new("DownloadedImages");
                 let mut count = 0;
-                for key_image_name in entries.unwrap().into_iter() {
+                for key_image_name in entries.unwrap_or_default().into_iter() {
                     let sdk_config = aws_config::load_from_env().await;
                     let client = aws_sdk_s3::Client::new(&sdk_config);
                     let outputs = client
@@ -410,38 +377,18 @@ pub async fn create_celebrity_single_pdf(
                         .key(&key_image_name)
                         .send()
                         .await
-                        .expect("Error while Downloading Content from Given Prefix or Bucket\n");
-                    let file_name: Vec<String> =
-                        if have_slash_and_dot_pattern.is_match(&key_image_name) {
-                            have_slash_and_dot_pattern
-                                .find_iter(&key_image_name)
-                                .map(|string| string.as_str().to_string())
-                                .collect()
-                        } else if have_slash_but_no_extension_pattern.is_match(&key_image_name) {
-                            have_slash_but_no_extension_pattern
-                                .find_iter(&key_image_name)
-                                .map(|string| string.as_str().to_string())
-                                .collect()
-                        } else {
-                            no_slash_no_dot_pattern
-                                .find_iter(&key_image_name)
-                                .map(|string| string.as_str().to_string())
-                                .collect()
-                        };
-                    let mut file_name = file_name.join("");
-                    if file_name.starts_with("/") {
-                        file_name.remove(0);
+                        .map_sdk_err(Service::S3, "get_object")?;
+                    let Some(file_path) = mapper.local_path(&key_image_name) else {
+                        continue;
                     };
-                    let file_path = format!("DownloadedImages/{file_name}");
-                    let bytes = outputs.body.collect().await.unwrap();
+                    let file_path = file_path.to_string_lossy().into_owned();
+                    let bytes = outputs.body.collect().await.map_err(|err| {
+                        Error::io("create_celebrity_single_pdf", &key_image_name, err.into())
+                    })?;
                     let bytes = bytes.into_bytes();
-                    let mut file = OpenOptions::new()
-                        .create(true)
-                        .read(true)
-                        .write(true)
-                        .open(&file_path)
-                        .expect("Error while creating file\n");
-                    file.write_all(&*bytes).unwrap();
+                    sink.write_artifact(&file_path, &bytes).map_err(|err| {
+                        Error::io("create_celebrity_single_pdf", file_path.as_str(), err)
+                    })?;
                     document.push(
                         Paragraph::new(format!("Bucket Name:  {}", bucket_name))
                             .aligned(Alignment::Center)
@@ -453,15 +400,11 @@ pub async fn create_celebrity_single_pdf(
                             .styled(Style::new().with_color(Color::Rgb(0, 128, 0)).bold()),
                     );
                     document.push(Break::new(1.0));
-                    let image = image::open(&file_path)
-                        .expect("Error while opening the image\n")
+                    let image = image::load_from_memory(&bytes)
+                        .map_err(image_err)?
                         .resize_to_fill(800, 600, image::imageops::FilterType::Gaussian);
-                    image
-                        .save("tempdir/image.png")
-                        .expect("Error while writing Image to tempdir\n");
                     document.push(
-                        Image::from_path("tempdir/image.png")
-                            .expect("Error reading the image path\n")
+                        image_element(image, "create_celebrity_single_pdf")?
                             .with_alignment(Alignment::Center),
                     );
 
@@ -470,8 +413,9 @@ pub async fn create_celebrity_single_pdf(
                         Some(&bucket_name),
                         Some(&key_image_name),
                         count,
+                        sink,
                     )
-                    .await;
+                    .await?;
                     count += 1;
                     let table = create_table("Celebrity Information", "Predictions");
                     push_each_table_data_celebrity_results_to_a_document(
@@ -481,34 +425,32 @@ pub async fn create_celebrity_single_pdf(
                         &mut document,
                     );
                 }
-                remove_dir_all("tempdir/").expect("Error while deleting tempdir/");
             }
-            _ => println!("This shouldn't have happened\n"),
+            _ => report!(sink, "This shouldn't have happened\n"),
         },
     }
 
-    match document.render_to_file("CelebrityResults.pdf") {
-        Ok(_) => println!(
-            "The '{}' is generated with the name '{}' in the current directory\n",
-            "PDF".green().bold(),
-            "CelebrityResults.pdf".green().bold(),
-        ),
-        Err(_) => {
-            println!(
-                "{}\n",
-                "Error while generating Celebrity Results 'PDF'"
-                    .bright_red()
-                    .bold()
-            );
-        }
-    }
+    let location = render_pdf(
+        document,
+        "CelebrityResults.pdf",
+        sink,
+        "create_celebrity_single_pdf",
+    )?;
+    report!(
+        sink,
+        "The '{}' is generated with the name '{}' in {location}\n",
+        "PDF".green().bold(),
+        "CelebrityResults.pdf".green().bold(),
+    );
+    Ok(())
 }
 async fn recognize_celebrities(
     local_image_path: Option<&str>,
     bucket_name: Option<&str>,
     image_key_name: Option<&str>,
     count: usize,
-) -> (Vec<String>, Vec<String>) {
+    sink: &dyn OutputSink,
+) -> Result<(Vec<String>, Vec<String>), Error> {
     use aws_sdk_pinpoint::primitives::Blob;
     use aws_sdk_rekognition::types::{Image as S3Image, S3Object};
     use std::io::Read;
@@ -516,10 +458,10 @@ async fn recognize_celebrities(
     let client = aws_sdk_rekognition::Client::new(&config);
     let image = match local_image_path {
         Some(local_image_path) => {
-            let mut file = std::fs::File::open(local_image_path)
-                .expect("Error while reading the path you specified\n");
+            let read_err = |err| Error::io("recognize_celebrities", local_image_path, err);
+            let mut file = std::fs::File::open(local_image_path).map_err(read_err)?;
             let mut vec_of_u8s = Vec::new();
-            file.read_to_end(&mut vec_of_u8s).unwrap();
+            file.read_to_end(&mut vec_of_u8s).map_err(read_err)?;
             let bytes_builder = Blob::new(vec_of_u8s);
             S3Image::builder().bytes(bytes_builder).build()
         }
@@ -537,7 +479,7 @@ async fn recognize_celebrities(
         .image(image)
         .send()
         .await
-        .expect("Error while Recognizing Celebrities\n");
+        .map_sdk_err(Service::Rekognition, "recognize_celebrities")?;
     let headers = vec![
         "Celebrity Name".into(),
         "Unique Celebrity Identifier".into(),
@@ -547,14 +489,9 @@ async fn recognize_celebrities(
     ];
     let mut records = Vec::new();
     let file_path = format!("CelebrityDetails_{count}.txt");
-    let mut file = OpenOptions::new()
-        .create(true)
-        .read(true)
-        .write(true)
-        .open(&file_path)
-        .expect("Error while creating file\n");
+    let mut contents = String::new();
     if let Some(celebrity_faces) = outputs.celebrity_faces {
-        celebrity_faces.into_iter().for_each(|faces| {
+        for faces in celebrity_faces {
             if let Some(name) = faces.name {
                 let famous_for = match name.as_str() {
                     "Ralph Fiennes" => format!("{name}(voldemort)"),
@@ -580,24 +517,24 @@ async fn recognize_celebrities(
                     "Ajith Kumar" => format!("{name}(Ultimate Star)"),
                     _ => format!("{name}"),
                 };
-                println!("Celebrity Name: {}", famous_for.green().bold());
+                report!(sink, "Celebrity Name: {}", famous_for.green().bold());
                 let buf = format!("Celebrity Name: {}\n", famous_for);
-                file.write_all(buf.as_bytes()).unwrap();
+                contents.push_str(&buf);
                 records.push(famous_for);
             }
             if let Some(id) = faces.id {
-                println!("Celebrity Amazon ID: {}", id.green().bold());
+                report!(sink, "Celebrity Amazon ID: {}", id.green().bold());
                 let buf = format!("Celebrity Amazon ID: {}\n", id);
-                file.write_all(buf.as_bytes()).unwrap();
+                contents.push_str(&buf);
                 records.push(id);
             }
             if let Some(gender) = faces.known_gender {
                 let gender_ = gender.r#type;
                 if let Some(genderr) = gender_ {
                     let finall = genderr.as_str().to_string();
-                    println!("Celebrity Gender: {}", finall.green().bold());
+                    report!(sink, "Celebrity Gender: {}", finall.green().bold());
                     let buf = format!("Celebrity Gender: {}\n", finall);
-                    file.write_all(buf.as_bytes()).unwrap();
+                    contents.push_str(&buf);
                     records.push(finall);
                 }
             }
@@ -610,36 +547,39 @@ async fn recognize_celebrities(
                         let format_bbox = format!(
                             "Width: {width:.2},Height: {height:.2},Left: {left:.2},Top: {top:.2}"
                         );
-                        println!(
+                        report!(
+                            sink,
                             "Celebrity Bounding Box Details: {}",
                             format_bbox.green().bold()
                         );
                         let buf = format!("Celebrity Bounding Box Details: {}\n", format_bbox);
-                        file.write_all(buf.as_bytes()).unwrap();
+                        contents.push_str(&buf);
                         bbox_string.push_str(&format_bbox);
                     }
                 }
                 records.push(bbox_string);
                 if let Some(smile) = face.smile {
                     let format_smile = format!("{}", smile.value);
-                    println!("Is Celebrity Smiling?: {}\n", format_smile.green().bold());
+                    report!(
+                        sink,
+                        "Is Celebrity Smiling?: {}\n",
+                        format_smile.green().bold()
+                    );
                     let buf = format!("Is Celebrity Smiling?: {}\n", format_smile);
-                    file.write_all(buf.as_bytes()).unwrap();
+                    contents.push_str(&buf);
                     records.push(format_smile);
                 }
             }
-        });
+        }
     }
-    match std::fs::File::open(file_path) {
-        Ok(_) => println!(
-            "{}\n",
-            "The text file has been successfully written to the current directory"
-                .green()
-                .bold()
-        ),
-        Err(_) => println!("{}\n", "Error while writing File".red().bold()),
+    match sink.write_artifact(&file_path, contents.as_bytes()) {
+        Ok(location) => {
+            let msg = format!("The text file has been successfully written to {location}");
+            report!(sink, "{}\n", msg.green().bold())
+        }
+        Err(_) => report!(sink, "{}\n", "Error while writing File".red().bold()),
     }
-    (headers, records)
+    Ok((headers, records))
 }
 pub fn push_each_table_data_celebrity_results_to_a_document(
     headers: Vec<String>,
@@ -713,7 +653,8 @@ pub fn create_text_result_pdf(
     records: Vec<String>,
     job_id: String,
     (bucket_name, video_name): (String, String),
-) {
+    sink: &dyn OutputSink,
+) -> Result<(), Error> {
     let mut table = create_table("Text Information", "Predictions");
     push_table_data_text_results(headers, records, &mut table);
     let mut document = build_document();
@@ -740,21 +681,21 @@ pub fn create_text_result_pdf(
     );
     document.push(Break::new(1.0));
     document.push(table);
-    match document.render_to_file("Text_Detection_Results.pdf") {
-        Ok(_) => println!(
-            "The '{}' is also generated with the name {} in the current directory\n",
-            "PDF".green().bold(),
-            "'Text_Detection_Results.pdf'".green().bold()
-        ),
-        Err(_) => println!(
-            "{}\n",
-            "Error while generating Text Detection Results 'PDF'"
-                .bright_red()
-                .bold()
-        ),
-    }
+    let location = render_pdf(
+        document,
+        "Text_Detection_Results.pdf",
+        sink,
+        "create_text_result_pdf",
+    )?;
+    report!(
+        sink,
+        "The '{}' is also generated with the name {} in {location}\n",
+        "PDF".green().bold(),
+        "'Text_Detection_Results.pdf'".green().bold()
+    );
+    Ok(())
 }
-pub fn create_text_only_pdf(texts: Vec<String>) {
+pub fn create_text_only_pdf(texts: Vec<String>, sink: &dyn OutputSink) -> Result<(), Error> {
     let mut document = build_document();
     document_configuration(
         &mut document,
@@ -775,21 +716,25 @@ pub fn create_text_only_pdf(texts: Vec<String>) {
                 .styled(Style::new().with_color(Color::Rgb(0, 128, 0)).bold()),
         );
     }
-    match document.render_to_file("TextDetectionResultsTextOnly.pdf") {
-        Ok(_) => println!(
-            "The '{}' is also generated with the name {} in the current directory\n",
-            "PDF".green().bold(),
-            "'TextDetectionResultsTextOnly.pdf'".green().bold()
-        ),
-        Err(_) => println!(
-            "{}\n",
-            "Error while generating TextDetectionResultsTextOnly 'PDF'"
-                .bright_red()
-                .bold()
-        ),
-    }
+    let location = render_pdf(
+        document,
+        "TextDetectionResultsTextOnly.pdf",
+        sink,
+        "create_text_only_pdf",
+    )?;
+    report!(
+        sink,
+        "The '{}' is also generated with the name {} in {location}\n",
+        "PDF".green().bold(),
+        "'TextDetectionResultsTextOnly.pdf'".green().bold()
+    );
+    Ok(())
 }
-pub fn create_detect_face_image_pdf(bucket_name: &str, path_prefix: &str) {
+pub fn create_detect_face_image_pdf(
+    bucket_name: &str,
+    path_prefix: &str,
+    sink: &dyn OutputSink,
+) -> Result<(), Error> {
     let mut document = build_document();
     document_configuration(&mut document, "DetectFaces", "Result of DetectFaces");
     document.push(Break::new(1.0));
@@ -805,26 +750,27 @@ pub fn create_detect_face_image_pdf(bucket_name: &str, path_prefix: &str) {
             .styled(Style::new().with_color(Color::Rgb(0, 128, 0)).bold()),
     );
     document.push(Break::new(1.0));
-    push_images_into_document(&mut document);
-    match document.render_to_file("DetectFaces.pdf") {
-        Ok(_) => println!(
-            "The '{}' is also generated with the name {} in the current directory\n",
-            "PDF".green().bold(),
-            "'DetectFaces.pdf'".green().bold()
-        ),
-        Err(_) => println!(
-            "{}\n",
-            "Error while generating DetectFaces 'PDF'"
-                .bright_red()
-                .bold()
-        ),
-    }
+    push_images_into_document(&mut document, sink)?;
+    let location = render_pdf(
+        document,
+        "DetectFaces.pdf",
+        sink,
+        "create_detect_face_image_pdf",
+    )?;
+    report!(
+        sink,
+        "The '{}' is also generated with the name {} in {location}\n",
+        "PDF".green().bold(),
+        "'DetectFaces.pdf'".green().bold()
+    );
+    Ok(())
 }
-fn push_images_into_document(document: &mut Document) {
+fn push_images_into_document(document: &mut Document, sink: &dyn OutputSink) -> Result<(), Error> {
     let face_image_dir = "compressed_images/";
-    let entries = read_dir(face_image_dir).expect("No compressed_images/ DIR is exist\n");
+    let read_err = |err| Error::io("create_detect_face_image_pdf", face_image_dir, err);
+    let entries = read_dir(face_image_dir).map_err(read_err)?;
     for path in entries.into_iter() {
-        let path = path.unwrap();
+        let path = path.map_err(read_err)?;
         match path.file_name().to_str() {
             Some(image_name) => {
                 let image_path = format!("compressed_images/{}", image_name);
@@ -836,21 +782,23 @@ fn push_images_into_document(document: &mut Document) {
                 document.push(Break::new(1.0));
                 document.push(
                     Image::from_path(image_path)
-                        .expect("Unable to Load Image")
+                        .map_err(|err| Error::other("create_detect_face_image_pdf", err))?
                         .with_alignment(Alignment::Center),
                 );
                 document.push(Break::new(2));
             }
-            None => println!("Error while Walking the Directory\n"),
+            None => report!(sink, "Error while Walking the Directory\n"),
         }
     }
+    Ok(())
 }
 pub fn create_face_result_pdf(
     headers: &Vec<&str>,
     records: Vec<String>,
     job_id: &str,
     (bucket_name, video_name): (String, String),
-) {
+    sink: &dyn OutputSink,
+) -> Result<(), Error> {
     let mut table = create_table("Face Information", "Predictions");
     push_table_data_face_results(headers, records, &mut table);
     let mut document = build_document();
@@ -877,19 +825,19 @@ pub fn create_face_result_pdf(
     );
     document.push(Break::new(1.0));
     document.push(table);
-    match document.render_to_file("Face_Detection_Results.pdf") {
-        Ok(_) => println!(
-            "The '{}' is also generated with the name {} in the current directory\n",
-            "PDF".green().bold(),
-            "'Face_Detection_Results.pdf'".green().bold()
-        ),
-        Err(_) => println!(
-            "{}\n",
-            "Error while generating face Detection Results 'PDF'"
-                .bright_red()
-                .bold()
-        ),
-    }
+    let location = render_pdf(
+        document,
+        "Face_Detection_Results.pdf",
+        sink,
+        "create_face_result_pdf",
+    )?;
+    report!(
+        sink,
+        "The '{}' is also generated with the name {} in {location}\n",
+        "PDF".green().bold(),
+        "'Face_Detection_Results.pdf'".green().bold()
+    );
+    Ok(())
 }
 fn push_table_data_text_results(
     headers: &Vec<&str>,
@@ -955,7 +903,10 @@ fn push_table_data_face_results(
         }
     }
 }
-pub fn create_translated_text_pdf(translated_texts: String) {
+pub fn create_translated_text_pdf(
+    translated_texts: String,
+    sink: &dyn OutputSink,
+) -> Result<(), Error> {
     let mut document = build_document();
     document_configuration(&mut document, "Translation", "Result of Translate Text");
     document.push(Break::new(1.0));
@@ -964,21 +915,25 @@ pub fn create_translated_text_pdf(translated_texts: String) {
             .aligned(Alignment::Left)
             .styled(Style::new().with_color(Color::Rgb(0, 128, 0)).bold()),
     );
-    match document.render_to_file("TranslatedText.pdf") {
-        Ok(_) => println!(
-            "The '{}' is also generated with the name {} in the current directory\n",
-            "PDF".green().bold(),
-            "'TranslatedText.pdf'".green().bold()
-        ),
-        Err(_) => println!(
-            "{}\n",
-            "Error while generating TranslatedText 'PDF'"
-                .bright_red()
-                .bold()
-        ),
-    }
+    let location = render_pdf(
+        document,
+        "TranslatedText.pdf",
+        sink,
+        "create_translated_text_pdf",
+    )?;
+    report!(
+        sink,
+        "The '{}' is also generated with the name {} in {location}\n",
+        "PDF".green().bold(),
+        "'TranslatedText.pdf'".green().bold()
+    );
+    Ok(())
 }
-pub fn create_translation_language_details_pdf(lang_codes: Vec<String>, lang_names: Vec<String>) {
+pub fn create_translation_language_details_pdf(
+    lang_codes: Vec<String>,
+    lang_names: Vec<String>,
+    sink: &dyn OutputSink,
+) -> Result<(), Error> {
     let mut table = create_table("Languge Name", "Language Code");
     let mut document = build_document();
     document_configuration(
@@ -995,19 +950,19 @@ pub fn create_translation_language_details_pdf(lang_codes: Vec<String>, lang_nam
         .unwrap();
     push_lang_info_into_table(lang_codes, lang_names, &mut table);
     document.push(table);
-    match document.render_to_file("ListLanguages.pdf") {
-        Ok(_) => println!(
-            "The '{}' is also generated with the name {} in the current directory\n",
-            "PDF".green().bold(),
-            "'ListLanguages.pdf'".green().bold()
-        ),
-        Err(_) => println!(
-            "{}\n",
-            "Error while generating ListLanguages 'PDF'"
-                .bright_red()
-                .bold()
-        ),
-    }
+    let location = render_pdf(
+        document,
+        "ListLanguages.pdf",
+        sink,
+        "create_translation_language_details_pdf",
+    )?;
+    report!(
+        sink,
+        "The '{}' is also generated with the name {} in {location}\n",
+        "PDF".green().bold(),
+        "'ListLanguages.pdf'".green().bold()
+    );
+    Ok(())
 }
 fn push_lang_info_into_table(headers: Vec<String>, values: Vec<String>, table: &mut TableLayout) {
     for (record, header) in values.into_iter().zip(headers.into_iter()) {
@@ -1031,7 +986,8 @@ pub fn create_email_identities_pdf(
     headers: &Vec<&str>,
     identities: Vec<String>,
     region_name: &str,
-) {
+    sink: &dyn OutputSink,
+) -> Result<(), Error> {
     let mut table = create_table("Identity Info", "Values");
     push_table_data_emails_identies(headers, identities, &mut table);
     let mut document = build_document();
@@ -1048,19 +1004,19 @@ pub fn create_email_identities_pdf(
     );
     document.push(Break::new(1.0));
     document.push(table);
-    match document.render_to_file("EmailIdentitiesInfo.pdf") {
-        Ok(_) => println!(
-            "The '{}' is also generated with the name {} in the current directory\n",
-            "PDF".green().bold(),
-            "'EmailIdentitiesInfo.pdf'".green().bold()
-        ),
-        Err(_) => println!(
-            "{}\n",
-            "Error while generating Text Detection Results 'PDF'"
-                .bright_red()
-                .bold()
-        ),
-    }
+    let location = render_pdf(
+        document,
+        "EmailIdentitiesInfo.pdf",
+        sink,
+        "create_email_identities_pdf",
+    )?;
+    report!(
+        sink,
+        "The '{}' is also generated with the name {} in {location}\n",
+        "PDF".green().bold(),
+        "'EmailIdentitiesInfo.pdf'".green().bold()
+    );
+    Ok(())
 }
 pub fn push_table_data_emails_identies(
     headers: &Vec<&str>,
@@ -1094,7 +1050,11 @@ pub fn push_table_data_emails_identies(
         }
     }
 }
-pub fn create_polly_voice_info_pdf(headers: Vec<&str>, values: Vec<String>) {
+pub fn create_polly_voice_info_pdf(
+    headers: Vec<&str>,
+    values: Vec<String>,
+    sink: &dyn OutputSink,
+) -> Result<(), Error> {
     let mut table = create_table("Voices Info", "Values");
     push_voice_info_into_table(headers, values, &mut table);
     let mut document = build_document();
@@ -1106,19 +1066,19 @@ pub fn create_polly_voice_info_pdf(headers: Vec<&str>, values: Vec<String>) {
     document.push(Break::new(1.0));
     document.push(Break::new(1.0));
     document.push(table);
-    match document.render_to_file("VoicesInfo.pdf") {
-        Ok(_) => println!(
-            "The '{}' is also generated with the name {} in the current directory\n",
-            "PDF".green().bold(),
-            "'VoicesInfo.pdf'".green().bold()
-        ),
-        Err(_) => println!(
-            "{}\n",
-            "Error while generating Text Detection Results 'PDF'"
-                .bright_red()
-                .bold()
-        ),
-    }
+    let location = render_pdf(
+        document,
+        "VoicesInfo.pdf",
+        sink,
+        "create_polly_voice_info_pdf",
+    )?;
+    report!(
+        sink,
+        "The '{}' is also generated with the name {} in {location}\n",
+        "PDF".green().bold(),
+        "'VoicesInfo.pdf'".green().bold()
+    );
+    Ok(())
 }
 pub fn push_voice_info_into_table(
     headers: Vec<&str>,
@@ -1152,3 +1112,123 @@ pub fn push_voice_info_into_table(
         }
     }
 }
+/// Renders a [`BucketUsageReport`] as `BucketUsage.pdf`, with a table for each of its sections
+pub fn create_bucket_usage_pdf(
+    usage: &BucketUsageReport,
+    sink: &dyn OutputSink,
+) -> Result<(), Error> {
+    let mut document = build_document();
+    document_configuration(
+        &mut document,
+        "Bucket Usage",
+        &format!("Storage Used by s3://{}/{}", usage.bucket(), usage.prefix()),
+    );
+    document.push(Break::new(1.0));
+    document.push(
+        Paragraph::new(format!(
+            "{} objects, {} in total, listed at {}",
+            usage.totals().objects(),
+            format_bytes(usage.totals().bytes() as f64),
+            usage.generated_at()
+        ))
+        .aligned(Alignment::Left)
+        .styled(Style::new().with_color(Color::Rgb(0, 128, 0)).bold()),
+    );
+
+    let mut table = create_table("Storage Class", "Usage");
+    for (class, totals) in usage.storage_classes() {
+        push_usage_row(&mut table, class, usage_of(*totals));
+    }
+    push_usage_section(&mut document, "By Storage Class", table);
+
+    let mut table = create_table("Prefix", "Usage");
+    for prefix in usage.prefixes() {
+        let name = match prefix.prefix() {
+            "" => "(top level)",
+            name => name,
+        };
+        push_usage_row(&mut table, name, usage_of(prefix.totals()));
+        for (class, totals) in prefix.storage_classes() {
+            push_usage_row(&mut table, &format!("  {class}"), usage_of(*totals));
+        }
+    }
+    push_usage_section(
+        &mut document,
+        &format!("By Prefix (depth {})", usage.prefix_depth()),
+        table,
+    );
+
+    let mut table = create_table("Last Modified", "Usage");
+    for age in usage.ages() {
+        push_usage_row(&mut table, age.label(), usage_of(age.totals()));
+    }
+    push_usage_section(&mut document, "By Age", table);
+
+    let mut table = create_table("Key", "Size");
+    for object in usage.largest_objects() {
+        let size = format!(
+            "{} ({}, {})",
+            format_bytes(object.size() as f64),
+            object.storage_class(),
+            object.last_modified().unwrap_or("unknown")
+        );
+        push_usage_row(&mut table, object.key(), size);
+    }
+    push_usage_section(&mut document, "Largest Objects", table);
+
+    if !usage.incomplete_uploads().is_empty() {
+        let mut table = create_table("Key", "Uploaded Parts");
+        for upload in usage.incomplete_uploads() {
+            let parts = format!(
+                "{} parts, {} (started {})",
+                upload.parts(),
+                format_bytes(upload.bytes() as f64),
+                upload.initiated().unwrap_or("unknown")
+            );
+            push_usage_row(&mut table, upload.key(), parts);
+        }
+        push_usage_section(&mut document, "Incomplete Multipart Uploads", table);
+    }
+
+    let location = render_pdf(document, "BucketUsage.pdf", sink, "create_bucket_usage_pdf")?;
+    report!(
+        sink,
+        "The '{}' is also generated with the name {} in {location}\n",
+        "PDF".green().bold(),
+        "'BucketUsage.pdf'".green().bold()
+    );
+    Ok(())
+}
+fn usage_of(totals: UsageTotals) -> String {
+    format!(
+        "{} objects, {}",
+        totals.objects(),
+        format_bytes(totals.bytes() as f64)
+    )
+}
+fn push_usage_section(document: &mut Document, heading: &str, table: TableLayout) {
+    document.push(Break::new(1.0));
+    document.push(
+        Paragraph::new(heading)
+            .aligned(Alignment::Center)
+            .styled(Style::new().with_color(Color::Rgb(208, 97, 0)).bold()),
+    );
+    document.push(Break::new(1.0));
+    document.push(table);
+}
+fn push_usage_row(table: &mut TableLayout, name: &str, value: String) {
+    table
+        .row()
+        .element(
+            Paragraph::new(name)
+                .aligned(Alignment::Center)
+                .styled(Style::new().with_color(Color::Rgb(34, 91, 247)).bold()),
+        )
+        .element(
+            Paragraph::new(value)
+                .aligned(Alignment::Center)
+                .styled(Style::new().with_color(Color::Rgb(208, 97, 0)).bold()),
+        )
+        .push()
+        .unwrap();
+}
@@ -1,27 +1,30 @@
+use crate::error::{Error, SdkResultExt, Service};
 use aws_config::SdkConfig;
 use aws_sdk_pinpoint::{
     types::{CreateApplicationRequest, EmailTemplateRequest, NumberValidateRequest},
     Client as PinPointClient,
 };
 
-pub struct PinPointOps<'a> {
-    config: &'a SdkConfig,
+#[derive(Debug, Clone)]
+pub struct PinPointOps {
+    client: PinPointClient,
 }
-impl<'a> PinPointOps<'a> {
-    pub fn build(config: &'a SdkConfig) -> Self {
-        Self { config }
+impl PinPointOps {
+    pub fn build(config: &SdkConfig) -> Self {
+        Self {
+            client: PinPointClient::new(config),
+        }
     }
-    pub async fn create_app(&self, app_name: &str) -> ApplicationResponse {
-        let client = PinPointClient::new(self.config);
-
+    pub async fn create_app(&self, app_name: &str) -> Result<ApplicationResponse, Error> {
         let create_request_builder = CreateApplicationRequest::builder().name(app_name).build();
 
-        let output = client
+        let output = self
+            .client
             .create_app()
             .create_application_request(create_request_builder)
             .send()
             .await
-            .expect("Error while creating app in pinpoint\n");
+            .map_sdk_err(Service::Pinpoint, "create_app")?;
         let response = output.application_response;
         let mut application_response = ApplicationResponse::default();
 
@@ -33,7 +36,7 @@ impl<'a> PinPointOps<'a> {
             application_response =
                 ApplicationResponse::build_application_response(arn, id, name, creation_date)
         }
-        application_response
+        Ok(application_response)
     }
 
     pub async fn create_email_template(
@@ -43,22 +46,21 @@ impl<'a> PinPointOps<'a> {
         default_values: &str,
         subject: &str,
         template_description: &str,
-    ) -> (Option<String>, Option<String>) {
-        let client = PinPointClient::new(self.config);
-
+    ) -> Result<(Option<String>, Option<String>), Error> {
         let email_template_builder = EmailTemplateRequest::builder()
             .default_substitutions(default_values)
             .html_part(template_data)
             .subject(subject)
             .template_description(template_description)
             .build();
-        let output = client
+        let output = self
+            .client
             .create_email_template()
             .template_name(template_name)
             .email_template_request(email_template_builder)
             .send()
             .await
-            .expect("Error while creating Email Template\n");
+            .map_sdk_err(Service::Pinpoint, "create_email_template")?;
         let msg_body = output.create_template_message_body;
         let mut message: Option<String> = None;
         let mut arn: Option<String> = None;
@@ -66,22 +68,25 @@ impl<'a> PinPointOps<'a> {
             message = msg_body_.message;
             arn = msg_body_.arn;
         }
-        (message, arn)
+        Ok((message, arn))
     }
-    pub async fn phone_number_validate(&self, phone_number: &str, iso_code: &str) {
-        let client = PinPointClient::new(self.config);
-
+    pub async fn phone_number_validate(
+        &self,
+        phone_number: &str,
+        iso_code: &str,
+    ) -> Result<(), Error> {
         let phone_number_builder = NumberValidateRequest::builder()
             .phone_number(phone_number)
             .iso_country_code(iso_code)
             .build();
 
-        client
+        self.client
             .phone_number_validate()
             .number_validate_request(phone_number_builder)
             .send()
             .await
-            .expect("Error while validating PhoneNumber");
+            .map_sdk_err(Service::Pinpoint, "phone_number_validate")?;
+        Ok(())
     }
 }
 
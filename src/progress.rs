@@ -0,0 +1,361 @@
+use std::{
+    fmt,
+    io::{self, Write},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        mpsc::{channel, Receiver, Sender},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+//Updates closer together than this are dropped, except the first and the last
+const UPDATE_INTERVAL: Duration = Duration::from_millis(100);
+const BAR_WIDTH: usize = 30;
+
+/// Receives the progress of S3 uploads, downloads and copies, set with
+/// [`S3Ops::with_progress`](crate::S3Ops::with_progress).
+///
+/// Each transfer reports when it starts, at most ten times a second while it runs, and once
+/// more when it completes or fails. Multipart uploads and copies advance a part at a time,
+/// downloads as the data arrives, and single-request uploads only when they start and end.
+/// The listener is called from the task running the transfer, so it should return quickly.
+pub trait ProgressListener: fmt::Debug + Send + Sync {
+    fn on_progress(&self, progress: &TransferProgress);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferDirection {
+    Upload,
+    Download,
+    /// A copy made within S3, which moves no data through this machine.
+    Copy,
+}
+
+impl TransferDirection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Upload => "upload",
+            Self::Download => "download",
+            Self::Copy => "copy",
+        }
+    }
+}
+
+/// A snapshot of one transfer.
+#[derive(Debug, Clone)]
+pub struct TransferProgress {
+    direction: TransferDirection,
+    bucket: String,
+    key: String,
+    bytes_done: u64,
+    total_bytes: u64,
+    //Bytes that were already there when a resumed transfer started, which don't count
+    //towards its throughput
+    resumed_bytes: u64,
+    elapsed: Duration,
+    failed: bool,
+}
+
+impl TransferProgress {
+    pub fn direction(&self) -> TransferDirection {
+        self.direction
+    }
+    pub fn bucket(&self) -> &str {
+        &self.bucket
+    }
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+    pub fn bytes_done(&self) -> u64 {
+        self.bytes_done
+    }
+    pub fn total_bytes(&self) -> u64 {
+        self.total_bytes
+    }
+    /// How much is done, from 0.0 to 1.0.
+    pub fn fraction(&self) -> f64 {
+        match self.total_bytes {
+            0 => 1.0,
+            total => self.bytes_done.min(total) as f64 / total as f64,
+        }
+    }
+    /// The time since this call started the transfer.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+    /// The average speed so far in bytes per second, counting only what this call moved.
+    pub fn throughput(&self) -> f64 {
+        match self.elapsed.as_secs_f64() {
+            secs if secs > 0.0 => self.bytes_done.saturating_sub(self.resumed_bytes) as f64 / secs,
+            _ => 0.0,
+        }
+    }
+    /// The time left at the current throughput, or `None` before anything was moved.
+    pub fn eta(&self) -> Option<Duration> {
+        let throughput = self.throughput();
+        (throughput > 0.0).then(|| {
+            Duration::from_secs_f64(
+                self.total_bytes.saturating_sub(self.bytes_done) as f64 / throughput,
+            )
+        })
+    }
+    pub fn is_complete(&self) -> bool {
+        !self.failed && self.bytes_done >= self.total_bytes
+    }
+    /// Whether the transfer stopped with an error; it's the last update the transfer sends.
+    pub fn is_failed(&self) -> bool {
+        self.failed
+    }
+}
+
+/// Draws a progress bar for each transfer on stderr, redrawing it in place:
+///
+/// ```text
+/// upload reports/2023.csv [###########...................]  37%  45.0 MiB/120.0 MiB  9.8 MiB/s  ETA 0:07
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TerminalProgress;
+
+impl ProgressListener for TerminalProgress {
+    fn on_progress(&self, progress: &TransferProgress) {
+        let filled = (progress.fraction() * BAR_WIDTH as f64) as usize;
+        let eta = match progress.eta() {
+            Some(eta) if !progress.is_complete() => format!("  ETA {}", format_duration(eta)),
+            _ => String::new(),
+        };
+        let status = match (progress.is_failed(), progress.is_complete()) {
+            (true, _) => "  failed\n",
+            (_, true) => "\n",
+            _ => "",
+        };
+        let mut stderr = io::stderr().lock();
+        let _ = write!(
+            stderr,
+            "\r{} {} [{}{}] {:>3}%  {}/{}  {}/s{eta}{status}",
+            progress.direction().as_str(),
+            progress.key(),
+            "#".repeat(filled),
+            ".".repeat(BAR_WIDTH - filled),
+            (progress.fraction() * 100.0) as u32,
+            format_bytes(progress.bytes_done() as f64),
+            format_bytes(progress.total_bytes() as f64),
+            format_bytes(progress.throughput()),
+        );
+        let _ = stderr.flush();
+    }
+}
+
+/// Sends every update into a channel, so another thread, such as a GUI's or a web server's,
+/// can show the progress of transfers running elsewhere. Updates sent after the receiver was
+/// dropped are discarded.
+#[derive(Debug)]
+pub struct ChannelProgress {
+    //Sender isn't Sync on every Rust version this crate supports
+    sender: Mutex<Sender<TransferProgress>>,
+}
+
+impl ChannelProgress {
+    pub fn new() -> (Self, Receiver<TransferProgress>) {
+        let (sender, receiver) = channel();
+        (Self::from_sender(sender), receiver)
+    }
+    pub fn from_sender(sender: Sender<TransferProgress>) -> Self {
+        Self {
+            sender: Mutex::new(sender),
+        }
+    }
+}
+
+impl ProgressListener for ChannelProgress {
+    fn on_progress(&self, progress: &TransferProgress) {
+        if let Ok(sender) = self.sender.lock() {
+            let _ = sender.send(progress.clone());
+        }
+    }
+}
+
+/// Counts the bytes of one transfer and passes updates to the listener, if there is one.
+/// Parts finish out of order on several futures, hence the atomics.
+pub(crate) struct ProgressTracker {
+    listener: Option<Arc<dyn ProgressListener>>,
+    direction: TransferDirection,
+    bucket: String,
+    key: String,
+    total_bytes: u64,
+    resumed_bytes: u64,
+    bytes_done: AtomicU64,
+    started: Instant,
+    last_update: Mutex<Option<Instant>>,
+    finished: AtomicBool,
+}
+
+impl ProgressTracker {
+    /// Starts tracking a transfer of which `resumed_bytes` are already done, and reports it.
+    pub(crate) fn start(
+        listener: Option<Arc<dyn ProgressListener>>,
+        direction: TransferDirection,
+        bucket: &str,
+        key: &str,
+        total_bytes: u64,
+        resumed_bytes: u64,
+    ) -> Self {
+        let tracker = Self {
+            listener,
+            direction,
+            bucket: bucket.to_string(),
+            key: key.to_string(),
+            total_bytes,
+            resumed_bytes,
+            bytes_done: AtomicU64::new(resumed_bytes),
+            started: Instant::now(),
+            last_update: Mutex::new(None),
+            finished: AtomicBool::new(false),
+        };
+        tracker.update(false);
+        tracker
+    }
+    pub(crate) fn advance(&self, bytes: u64) {
+        self.bytes_done.fetch_add(bytes, Ordering::Relaxed);
+        self.update(false);
+    }
+    /// Takes back bytes that have to be transferred again, as when a part is retried.
+    pub(crate) fn rewind(&self, bytes: u64) {
+        let _ = self
+            .bytes_done
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |done| {
+                Some(done.saturating_sub(bytes))
+            });
+    }
+    /// Reports the transfer as failed when the result is an error, and passes it on.
+    pub(crate) fn finish<T, E>(&self, result: Result<T, E>) -> Result<T, E> {
+        if result.is_err() {
+            self.update(true);
+        }
+        result
+    }
+    fn update(&self, failed: bool) {
+        let Some(listener) = &self.listener else {
+            return;
+        };
+        let bytes_done = self.bytes_done.load(Ordering::Relaxed);
+        let last = failed || bytes_done >= self.total_bytes;
+        //A check after the last byte can still fail the transfer
+        if self.finished.load(Ordering::Relaxed) && !failed {
+            return;
+        }
+        if let Ok(mut last_update) = self.last_update.lock() {
+            let now = Instant::now();
+            if !last
+                && last_update
+                    .is_some_and(|previous| now.duration_since(previous) < UPDATE_INTERVAL)
+            {
+                return;
+            }
+            *last_update = Some(now);
+        }
+        if last {
+            self.finished.store(true, Ordering::Relaxed);
+        }
+        listener.on_progress(&TransferProgress {
+            direction: self.direction,
+            bucket: self.bucket.clone(),
+            key: self.key.clone(),
+            bytes_done,
+            total_bytes: self.total_bytes,
+            resumed_bytes: self.resumed_bytes,
+            elapsed: self.started.elapsed(),
+            failed,
+        });
+    }
+}
+
+pub(crate) fn format_bytes(bytes: f64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    match unit {
+        0 => format!("{value:.0} {}", UNITS[unit]),
+        _ => format!("{value:.1} {}", UNITS[unit]),
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match secs {
+        0..=3599 => format!("{}:{:02}", secs / 60, secs % 60),
+        _ => format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress(bytes_done: u64, resumed_bytes: u64, elapsed: Duration) -> TransferProgress {
+        TransferProgress {
+            direction: TransferDirection::Upload,
+            bucket: "bucket".into(),
+            key: "key".into(),
+            bytes_done,
+            total_bytes: 500,
+            resumed_bytes,
+            elapsed,
+            failed: false,
+        }
+    }
+
+    #[test]
+    fn formats_bytes_and_durations() {
+        assert_eq!(format_bytes(0.0), "0 B");
+        assert_eq!(format_bytes(1023.0), "1023 B");
+        assert_eq!(format_bytes(1024.0), "1.0 KiB");
+        assert_eq!(format_bytes(1536.0), "1.5 KiB");
+        assert_eq!(format_bytes(5.0 * 1024.0 * 1024.0 * 1024.0), "5.0 GiB");
+        assert_eq!(format_bytes(2048.0 * 1024f64.powi(4)), "2048.0 TiB");
+        assert_eq!(format_duration(Duration::ZERO), "0:00");
+        assert_eq!(format_duration(Duration::from_secs(65)), "1:05");
+        assert_eq!(format_duration(Duration::from_secs(3599)), "59:59");
+        assert_eq!(format_duration(Duration::from_secs(3600)), "1:00:00");
+        assert_eq!(format_duration(Duration::from_secs(3725)), "1:02:05");
+    }
+
+    #[test]
+    fn leaves_resumed_bytes_out_of_the_throughput() {
+        let resumed = progress(300, 100, Duration::from_secs(2));
+        assert_eq!(resumed.throughput(), 100.0);
+        assert_eq!(resumed.eta(), Some(Duration::from_secs(2)));
+        assert_eq!(resumed.fraction(), 0.6);
+        let nothing_moved = progress(100, 100, Duration::from_secs(2));
+        assert_eq!(nothing_moved.throughput(), 0.0);
+        assert_eq!(nothing_moved.eta(), None);
+        assert_eq!(progress(300, 0, Duration::ZERO).eta(), None);
+    }
+
+    #[test]
+    fn throttles_updates_but_sends_the_first_and_last() {
+        let (listener, updates) = ChannelProgress::new();
+        let tracker = ProgressTracker::start(
+            Some(Arc::new(listener)),
+            TransferDirection::Download,
+            "bucket",
+            "key",
+            100,
+            20,
+        );
+        tracker.advance(10);
+        tracker.advance(70);
+        //Nothing is sent once the transfer is complete, except a failure
+        tracker.advance(5);
+        let _ = tracker.finish(Err::<(), _>("checksum mismatch"));
+        let sent = updates
+            .try_iter()
+            .map(|progress| (progress.bytes_done(), progress.is_failed()))
+            .collect::<Vec<_>>();
+        assert_eq!(sent, [(20, false), (100, false), (105, true)]);
+    }
+}
@@ -1,18 +1,33 @@
+use crate::{
+    error::{Error, SdkResultExt, Service},
+    output::{report, OutputSink, TerminalSink},
+    pagination::{collect_items, items_of_pages, ItemStream},
+};
 use aws_config::SdkConfig;
 use aws_sdk_rds::{
-    types::{DbClusterMember, Endpoint, MasterUserSecret},
+    types::{DbCluster, DbClusterMember, Endpoint, MasterUserSecret},
     Client as RdsClient,
 };
 use colored::Colorize;
 use dotenv::dotenv;
 use std::env::var;
-#[derive(Debug)]
-pub struct RdsOps<'a> {
-    config: &'a SdkConfig,
+use std::sync::Arc;
+use tokio_stream::StreamExt;
+#[derive(Debug, Clone)]
+pub struct RdsOps {
+    client: RdsClient,
+    sink: Arc<dyn OutputSink>,
 }
-impl<'a> RdsOps<'a> {
-    pub fn build(config: &'a SdkConfig) -> Self {
-        Self { config: config }
+impl RdsOps {
+    pub fn build(config: &SdkConfig) -> Self {
+        Self {
+            client: RdsClient::new(config),
+            sink: Arc::new(TerminalSink),
+        }
+    }
+    pub fn with_sink(mut self, sink: Arc<dyn OutputSink>) -> Self {
+        self.sink = sink;
+        self
     }
 
     /// Operations trigger panics prematurely when default error messages are absent
@@ -35,10 +50,8 @@ impl<'a> RdsOps<'a> {
         password: &str,
         allocated_storage: i32,
         storage_type: &str,
-    ) {
-        let client = RdsClient::new(self.config);
-
-        let status = client.create_db_instance()
+    ) -> Result<(), Error> {
+        let status = self.client.create_db_instance()
                     .db_name(db_name)
                     .db_instance_identifier(db_instance_identifier)
                     .storage_type(storage_type)
@@ -53,17 +66,18 @@ impl<'a> RdsOps<'a> {
                     .await
                     .map(|output|{
                         let colored = format!("DbInstance with the identifier: {} has been created successfully.\nIt will take some time to set up and become fully operational.\nYou can check the status of the database instance by using the 'Status Of Db Instance' option\n",db_instance_identifier).green().bold();
-                        println!("{colored}");
+                        report!(self.sink, "{colored}");
                         output
                     })
-                    .expect("Error while creating db instance\n");
+                    .map_sdk_err(Service::Rds, "create_db_instance")?;
 
         let option_of_dbinstance = status.db_instance;
 
         if let Some(dbinstance) = option_of_dbinstance {
             if let Some(status) = dbinstance.db_instance_status {
                 let colored_status = status.green().bold();
-                println!(
+                report!(
+                    self.sink,
                     "{}: {}\n",
                     "The current status of the database instance is"
                         .yellow()
@@ -72,94 +86,90 @@ impl<'a> RdsOps<'a> {
                 );
             }
         }
+        Ok(())
     }
 
     pub async fn describe_db_instance(
         &self,
         db_instance_identifier: Option<&str>,
-    ) -> DbInstanceInfo {
-        let client = RdsClient::new(self.config);
-
+    ) -> Result<DbInstanceInfo, Error> {
         let default_db_instance_id = match db_instance_identifier {
             Some(id) => id.to_string(),
             None => self.get_db_instance_id(),
         };
 
-        let client = client
+        let output = self
+            .client
             .describe_db_instances()
             .db_instance_identifier(default_db_instance_id)
             .send()
             .await
-            .expect("Error while calling describe instances\n");
-        let mut db_instances = client.db_instances.unwrap();
+            .map_sdk_err(Service::Rds, "describe_db_instances")?;
         //Taking first DbInstance
-        let mut db_instance = db_instances.drain(..1).collect::<Vec<_>>();
-
-        DbInstanceInfo::build_instance(
-            db_instance[0].endpoint.take(),
-            db_instance[0].allocated_storage,
-            db_instance[0].db_instance_identifier.take(),
-            db_instance[0].db_instance_class.take(),
-            db_instance[0].db_instance_status.take(),
-            db_instance[0].db_name.take(),
-            db_instance[0].availability_zone.take(),
-            db_instance[0].master_user_secret.take(),
-            db_instance[0].master_username.take(),
-            db_instance[0].publicly_accessible,
-            db_instance[0].db_instance_port,
-        )
+        let db_instance = output
+            .db_instances
+            .and_then(|db_instances| db_instances.into_iter().next())
+            .ok_or(Error::missing(
+                Service::Rds,
+                "describe_db_instances",
+                "db instance",
+            ))?;
+
+        Ok(DbInstanceInfo::build_instance(
+            db_instance.endpoint,
+            db_instance.allocated_storage,
+            db_instance.db_instance_identifier,
+            db_instance.db_instance_class,
+            db_instance.db_instance_status,
+            db_instance.db_name,
+            db_instance.availability_zone,
+            db_instance.master_user_secret,
+            db_instance.master_username,
+            db_instance.publicly_accessible,
+            db_instance.db_instance_port,
+        ))
     }
 
     pub async fn status_of_db_instance(
         &self,
         db_instance_identifier: Option<&str>,
-    ) -> Option<String> {
-        let client = RdsClient::new(self.config);
-
+    ) -> Result<Option<String>, Error> {
         let default_db_instance_id = match db_instance_identifier {
             Some(id) => id.to_string(),
             None => self.get_db_instance_id(),
         };
 
-        let output = client
+        let output = self
+            .client
             .describe_db_instances()
             .db_instance_identifier(default_db_instance_id)
             .send()
             .await
-            .expect("Error while getting status of db instance\n");
-        let mut db_status = None;
-        let db_instance = output.db_instances;
-        if let Some(mut vec_of_db_instance) = db_instance {
-            let first_instance = vec_of_db_instance.drain(..1);
-            first_instance.into_iter().for_each(|output| {
-                let status_ = output.db_instance_status;
-                db_status = status_;
-            });
-        }
-        db_status
+            .map_sdk_err(Service::Rds, "describe_db_instances")?;
+        let db_status = output
+            .db_instances
+            .and_then(|db_instances| db_instances.into_iter().next())
+            .and_then(|db_instance| db_instance.db_instance_status);
+        Ok(db_status)
     }
 
     /// Returns the status of db instance if it successfully start the db_instance
-    pub async fn start_db_instance(&self, db_instance_identifier: Option<&str>) {
-        let client = RdsClient::new(self.config);
-
+    pub async fn start_db_instance(
+        &self,
+        db_instance_identifier: Option<&str>,
+    ) -> Result<(), Error> {
         let default_db_instance_id = match db_instance_identifier {
             Some(id) => id.to_string(),
             None => self.get_db_instance_id(),
         };
 
-        let error = format!(
-            "Error while starting db instance: {}\n",
-            default_db_instance_id
-        );
-
-        client.start_db_instance()
+        self.client.start_db_instance()
                   .db_instance_identifier(&default_db_instance_id)
                   .send()
                   .await
                   .map(|output|{
                    let colored_msg = format!("An instance with the ID of {} initiates the process of starting the database instance if it was stopped before\n",default_db_instance_id).green().bold();
-                   println!("{colored_msg}");
+                   report!(self.sink, "{colored_msg}");
                    let status = if let Some(dbinstance) = output.db_instance{
                              if let Some(status_) = dbinstance.db_instance_status {
                                  Some(status_)
@@ -171,33 +181,29 @@ impl<'a> RdsOps<'a> {
                    };
                    if let Some(status_) = status {
                     let colored_status = status_.green().bold();
-                    println!("{}: {}\n",colored_status,"The current status of the Database Instance".yellow().bold());
+                    report!(self.sink, "{}: {}\n",colored_status,"The current status of the Database Instance".yellow().bold());
                        
                    }
   
                   })
-                  .expect(&error);
+                  .map_sdk_err(Service::Rds, "start_db_instance")
     }
 
-    pub async fn stop_db_instance(&self, db_instance_identifier: Option<&str>) {
-        let client = RdsClient::new(self.config);
-
+    pub async fn stop_db_instance(
+        &self,
+        db_instance_identifier: Option<&str>,
+    ) -> Result<(), Error> {
         let default_db_instance_id = match db_instance_identifier {
             Some(id) => id.to_string(),
             None => self.get_db_instance_id(),
         };
 
-        let error = format!(
-            "Error while stopping db instance: {}\n",
-            default_db_instance_id
-        );
-
-        client.stop_db_instance()
+        self.client.stop_db_instance()
                      .db_instance_identifier(&default_db_instance_id)
                      .send()
                      .await
                      .map(|output|{
-                        println!("The db_instance with the db_instance_id: {} is initiating the process of stopping\n",default_db_instance_id.green().bold());
+                        report!(self.sink, "The db_instance with the db_instance_id: {} is initiating the process of stopping\n",default_db_instance_id.green().bold());
                         let status = if let Some(dbinstance) = output.db_instance{
                             if let Some(status) =dbinstance.db_instance_status{
                                 Some(status)
@@ -209,11 +215,11 @@ impl<'a> RdsOps<'a> {
                         };
                         if let Some(status_) = status {
                             let colored_status = status_.green().bold();
-                            println!("{}: {}\n","The current status of the Database Instance".yellow().bold(),colored_status);
+                            report!(self.sink, "{}: {}\n","The current status of the Database Instance".yellow().bold(),colored_status);
                                
                            }
                      })
-                     .expect(&error);
+                     .map_sdk_err(Service::Rds, "stop_db_instance")
     }
 
     /// Some modifications result in downtime because Amazon RDS must reboot your DB instance for the change to take effect.
@@ -223,21 +229,22 @@ impl<'a> RdsOps<'a> {
         db_instance_identifier: &str,
         master_password_to_replace: &str,
         apply_immediately: bool,
-    ) {
-        let client = RdsClient::new(self.config);
-        let ouput = client
+    ) -> Result<(), Error> {
+        let ouput = self
+            .client
             .modify_db_instance()
             .set_master_user_password(Some(master_password_to_replace.into()))
             .set_db_instance_identifier(Some(db_instance_identifier.into()))
             .set_apply_immediately(Some(apply_immediately))
             .send()
             .await
-            .expect("Error while modifying the db instance settings\n");
+            .map_sdk_err(Service::Rds, "modify_db_instance")?;
 
         if let Some(dbinstance) = ouput.db_instance {
             if let Some(status) = dbinstance.db_instance_status {
                 let colored_status = status.green().bold();
-                println!(
+                report!(
+                    self.sink,
                     "{} : {}\n",
                     "The current status of the Database Instance"
                         .yellow()
@@ -246,29 +253,26 @@ impl<'a> RdsOps<'a> {
                 );
             }
         }
+        Ok(())
     }
 
-    pub async fn delete_db_instance(&self, db_instance_identifier: Option<&str>) {
-        let client = RdsClient::new(self.config);
-
+    pub async fn delete_db_instance(
+        &self,
+        db_instance_identifier: Option<&str>,
+    ) -> Result<(), Error> {
         let default_db_instance_id = match db_instance_identifier {
             Some(id) => id.to_string(),
             None => self.get_db_instance_id(),
         };
 
-        let error = format!(
-            "Error While deleting db instance:{}\n",
-            default_db_instance_id
-        );
-
-        client.delete_db_instance()
+        self.client.delete_db_instance()
                   .db_instance_identifier(&default_db_instance_id)
                   .skip_final_snapshot(true)
                   .send()
                   .await
                   .map(|output|{
                    let colored_msg = format!("The database instance with the ID {default_db_instance_id} has initiated the deletion process.").green().bold();
-                   println!("{}\n",colored_msg);
+                   report!(self.sink, "{}\n",colored_msg);
                    let status = if let Some(dbinstance) = output.db_instance{
                         if let Some(status) = dbinstance.db_instance_status {
                             Some(status)
@@ -281,85 +285,96 @@ impl<'a> RdsOps<'a> {
                    };
                    if let Some(status_) = status {
                     let colored_status = status_.green().bold();
-                    println!("{}: {}\n","The current status of the Database Instance".yellow().bold(),colored_status); 
+                    report!(self.sink, "{}: {}\n","The current status of the Database Instance".yellow().bold(),colored_status); 
                    }
                   })
-                  .expect(&error);
+                  .map_sdk_err(Service::Rds, "delete_db_instance")
     }
 
     pub async fn describe_db_cluster(
         &self,
         db_cluster_identifier: Option<&str>,
-    ) -> Vec<DbClusterInfo> {
-        let client = RdsClient::new(self.config);
-
+    ) -> Result<Vec<DbClusterInfo>, Error> {
         let default_cluster_id = match db_cluster_identifier {
             Some(id) => id.to_string(),
             None => self.get_db_instance_id(),
         };
-        let client = client
-            .describe_db_clusters()
-            .db_cluster_identifier(default_cluster_id)
-            .send()
-            .await
-            .expect("Error while describing db cluster\n");
-        let cluster_info = client.db_clusters;
+        let clusters =
+            collect_items(self.describe_db_clusters_stream(Some(&default_cluster_id))).await?;
 
         let mut vec_of_db_cluster_info = Vec::new();
 
-        if let Some(clusters) = cluster_info {
-            clusters.into_iter().for_each(|db_cluster_info| {
-                let db_cluster_status = db_cluster_info.status;
-                let availability_zones = db_cluster_info.availability_zones;
-                let db_cluster_member = db_cluster_info.db_cluster_members;
-                let database_name = db_cluster_info.database_name;
-                let cluster_endpoint = db_cluster_info.endpoint;
-                let master_username = db_cluster_info.master_username;
-                let port = db_cluster_info.port;
-                vec_of_db_cluster_info.push(DbClusterInfo::build_cluster_info(
-                    db_cluster_status,
-                    db_cluster_member,
-                    availability_zones,
-                    database_name,
-                    cluster_endpoint,
-                    master_username,
-                    port,
-                ));
-            });
-        }
-        vec_of_db_cluster_info
+        clusters.into_iter().for_each(|db_cluster_info| {
+            let db_cluster_status = db_cluster_info.status;
+            let availability_zones = db_cluster_info.availability_zones;
+            let db_cluster_member = db_cluster_info.db_cluster_members;
+            let database_name = db_cluster_info.database_name;
+            let cluster_endpoint = db_cluster_info.endpoint;
+            let master_username = db_cluster_info.master_username;
+            let port = db_cluster_info.port;
+            vec_of_db_cluster_info.push(DbClusterInfo::build_cluster_info(
+                db_cluster_status,
+                db_cluster_member,
+                availability_zones,
+                database_name,
+                cluster_endpoint,
+                master_username,
+                port,
+            ));
+        });
+        Ok(vec_of_db_cluster_info)
+    }
+    /// Describes the DB clusters, or only the given one, fetching further pages as the
+    /// stream is consumed
+    pub fn describe_db_clusters_stream(
+        &self,
+        db_cluster_identifier: Option<&str>,
+    ) -> ItemStream<DbCluster> {
+        let pages = self
+            .client
+            .describe_db_clusters()
+            .set_db_cluster_identifier(db_cluster_identifier.map(|id| id.to_string()))
+            .into_paginator()
+            .send()
+            .map(|page| page.map_sdk_err(Service::Rds, "describe_db_clusters"));
+        items_of_pages(pages, |page| page.db_clusters.unwrap_or_default())
     }
 
     /// When deleting a database cluster, you can set the 'skip_final_snapshot' option to 'true,' which means you don't
     /// have to specify the final snapshot ID. If that's not what you want, set it to 'false' and provide
     /// the final snapshot ID.
-    pub async fn delete_db_cluster(&self, db_cluster_identifier: Option<&str>) -> DbClusterInfo {
-        let client = RdsClient::new(self.config);
-
+    pub async fn delete_db_cluster(
+        &self,
+        db_cluster_identifier: Option<&str>,
+    ) -> Result<DbClusterInfo, Error> {
         let default_cluster_id = match db_cluster_identifier {
             Some(id) => id.to_string(),
             None => self.get_db_instance_id(),
         };
 
-        let cluster_output= client.delete_db_cluster()
+        let cluster_output= self.client.delete_db_cluster()
                .db_cluster_identifier(&default_cluster_id)
                .skip_final_snapshot(true)
                .send()
                .await
                .map(|output|{
-                println!("The db_cluster identified by ID {} is initiating the deletion process for both the clusters and the associated DB instances\n",default_cluster_id);
+                report!(self.sink, "The db_cluster identified by ID {} is initiating the deletion process for both the clusters and the associated DB instances\n",default_cluster_id);
                 if let Some(cluster) = output.db_cluster.clone() {
                     if let Some(status) = cluster.status {
                         let colored_status = status.green().bold();
-                        println!("{}: {}\n","The current status of the Database Cluster".yellow().bold(),colored_status);
+                        report!(self.sink, "{}: {}\n","The current status of the Database Cluster".yellow().bold(),colored_status);
                     }
                    }
                 output
 
                })
-               .expect("Error while deleting dbcluster\n");
+               .map_sdk_err(Service::Rds, "delete_db_cluster")?;
 
-        let db_cluster_info = cluster_output.db_cluster.unwrap();
+        let db_cluster_info = cluster_output.db_cluster.ok_or(Error::missing(
+            Service::Rds,
+            "delete_db_cluster",
+            "db cluster",
+        ))?;
         let db_cluster_status = db_cluster_info.status;
         let availability_zones = db_cluster_info.availability_zones;
         let db_cluster_member = db_cluster_info.db_cluster_members;
@@ -368,7 +383,7 @@ impl<'a> RdsOps<'a> {
         let master_username = db_cluster_info.master_username;
         let port = db_cluster_info.port;
 
-        DbClusterInfo::build_cluster_info(
+        Ok(DbClusterInfo::build_cluster_info(
             db_cluster_status,
             db_cluster_member,
             availability_zones,
@@ -376,7 +391,7 @@ impl<'a> RdsOps<'a> {
             cluster_endpoint,
             master_username,
             port,
-        )
+        ))
     }
 }
 
@@ -1,7 +1,4 @@
-use std::{
-    fs::OpenOptions,
-    io::{Read, Write},
-};
+use std::{io::Read, sync::Arc};
 
 use aws_config::SdkConfig;
 use aws_sdk_pinpoint::primitives::Blob;
@@ -22,19 +19,33 @@ use std::ops::Deref;
 
 use crate::{
     create_celebrity_pdf,
+    error::{Error, SdkResultExt, Service},
+    output::{report, OutputSink, TerminalSink},
     pdf_writer::{create_face_result_pdf, create_text_only_pdf, create_text_result_pdf},
 };
 
-pub struct RekognitionOps<'a> {
-    config: &'a SdkConfig,
+#[derive(Debug, Clone)]
+pub struct RekognitionOps {
+    client: RekogClient,
+    sink: Arc<dyn OutputSink>,
 }
-impl<'a> RekognitionOps<'a> {
-    pub fn build(config: &'a SdkConfig) -> Self {
-        Self { config }
+impl RekognitionOps {
+    pub fn build(config: &SdkConfig) -> Self {
+        Self {
+            client: RekogClient::new(config),
+            sink: Arc::new(TerminalSink),
+        }
+    }
+    pub fn with_sink(mut self, sink: Arc<dyn OutputSink>) -> Self {
+        self.sink = sink;
+        self
     }
     /// [`Attribute`](https://docs.rs/aws-sdk-rekognition/latest/aws_sdk_rekognition/types/enum.Attribute.html)
-    pub async fn detect_faces(&self, key_name: &str, bucket_name: &str) -> Vec<FaceDetails> {
-        let client = RekogClient::new(self.config);
+    pub async fn detect_faces(
+        &self,
+        key_name: &str,
+        bucket_name: &str,
+    ) -> Result<Vec<FaceDetails>, Error> {
         let s3_object_builder = S3Object::builder()
             .name(key_name)
             .bucket(bucket_name)
@@ -48,13 +59,14 @@ impl<'a> RekognitionOps<'a> {
             Attribute::Default,
         ];
         let image_builder = Image::builder().s3_object(s3_object_builder).build();
-        let detect_face_output = client
+        let detect_face_output = self
+            .client
             .detect_faces()
             .set_attributes(Some(attribute))
             .image(image_builder)
             .send()
             .await
-            .expect("Error while detecting faces\n");
+            .map_sdk_err(Service::Rekognition, "detect_faces")?;
         let mut vec_of_facedetails = Vec::new();
 
         if let Some(face_detail) = detect_face_output.face_details {
@@ -62,47 +74,54 @@ impl<'a> RekognitionOps<'a> {
                 vec_of_facedetails.push(FaceDetails(outputs));
             });
         }
-        vec_of_facedetails
+        Ok(vec_of_facedetails)
     }
 
-    pub async fn create_collection(&self, collection_id: &str) {
-        let client = RekogClient::new(self.config);
-        let outputs = client
+    pub async fn create_collection(&self, collection_id: &str) -> Result<(), Error> {
+        let outputs = self
+            .client
             .create_collection()
             .collection_id(collection_id)
             .send()
             .await
-            .expect("Error while creating Create Collection\n");
+            .map_sdk_err(Service::Rekognition, "create_collection")?;
         if let Some(arn) = outputs.collection_arn {
-            println!("Collection Arn: {}", arn.green().bold());
+            report!(self.sink, "Collection Arn: {}", arn.green().bold());
         }
         if let Some(model_version) = outputs.face_model_version {
-            println!("Model Version: {}", model_version.green().bold());
+            report!(self.sink, "Model Version: {}", model_version.green().bold());
         }
         if let Some(code) = outputs.status_code {
             let format = format!("Status Code: {}\n", code);
-            println!("{}\n", format.green().bold());
+            report!(self.sink, "{}\n", format.green().bold());
         }
+        Ok(())
     }
-    pub async fn index_faces(&self, bucket_name: &str, key_image_name: &str, collection_id: &str) {
-        let client = RekogClient::new(self.config);
+    pub async fn index_faces(
+        &self,
+        bucket_name: &str,
+        key_image_name: &str,
+        collection_id: &str,
+    ) -> Result<(), Error> {
         let s3_object_builder = S3Object::builder()
             .bucket(bucket_name)
             .name(key_image_name)
             .build();
         let image_builder = Image::builder().s3_object(s3_object_builder).build();
-        let outputs = client
+        let outputs = self
+            .client
             .index_faces()
             .collection_id(collection_id)
             .image(image_builder)
             .send()
             .await
-            .expect("Error while Indexing Faces\n");
+            .map_sdk_err(Service::Rekognition, "index_faces")?;
         if let Some(face_record) = outputs.face_records {
             face_record.into_iter().for_each(|face| {
                 if let Some(face) = face.face {
                     if let Some(face_id) = face.face_id {
-                        println!(
+                        report!(
+                            self.sink,
                             "Face Id For the Uploaded Face: {}\n",
                             face_id.green().bold()
                         );
@@ -110,27 +129,35 @@ impl<'a> RekognitionOps<'a> {
                 }
             })
         }
+        Ok(())
     }
-    pub async fn search_faces(&self, collection_id: &str, face_id: &str) {
-        let client = RekogClient::new(self.config);
-        let outputs = client
+    pub async fn search_faces(&self, collection_id: &str, face_id: &str) -> Result<(), Error> {
+        let outputs = self
+            .client
             .search_faces()
             .collection_id(collection_id)
             .face_id(face_id)
             .send()
             .await
-            .expect("Error While Searching Faces\n");
+            .map_sdk_err(Service::Rekognition, "search_faces")?;
         if let Some(search_face_output) = outputs.face_matches {
             search_face_output.into_iter().for_each(|similarity| {
                 if let Some(simiarity_) = similarity.similarity {
-                    println!("Similarity With Already Indexed Face: {}\n", simiarity_);
+                    report!(
+                        self.sink,
+                        "Similarity With Already Indexed Face: {}\n",
+                        simiarity_
+                    );
                 }
             })
         }
+        Ok(())
     }
-    pub async fn detect_texts(&self, bucket_name: &str, key_name: &str) -> Vec<TextDetect> {
-        let client = RekogClient::new(self.config);
-
+    pub async fn detect_texts(
+        &self,
+        bucket_name: &str,
+        key_name: &str,
+    ) -> Result<Vec<TextDetect>, Error> {
         let s3_object_builder = S3Object::builder()
             .bucket(bucket_name)
             .name(key_name)
@@ -138,12 +165,13 @@ impl<'a> RekognitionOps<'a> {
 
         let image_builder = Image::builder().s3_object(s3_object_builder).build();
 
-        let detect_text_output = client
+        let detect_text_output = self
+            .client
             .detect_text()
             .image(image_builder)
             .send()
             .await
-            .expect("Error while detetecting text\n");
+            .map_sdk_err(Service::Rekognition, "detect_text")?;
 
         let mut vec_of_text_detect = Vec::new();
         if let Some(text_detection) = detect_text_output.text_detections {
@@ -151,80 +179,75 @@ impl<'a> RekognitionOps<'a> {
                 vec_of_text_detect.push(TextDetect(texts));
             });
         }
-        vec_of_text_detect
+        Ok(vec_of_text_detect)
     }
     pub async fn start_text_detection_task(
         &self,
         bucket_name: &str,
         key_video_name: &str,
-    ) -> Option<String> {
-        let client = RekogClient::new(self.config);
-
+    ) -> Result<Option<String>, Error> {
         let s3_object_builder = S3Object::builder()
             .bucket(bucket_name)
             .name(key_video_name)
             .build();
         let video_builder = Video::builder().s3_object(s3_object_builder).build();
 
-        let output = client
+        let output = self
+            .client
             .start_text_detection()
             .video(video_builder)
             .send()
             .await
-            .expect("Error while start text detection task\n");
+            .map_sdk_err(Service::Rekognition, "start_text_detection")?;
         let job_id = output.job_id;
 
-        let mut file = OpenOptions::new()
-            .create(true)
-            .read(true)
-            .write(true)
-            .open("start_text_detection_job_id.txt")
-            .expect("Error while creating job id file");
         if let Some(id) = job_id.as_deref() {
-            println!(
+            report!(
+                self.sink,
                 "The Job ID for the start text detection task is: {}\n",
                 id.green().bold()
             );
             let id = format!("The Job ID for initiating text detection is: {}", id);
-            match file.write_all(id.as_bytes()) {
-                Ok(_) => {
-                    println!(
-                        "{}\n",
-                        "The job ID has been successfully written to the current directory"
-                            .green()
-                            .bold()
-                    );
-                    println!("{}","Before initiating a new text detection task, please change the job ID file name in the current directory where the CLI is running".yellow().bold());
-                    println!(
+            match self
+                .sink
+                .write_artifact("start_text_detection_job_id.txt", id.as_bytes())
+            {
+                Ok(location) => {
+                    let msg = format!("The job ID has been successfully written to {location}");
+                    report!(self.sink, "{}\n", msg.green().bold());
+                    report!(self.sink, "{}","Before initiating a new text detection task, please change the job ID file name where the output is written".yellow().bold());
+                    report!(
+                        self.sink,
                         "{}\n",
                         "This ensures that the old file is not replaced by the new job ID"
                             .yellow()
                             .bold()
                     );
                 }
-                Err(_) => println!("Error while writing job id...\n"),
+                Err(_) => report!(self.sink, "Error while writing job id...\n"),
             }
         }
 
-        job_id
+        Ok(job_id)
     }
-    pub async fn get_text_detection_results(&self, text_job_id: &str) -> GetTextInfo {
-        let client = RekogClient::new(self.config);
-        let get_text_detection_ouput = client
+    pub async fn get_text_detection_results(
+        &self,
+        text_job_id: &str,
+    ) -> Result<GetTextInfo, Error> {
+        let get_text_detection_ouput = self
+            .client
             .get_text_detection()
             .job_id(text_job_id)
             .send()
             .await
-            .expect("Error while getting text detection\n");
-        GetTextInfo(get_text_detection_ouput)
+            .map_sdk_err(Service::Rekognition, "get_text_detection")?;
+        Ok(GetTextInfo(get_text_detection_ouput))
     }
     pub async fn start_face_detection_task(
         &self,
         bucket_name: &str,
         key_video_name: &str,
-    ) -> Option<String> {
-        let client = RekogClient::new(self.config);
-
+    ) -> Result<Option<String>, Error> {
         let s3_object_builder = S3Object::builder()
             .bucket(bucket_name)
             .name(key_video_name)
@@ -232,74 +255,72 @@ impl<'a> RekognitionOps<'a> {
 
         let video_builder = Video::builder().s3_object(s3_object_builder).build();
 
-        let output = client
+        let output = self
+            .client
             .start_face_detection()
             .video(video_builder)
             .send()
             .await
-            .expect("Error while starting face detetction task\n");
+            .map_sdk_err(Service::Rekognition, "start_face_detection")?;
 
         let job_id = output.job_id;
 
-        let mut file = OpenOptions::new()
-            .create(true)
-            .read(true)
-            .write(true)
-            .open("start_face_detection_job_id.txt")
-            .expect("Error while creating job id file");
         if let Some(id) = job_id.as_deref() {
-            println!(
+            report!(
+                self.sink,
                 "The job ID for the start face detection task is: {}\n",
                 id.green().bold()
             );
             let id = format!("The Job ID for initiating face detection is: {}", id);
-            match file.write_all(id.as_bytes()) {
-                Ok(_) => {
-                    println!(
-                        "{}\n",
-                        "The job ID has been successfully written to the current directory"
-                            .green()
-                            .bold()
-                    );
-                    println!("{}","Before initiating a new face detection task, please change the job ID file name in the current directory where the CLI is running".yellow().bold());
-                    println!(
+            match self
+                .sink
+                .write_artifact("start_face_detection_job_id.txt", id.as_bytes())
+            {
+                Ok(location) => {
+                    let msg = format!("The job ID has been successfully written to {location}");
+                    report!(self.sink, "{}\n", msg.green().bold());
+                    report!(self.sink, "{}","Before initiating a new face detection task, please change the job ID file name where the output is written".yellow().bold());
+                    report!(
+                        self.sink,
                         "{}\n",
                         "This ensures that the old file is not replaced by the new job ID"
                             .yellow()
                             .bold()
                     );
                 }
-                Err(_) => println!("Error while writing job id...\n"),
+                Err(_) => report!(self.sink, "Error while writing job id...\n"),
             }
         }
 
-        job_id
+        Ok(job_id)
     }
 
-    pub async fn get_face_detection_results(&self, face_job_id: &str) -> GetFaceInfo {
-        let client = RekogClient::new(self.config);
-        let get_face_detection_output = client
+    pub async fn get_face_detection_results(
+        &self,
+        face_job_id: &str,
+    ) -> Result<GetFaceInfo, Error> {
+        let get_face_detection_output = self
+            .client
             .get_face_detection()
             .job_id(face_job_id)
             .send()
             .await
-            .expect("Error while getting face detection result\n");
+            .map_sdk_err(Service::Rekognition, "get_face_detection")?;
 
-        GetFaceInfo(get_face_detection_output)
+        Ok(GetFaceInfo(get_face_detection_output))
     }
     pub async fn recognize_celebrities(
         &self,
         local_image_path: Option<&str>,
         bucket_name: Option<&str>,
         image_key_name: Option<&str>,
-    ) {
-        let client = RekogClient::new(self.config);
+    ) -> Result<(), Error> {
         let image = match local_image_path {
             Some(local_image_path_) => {
-                let mut file = std::fs::File::open(local_image_path_)
-                    .expect("Error while reading the path you specified\n");
+                let read_err = |err| Error::io("recognize_celebrities", local_image_path_, err);
+                let mut file = std::fs::File::open(local_image_path_).map_err(read_err)?;
                 let mut vec_of_u8s = Vec::new();
-                file.read_to_end(&mut vec_of_u8s).unwrap();
+                file.read_to_end(&mut vec_of_u8s).map_err(read_err)?;
                 let bytes_builder = Blob::new(vec_of_u8s);
                 Image::builder().bytes(bytes_builder).build()
             }
@@ -312,12 +333,13 @@ impl<'a> RekognitionOps<'a> {
             }
         };
 
-        let outputs = client
+        let outputs = self
+            .client
             .recognize_celebrities()
             .image(image)
             .send()
             .await
-            .expect("Erro while Recognizing Celebrities\n");
+            .map_sdk_err(Service::Rekognition, "recognize_celebrities")?;
         if let Some(celebrity_faces) = outputs.celebrity_faces {
             let headers = vec![
                 "Celebrity Name".into(),
@@ -328,13 +350,8 @@ impl<'a> RekognitionOps<'a> {
             ];
             let mut records = Vec::new();
             let file_name = format!("CelebrityDetails.txt");
-            let mut file = OpenOptions::new()
-                .create(true)
-                .read(true)
-                .write(true)
-                .open(&file_name)
-                .expect("Error while creating file\n");
-            celebrity_faces.into_iter().for_each(|faces| {
+            let mut contents = String::new();
+            for faces in celebrity_faces {
                 if let Some(name) = faces.name {
                     let famous_for = match name.as_str() {
                         "Ralph Fiennes" => format!("{name}(voldemort)"),
@@ -360,24 +377,24 @@ impl<'a> RekognitionOps<'a> {
                         "Ajith Kumar" => format!("{name}(Ultimate Star)"),
                         _ => format!("{name}"),
                     };
-                    println!("Celebrity Name: {}", famous_for.green().bold());
+                    report!(self.sink, "Celebrity Name: {}", famous_for.green().bold());
                     let buf = format!("Celebrity Name: {}\n", famous_for);
-                    file.write_all(buf.as_bytes()).unwrap();
+                    contents.push_str(&buf);
                     records.push(famous_for);
                 }
                 if let Some(id) = faces.id {
-                    println!("Celebrity Amazon ID: {}", id.green().bold());
+                    report!(self.sink, "Celebrity Amazon ID: {}", id.green().bold());
                     let buf = format!("Celebrity Amazon ID: {}\n", id);
-                    file.write_all(buf.as_bytes()).unwrap();
+                    contents.push_str(&buf);
                     records.push(id);
                 }
                 if let Some(gender) = faces.known_gender {
                     let gender_ = gender.r#type;
                     if let Some(genderr) = gender_ {
                         let finall = genderr.as_str().to_string();
-                        println!("Celebrity Gender: {}", finall.green().bold());
+                        report!(self.sink, "Celebrity Gender: {}", finall.green().bold());
                         let buf = format!("Celebrity Gender: {}\n", finall);
-                        file.write_all(buf.as_bytes()).unwrap();
+                        contents.push_str(&buf);
                         records.push(finall);
                     }
                 }
@@ -389,49 +406,49 @@ impl<'a> RekognitionOps<'a> {
                         {
                             let format_bbox =
                                 format!("Width: {width:.2},Height: {height:.2},Left: {left:.2},Top: {top:.2}");
-                            println!(
+                            report!(
+                                self.sink,
                                 "Celebrity Face Location Info: {}",
                                 format_bbox.green().bold()
                             );
                             let buf = format!("Bouding Box Details: {}\n", format_bbox);
-                            file.write_all(buf.as_bytes()).unwrap();
+                            contents.push_str(&buf);
                             bbox_string.push_str(&format_bbox);
                         }
                     }
                     records.push(bbox_string);
                     if let Some(smile) = face.smile {
                         let format_smile = format!("{}", smile.value);
-                        println!(
+                        report!(
+                            self.sink,
                             "Is the Celebrity Smiling: {}\n",
                             format_smile.green().bold()
                         );
                         let buf = format!("Is the Celebrity Smiling: {}", format_smile);
-                        file.write_all(buf.as_bytes()).unwrap();
+                        contents.push_str(&buf);
                         records.push(format_smile);
                     }
                 }
-            });
-            match std::fs::File::open(file_name) {
-                Ok(_) => println!(
-                    "{}\n",
-                    "The text file has been successfully written to the current directory"
-                        .green()
-                        .bold()
-                ),
-                Err(_) => println!("{}\n", "Error while writing File".red().bold()),
+            }
+            match self.sink.write_artifact(&file_name, contents.as_bytes()) {
+                Ok(location) => {
+                    let msg = format!("The text file has been successfully written to {location}");
+                    report!(self.sink, "{}\n", msg.green().bold())
+                }
+                Err(_) => report!(self.sink, "{}\n", "Error while writing File".red().bold()),
             }
             create_celebrity_pdf(
                 headers,
                 records,
                 local_image_path,
                 (bucket_name, image_key_name),
+                self.sink.as_ref(),
             )
-            .await;
+            .await?;
         }
+        Ok(())
     }
-    pub async fn create_face_liveness(&self, bucket_name: &str) {
-        let client = RekogClient::new(self.config);
-
+    pub async fn create_face_liveness(&self, bucket_name: &str) -> Result<(), Error> {
         let s3_object_builder = LivenessOutputConfig::builder()
             .s3_bucket(bucket_name)
             .s3_key_prefix("faceliveness/")
@@ -441,44 +458,40 @@ impl<'a> RekognitionOps<'a> {
             .output_config(s3_object_builder)
             .build();
 
-        let output = client
+        let output = self
+            .client
             .create_face_liveness_session()
             .settings(request_settings_builder)
             .send()
             .await
-            .expect("Error while creating face liveness\n");
-
-        let mut file = OpenOptions::new()
-            .create(true)
-            .read(true)
-            .write(true)
-            .open("session_id.txt")
-            .expect("Error while creating file\n");
+            .map_sdk_err(Service::Rekognition, "create_face_liveness_session")?;
+
         if let Some(session_id) = output.session_id {
             let colored_session_id = session_id.green().bold();
             let buf = format!("The session identifier is: {session_id}\nThe key prefix for the S3 object is set to 'faceliveness/' in the bucket named {bucket_name}");
-            println!("The session ID for the 'CreateFaceLiveness' project is: {colored_session_id}\nThe key prefix for the S3 object is set to 'faceliveness/' in the bucket named {bucket_name}\n");
-            match file.write_all(buf.as_bytes()) {
-                Ok(_) => println!(
-                    "{}\n",
-                    "Session ID has been written to the current directory"
-                        .green()
-                        .bold()
-                ),
-                Err(_) => println!(""),
+            report!(self.sink, "The session ID for the 'CreateFaceLiveness' project is: {colored_session_id}\nThe key prefix for the S3 object is set to 'faceliveness/' in the bucket named {bucket_name}\n");
+            match self.sink.write_artifact("session_id.txt", buf.as_bytes()) {
+                Ok(location) => {
+                    let msg = format!("Session ID has been written to {location}");
+                    report!(self.sink, "{}\n", msg.green().bold())
+                }
+                Err(_) => report!(self.sink),
             }
         }
+        Ok(())
     }
-    pub async fn get_face_liveness_session_results(&self, session_id: &str) -> LivenessOutput {
-        let client = RekogClient::new(self.config);
-
-        let get_faceliveness_session_results_ouput = client
+    pub async fn get_face_liveness_session_results(
+        &self,
+        session_id: &str,
+    ) -> Result<LivenessOutput, Error> {
+        let get_faceliveness_session_results_ouput = self
+            .client
             .get_face_liveness_session_results()
             .session_id(session_id)
             .send()
             .await
-            .expect("Error while getting faceliveness results\n");
-        LivenessOutput(get_faceliveness_session_results_ouput)
+            .map_sdk_err(Service::Rekognition, "get_face_liveness_session_results")?;
+        Ok(LivenessOutput(get_faceliveness_session_results_ouput))
     }
 }
 
@@ -602,7 +615,10 @@ impl GetTextInfo {
         }
         vec_of_text_detection_result
     }
-    pub fn write_text_detection_results_as_text_and_pdf(&mut self) {
+    pub fn write_text_detection_results_as_text_and_pdf(
+        &mut self,
+        sink: &dyn OutputSink,
+    ) -> Result<(), Error> {
         let job_id = self
             .0
             .job_id
@@ -617,14 +633,9 @@ impl GetTextInfo {
         ];
         let (bucket_name, video_key_name) = self.s3_details();
         let mut all_types_results = Vec::new();
-        let mut file = OpenOptions::new()
-            .create(true)
-            .read(true)
-            .write(true)
-            .open("Get_Text_Detection_Results.txt")
-            .expect("Error while Creating File\n");
+        let mut contents = String::new();
         let mut only_texts = Vec::new();
-        text_detection_result.into_iter().for_each(|text_outputs| {
+        for text_outputs in text_detection_result {
             let timestamp = text_outputs.timestamp();
             let get_text = text_outputs.text_detection;
             if let Some(text_detection) = get_text {
@@ -633,38 +644,40 @@ impl GetTextInfo {
                 let text_type = textdetails.text_type();
                 let confidence = textdetails.confidence();
                 let buf = format!("Timestamp: {timestamp}\n");
-                file.write_all(buf.as_bytes()).unwrap();
+                contents.push_str(&buf);
                 all_types_results.push(timestamp.to_string());
 
                 if let Some(text) = texts {
                     let buf = format!("Detected Text: {text}\n");
-                    file.write_all(buf.as_bytes()).unwrap();
+                    contents.push_str(&buf);
                     all_types_results.push(text.clone());
                     only_texts.push(text);
                 }
                 if let Some(text_type) = text_type {
                     let buf = format!("Text Type: {text_type}\n");
-                    file.write_all(buf.as_bytes()).unwrap();
+                    contents.push_str(&buf);
                     all_types_results.push(text_type)
                 }
                 if let Some(confidence) = confidence {
                     let buf = format!("Confidence Level: {confidence}\n\n");
-                    file.write_all(buf.as_bytes()).unwrap();
+                    contents.push_str(&buf);
                     all_types_results.push(confidence.to_string());
                 }
             }
-        });
-        match std::fs::File::open("Get_Text_Detection_Results.txt"){
-            Ok(_) => println!("The text detection results have been successfully written to the current directory with the file name '{}'\n","Get_Text_Detection_Results.txt".green().bold()),
-            Err(_) => println!("Error while Writing the data\n")
+        }
+        match sink.write_artifact("Get_Text_Detection_Results.txt", contents.as_bytes()) {
+            Ok(location) => report!(sink, "The text detection results have been successfully written to {location} with the file name '{}'\n","Get_Text_Detection_Results.txt".green().bold()),
+            Err(_) => report!(sink, "Error while Writing the data\n")
         }
         create_text_result_pdf(
             &headers,
             all_types_results,
             job_id,
             (bucket_name, video_key_name),
-        );
-        create_text_only_pdf(only_texts);
+            sink,
+        )?;
+        create_text_only_pdf(only_texts, sink)?;
+        Ok(())
     }
 }
 impl Deref for GetTextInfo {
@@ -794,7 +807,10 @@ impl GetFaceInfo {
         }
         vec_of_face_details
     }
-    pub fn write_face_detection_results_as_text_and_pdf(&mut self) {
+    pub fn write_face_detection_results_as_text_and_pdf(
+        &mut self,
+        sink: &dyn OutputSink,
+    ) -> Result<(), Error> {
         let mut job_id = String::new();
         if let Some(job_id_) = self.0.job_id.take() {
             job_id.push_str(&job_id_);
@@ -813,43 +829,38 @@ impl GetFaceInfo {
         ];
         let mut face_details_vector = Vec::new();
         let face_detail = self.face_detection();
-        let mut file = OpenOptions::new()
-            .create(true)
-            .read(true)
-            .write(true)
-            .open("Get_Face_Detection_Results.txt")
-            .expect("Error while Creating File\n");
-        face_detail.into_iter().for_each(|face_details| {
+        let mut contents = String::new();
+        for face_details in face_detail {
             let timestamp = face_details.timestamp;
             let format_timestamp = format!("Timestamp: {timestamp}\n");
-            file.write_all(format_timestamp.as_bytes()).unwrap();
+            contents.push_str(&format_timestamp);
             if let Some(face_detail_type) = face_details.face {
                 let mut wrap_face_detection = FaceDetails::build(face_detail_type);
                 let gender = wrap_face_detection.gender();
                 let mut gender_string = String::new();
                 //let format_gender = format!("{:?} ,{:?}", gender.0, gender.1);
-               // gender_string.push_str(&format_gender);
+                // gender_string.push_str(&format_gender);
                 if let (Some(gender_), Some(conf_level)) = (gender.0, gender.1) {
                     gender_string.push_str(&gender_);
                     gender_string.push_str(" and ");
                     let confidence_level = format!("{}", conf_level);
                     gender_string.push_str(&confidence_level);
-                } 
+                }
                 let buf = format!("Gender and Confidence Level: {}\n", gender_string);
-                file.write_all(buf.as_bytes()).unwrap();
+                contents.push_str(&buf);
 
                 let age_range = wrap_face_detection.age_range();
                 let mut age_range_string = String::new();
-                if let (Some(low),Some(high)) = (age_range.0,age_range.1) {
+                if let (Some(low), Some(high)) = (age_range.0, age_range.1) {
                     let format_age_range = format!("The lowest age prediction is {low}, and the highest age prediction is {high}");
                     age_range_string.push_str(&format_age_range);
-                } 
+                }
                 let buf = format!("Age Range in Years: {}\n", age_range_string);
-                file.write_all(buf.as_bytes()).unwrap();
+                contents.push_str(&buf);
 
                 let smile = wrap_face_detection.smile();
                 let mut smile_string = String::new();
-                if let (Some(smiling),Some(conf_level)) = (smile.0,smile.1) {
+                if let (Some(smiling), Some(conf_level)) = (smile.0, smile.1) {
                     let format_smile = format!("{smiling},{conf_level}");
                     smile_string.push_str(&format_smile);
                 }
@@ -857,50 +868,50 @@ impl GetFaceInfo {
                     "Is the Face Smiling and Confidence Level: {}\n",
                     smile_string
                 );
-                file.write_all(buf.as_bytes()).unwrap();
+                contents.push_str(&buf);
 
                 let beard = wrap_face_detection.beard();
                 let mut beard_string = String::new();
-                if let (Some(beard),Some(conf_level)) =(beard.0,beard.1)  {
+                if let (Some(beard), Some(conf_level)) = (beard.0, beard.1) {
                     let format_beard = format!("{beard},{conf_level}");
                     beard_string.push_str(&format_beard);
-                } 
+                }
                 let buf = format!("Has a Beard and Confidence Level: {}\n", beard_string);
-                file.write_all(buf.as_bytes()).unwrap();
+                contents.push_str(&buf);
 
                 let mustache = wrap_face_detection.mustache();
                 let mut mustache_string = String::new();
-                if let (Some(mustache),Some(conf_level)) =(mustache.0,mustache.1)  {
+                if let (Some(mustache), Some(conf_level)) = (mustache.0, mustache.1) {
                     let format_mustache = format!("{mustache},{conf_level}");
                     mustache_string.push_str(&format_mustache);
                 }
                 let buf = format!("Has a Mustache and Confidence Level: {}\n", mustache_string);
-                file.write_all(buf.as_bytes()).unwrap();
+                contents.push_str(&buf);
 
                 let sunglasses = wrap_face_detection.sunglasses();
                 let mut sunglasses_string = String::new();
-                if let (Some(sun),Some(conf_level)) = (sunglasses.0,sunglasses.1) {
+                if let (Some(sun), Some(conf_level)) = (sunglasses.0, sunglasses.1) {
                     let format_sunglass = format!("{sun},{conf_level}");
                     sunglasses_string.push_str(&format_sunglass);
-                } 
+                }
                 let buf = format!(
                     "Has Sunglasses and Confidence Level: {}\n",
                     sunglasses_string
                 );
-                file.write_all(buf.as_bytes()).unwrap();
+                contents.push_str(&buf);
 
                 let eyeglasses = wrap_face_detection.eyeglasses();
                 let mut eyeglasses_string = String::new();
-                
-                if let (Some(eye),Some(conf_level)) =(eyeglasses.0,eyeglasses.1)  {
+
+                if let (Some(eye), Some(conf_level)) = (eyeglasses.0, eyeglasses.1) {
                     let format_eyeglasses = format!("{eye},{conf_level}");
                     eyeglasses_string.push_str(&format_eyeglasses);
-                } 
+                }
                 let buf = format!(
                     "Has Eyeglasses and Confidence Level: {}\n",
                     eyeglasses_string
                 );
-                file.write_all(buf.as_bytes()).unwrap();
+                contents.push_str(&buf);
 
                 let bounding_box = wrap_face_detection.bounding_box();
                 let mut bounding_string = String::new();
@@ -915,7 +926,7 @@ impl GetFaceInfo {
                     bounding_string.push_str(&format_bounding_box);
                 }
                 let buf = format!("Bounding Box Details: {}\n\n\n", bounding_string);
-                file.write_all(buf.as_bytes()).unwrap();
+                contents.push_str(&buf);
 
                 face_details_vector.push(timestamp.to_string());
                 face_details_vector.push(gender_string);
@@ -927,16 +938,18 @@ impl GetFaceInfo {
                 face_details_vector.push(eyeglasses_string);
                 face_details_vector.push(bounding_string);
             }
-        });
-        match std::fs::File::open("Get_Face_Detection_Results.txt"){
-            Ok(_) => println!("The Face detection results have been successfully written to the current directory with the file name '{}'\n","Get_Face_Detection_Results.txt".green().bold()),
-            Err(_) => println!("Error while Writing the data\n")
+        }
+        match sink.write_artifact("Get_Face_Detection_Results.txt", contents.as_bytes()) {
+            Ok(location) => report!(sink, "The Face detection results have been successfully written to {location} with the file name '{}'\n","Get_Face_Detection_Results.txt".green().bold()),
+            Err(_) => report!(sink, "Error while Writing the data\n")
         }
         create_face_result_pdf(
             &headers,
             face_details_vector,
             &job_id,
             (bucket_name, video_key_name),
-        );
+            sink,
+        )?;
+        Ok(())
     }
 }
@@ -1,26 +1,46 @@
+use std::sync::Arc;
+
+use crate::{
+    error::{Error, SdkResultExt, Service},
+    output::{report, OutputSink, TerminalSink},
+};
 use aws_config::SdkConfig;
 use aws_sdk_route53::Client as Route53Client;
 use colored::Colorize;
 
-pub struct Route53Ops<'a> {
-    config: &'a SdkConfig,
+#[derive(Debug, Clone)]
+pub struct Route53Ops {
+    client: Route53Client,
+    sink: Arc<dyn OutputSink>,
 }
-impl<'a> Route53Ops<'a> {
-    pub fn build(config: &'a SdkConfig) -> Self {
-        Self { config }
+impl Route53Ops {
+    pub fn build(config: &SdkConfig) -> Self {
+        Self {
+            client: Route53Client::new(config),
+            sink: Arc::new(TerminalSink),
+        }
+    }
+    pub fn with_sink(mut self, sink: Arc<dyn OutputSink>) -> Self {
+        self.sink = sink;
+        self
     }
-    pub async fn create_hosted_zone(&self, domain_name: &str, caller_reference: &str) {
-        let client = Route53Client::new(self.config);
-        client
+    pub async fn create_hosted_zone(
+        &self,
+        domain_name: &str,
+        caller_reference: &str,
+    ) -> Result<(), Error> {
+        self.client
             .create_hosted_zone()
             .name(domain_name)
             .caller_reference(caller_reference)
             .send()
             .await
-            .expect("Error while creating domain");
-        println!(
+            .map_sdk_err(Service::Route53, "create_hosted_zone")?;
+        report!(
+            self.sink,
             "The domain name {} has been registered sucessfully",
             domain_name.green().bold()
         );
+        Ok(())
     }
 }
@@ -1,612 +1,4866 @@
 use aws_config::SdkConfig;
+use aws_credential_types::provider::ProvideCredentials;
 use aws_sdk_s3::{
-    presigning::PresigningConfig,
+    config::{AsyncSleep, Builder as S3ConfigBuilder},
+    operation::{head_object::HeadObjectOutput, list_parts::ListPartsOutput},
+    presigning::{PresignedRequest, PresigningConfig},
     primitives::ByteStream,
     types::{
-        BucketLocationConstraint, CompletedMultipartUpload, CompletedPart,
-        CreateBucketConfiguration, ObjectCannedAcl,
+        AccessControlPolicy, BucketLifecycleConfiguration, BucketLocationConstraint, ChecksumMode,
+        CompletedMultipartUpload, CompletedPart, CorsConfiguration, CreateBucketConfiguration,
+        Delete, ExpressionType, MetadataDirective, Object, ObjectCannedAcl, ObjectIdentifier,
+        ObjectLockLegalHold, ObjectLockLegalHoldStatus, RequestProgress,
+        ServerSideEncryption as SseAlgorithm, ServerSideEncryptionByDefault,
+        ServerSideEncryptionConfiguration, ServerSideEncryptionRule, StorageClass,
+        TaggingDirective, VersioningConfiguration,
     },
     Client as S3Client,
 };
+use aws_sigv4::sign::{calculate_signature, generate_signing_key};
+use aws_smithy_async::rt::sleep::default_async_sleep;
+use aws_smithy_types::base64;
+use chrono::{DateTime, Utc};
 use colored::Colorize;
 use dotenv::dotenv;
-use regex::Regex;
+use futures_util::{stream, TryStreamExt};
+use md5::{Digest, Md5};
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use tokio_stream::StreamExt;
+use zeroize::Zeroizing;
+
+use crate::{
+    bucket_config::{BucketConfig, CorsRule, LifecycleRule, PublicAccessBlock, Versioning},
+    bucket_usage::{BucketUsageReport, IncompleteUpload, UsageAggregator, UsageOptions},
+    checksums::{checksum_of, checksum_of_file, composite_checksum, Checksum},
+    content_type::detect_content_type,
+    envelope::{
+        envelope_metadata, random_bytes, read_envelope_metadata, ChunkCipher, KeyWrapper,
+        CHUNK_SIZE, SEALED_CHUNK_SIZE,
+    },
+    error::{Error, SdkResultExt, Service},
+    key_paths::{ConflictPolicy, KeyPathMapper},
+    object_lock::{
+        legal_hold_status, DefaultRetention, ObjectRetention, RetentionMode, RetentionPeriod,
+    },
+    output::{report, OutputSink, TerminalSink},
+    pagination::{collect_items, items_of_pages, ItemStream},
+    progress::{format_bytes, ProgressListener, ProgressTracker, TransferDirection},
+    s3_select::{SelectCompression, SelectInput, SelectOutput, SelectStream},
+    static_site::{public_read_policy, website_endpoint, SiteDeployment, SiteOptions},
+};
 use std::{
+    collections::{BTreeMap, HashMap, HashSet},
     env::var,
-    fs::{create_dir, File, OpenOptions},
-    io::Write,
+    fs::{self, File, OpenOptions},
+    io::{BufRead, BufReader, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
     time::{Duration, SystemTime},
 };
+
+//S3 rejects parts under 5 MiB, except the last one, and uploads of more than 10,000 parts
+const MIN_PART_SIZE: u64 = 5 * 1024 * 1024;
+const MAX_PARTS: u64 = 10_000;
+//Tags are sent as a URL query string in which only the unreserved characters stay as they are
+const TAG_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+//The key in a copy source is URL-encoded too, but keeps its slashes
+const COPY_SOURCE_ENCODE_SET: &AsciiSet = &TAG_ENCODE_SET.remove(b'/');
+//CopyObject takes objects of up to 5 GiB; larger ones are copied in parts of this size
+const MAX_COPY_SIZE: u64 = 5 * 1024 * 1024 * 1024;
+const COPY_PART_SIZE: u64 = 512 * 1024 * 1024;
+
+//The request builders share the encryption setters but no trait, so they're applied by macro
+macro_rules! with_customer_key {
+    ($request:expr, $key:expr) => {
+        match $key {
+            Some(key) => $request
+                .sse_customer_algorithm("AES256")
+                .sse_customer_key(key.key_base64())
+                .sse_customer_key_md5(key.key_md5_base64()),
+            None => $request,
+        }
+    };
+}
+macro_rules! with_copy_source_key {
+    ($request:expr, $key:expr) => {
+        match $key {
+            Some(key) => $request
+                .copy_source_sse_customer_algorithm("AES256")
+                .copy_source_sse_customer_key(key.key_base64())
+                .copy_source_sse_customer_key_md5(key.key_md5_base64()),
+            None => $request,
+        }
+    };
+}
+macro_rules! with_encryption {
+    ($request:expr, $encryption:expr) => {
+        match $encryption {
+            Some(ServerSideEncryption::S3) => $request.server_side_encryption(SseAlgorithm::Aes256),
+            Some(ServerSideEncryption::Kms {
+                key_id,
+                context,
+                bucket_key,
+            }) => $request
+                .server_side_encryption(SseAlgorithm::AwsKms)
+                .set_ssekms_key_id(key_id.clone())
+                .set_ssekms_encryption_context(kms_context(context))
+                .set_bucket_key_enabled(*bucket_key),
+            Some(ServerSideEncryption::Customer(key)) => with_customer_key!($request, Some(key)),
+            None => $request,
+        }
+    };
+}
+macro_rules! with_checksum {
+    ($request:expr, $checksum:expr) => {
+        match $checksum {
+            Some((Checksum::Crc32c, value)) => $request.checksum_crc32_c(value),
+            Some((Checksum::Sha256, value)) => $request.checksum_sha256(value),
+            None => $request,
+        }
+    };
+}
+macro_rules! stored_checksum {
+    ($output:expr, $checksum:expr) => {
+        match $checksum {
+            Checksum::Crc32c => $output.checksum_crc32_c(),
+            Checksum::Sha256 => $output.checksum_sha256(),
+        }
+        .map(|value| value.to_string())
+    };
+}
+macro_rules! with_upload_options {
+    ($request:expr, $options:expr, $content_type:expr, $metadata:expr) => {
+        with_encryption!($request, $options.encryption.as_ref())
+            .set_content_type($content_type)
+            .set_metadata($metadata)
+            .set_tagging($options.tagging())
+            .set_cache_control($options.cache_control.clone())
+            .set_content_disposition($options.content_disposition.clone())
+            .set_storage_class($options.storage_class.clone())
+            .set_object_lock_mode(
+                $options
+                    .retention
+                    .map(|retention| retention.mode().to_object_mode()),
+            )
+            .set_object_lock_retain_until_date(
+                $options
+                    .retention
+                    .map(|retention| retention.retain_until_date()),
+            )
+            .set_object_lock_legal_hold_status($options.legal_hold.then(|| legal_hold_status(true)))
+    };
+}
+
+/// How [`S3Ops`] splits large transfers into parts, set with [`S3Ops::with_multipart`]:
+/// uploads through [`S3Ops::mulitpart_upload`] and downloads through the byte-range GETs of
+/// [`S3Ops::download_to_file`].
+///
+/// The defaults are 8 MiB parts, four parts in flight, three attempts per part and a
+/// 16 MiB threshold above which transfers are split. Files that would need more than
+/// 10,000 parts get larger parts automatically.
+#[derive(Debug, Clone)]
+pub struct MultipartConfig {
+    part_size: u64,
+    concurrency: usize,
+    max_attempts: u32,
+    threshold: u64,
+    abort_on_failure: bool,
+}
+
+impl Default for MultipartConfig {
+    fn default() -> Self {
+        Self {
+            part_size: 8 * 1024 * 1024,
+            concurrency: 4,
+            max_attempts: 3,
+            threshold: 16 * 1024 * 1024,
+            abort_on_failure: true,
+        }
+    }
+}
+
+impl MultipartConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// The size of every part but the last, in bytes. Values under 5 MiB are raised to it.
+    pub fn part_size(mut self, bytes: u64) -> Self {
+        self.part_size = bytes.max(MIN_PART_SIZE);
+        self
+    }
+    /// How many parts are uploaded at the same time. Each one is held in memory while it's
+    /// being sent.
+    pub fn concurrency(mut self, parts: usize) -> Self {
+        self.concurrency = parts.max(1);
+        self
+    }
+    /// How many times a part is sent before the upload fails, counting the first try. Only
+    /// errors for which [`Error::is_retryable`] holds are tried again.
+    pub fn max_attempts(mut self, attempts: u32) -> Self {
+        self.max_attempts = attempts.max(1);
+        self
+    }
+    /// Files of at least this many bytes are sent as a multipart upload by
+    /// [`S3Ops::upload_content_to_a_bucket`], and objects of at least this size are
+    /// downloaded in parallel ranges.
+    pub fn threshold(mut self, bytes: u64) -> Self {
+        self.threshold = bytes;
+        self
+    }
+    /// Whether a failed upload is aborted, which deletes the parts already sent. When it's
+    /// turned off the parts are kept, the upload ID is reported, and
+    /// [`S3Ops::resume_multipart_upload`] can finish the upload later.
+    pub fn abort_on_failure(mut self, abort: bool) -> Self {
+        self.abort_on_failure = abort;
+        self
+    }
+    fn part_size_for(&self, file_size: u64) -> u64 {
+        self.part_size.max((file_size + MAX_PARTS - 1) / MAX_PARTS)
+    }
+}
+
+/// Which files [`S3Ops::sync_up`] and [`S3Ops::sync_down`] look at and what they do with
+/// the ones missing on the other side, after the options of `aws s3 sync`.
+///
+/// Patterns are matched against paths relative to the synced directory or prefix, with `/`
+/// as the separator; `*` matches any run of characters including `/` and `?` matches one
+/// character. As in the AWS CLI, everything is included to begin with and the filters are
+/// applied in the order they're given, so a later pattern overrides an earlier one.
+#[derive(Debug, Clone, Default)]
+pub struct SyncOptions {
+    filters: Vec<(bool, String)>,
+    delete: bool,
+    dry_run: bool,
+}
+
+impl SyncOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn include(mut self, pattern: impl Into<String>) -> Self {
+        self.filters.push((true, pattern.into()));
+        self
+    }
+    pub fn exclude(mut self, pattern: impl Into<String>) -> Self {
+        self.filters.push((false, pattern.into()));
+        self
+    }
+    /// Deletes the files or objects of the destination that have no counterpart in the
+    /// source. Excluded paths are never deleted.
+    pub fn delete(mut self, delete: bool) -> Self {
+        self.delete = delete;
+        self
+    }
+    /// Only reports what would be transferred and deleted.
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+    fn is_included(&self, relative_path: &str) -> bool {
+        self.filters
+            .iter()
+            .rev()
+            .find(|(_, pattern)| glob_match(pattern, relative_path))
+            .map(|(include, _)| *include)
+            .unwrap_or(true)
+    }
+}
+
+/// How S3 encrypts an object at rest.
+#[derive(Debug, Clone)]
+pub enum ServerSideEncryption {
+    /// SSE-S3: S3 encrypts the object with keys it manages.
+    S3,
+    /// SSE-KMS: the object's data key is protected by a KMS key, the account's `aws/s3` key
+    /// when `key_id` is `None`. The encryption context is logged in CloudTrail and must be
+    /// allowed by the key policy; S3 Bucket Keys cut the number of KMS requests.
+    Kms {
+        key_id: Option<String>,
+        context: BTreeMap<String, String>,
+        bucket_key: Option<bool>,
+    },
+    /// SSE-C: S3 encrypts the object with a key the caller keeps and has to send again with
+    /// every read. S3 doesn't store the key, so an object whose key is lost can't be read.
+    Customer(CustomerKey),
+}
+
+impl ServerSideEncryption {
+    /// SSE-KMS with the given key ID, alias or ARN and no encryption context.
+    pub fn kms(key_id: impl Into<String>) -> Self {
+        Self::Kms {
+            key_id: Some(key_id.into()),
+            context: BTreeMap::new(),
+            bucket_key: None,
+        }
+    }
+    fn customer_key(&self) -> Option<&CustomerKey> {
+        match self {
+            Self::Customer(key) => Some(key),
+            _ => None,
+        }
+    }
+}
+
+/// A 256-bit AES key for SSE-C. It's wiped from memory when dropped and never printed.
+#[derive(Clone)]
+pub struct CustomerKey(Zeroizing<[u8; 32]>);
+
+impl CustomerKey {
+    pub fn new(key: [u8; 32]) -> Self {
+        Self(Zeroizing::new(key))
+    }
+    /// Reads a key in the base64 form the AWS CLI and the SSE-C headers use.
+    pub fn from_base64(key: &str) -> Result<Self, Error> {
+        let decoded = Zeroizing::new(
+            base64::decode(key.trim())
+                .map_err(|err| Error::invalid_input("CustomerKey", err.to_string()))?,
+        );
+        let key = <[u8; 32]>::try_from(decoded.as_slice()).map_err(|_| {
+            Error::invalid_input(
+                "CustomerKey",
+                format!("an SSE-C key has 32 bytes, not {}", decoded.len()),
+            )
+        })?;
+        Ok(Self::new(key))
+    }
+    fn key_base64(&self) -> String {
+        base64::encode(self.0.as_slice())
+    }
+    fn key_md5_base64(&self) -> String {
+        base64::encode(Md5::digest(self.0.as_slice()))
+    }
+}
+
+impl std::fmt::Debug for CustomerKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("CustomerKey(<redacted>)")
+    }
+}
+
+/// Settings for the object written by [`S3Ops::upload_with_options`].
+///
+/// Unless a content type is given, it's detected from the file's extension or, failing
+/// that, its first bytes, so browsers render pages and images instead of downloading them.
+/// Files that match neither are left for S3 to label `binary/octet-stream`.
+#[derive(Debug, Clone)]
+pub struct UploadOptions {
+    encryption: Option<ServerSideEncryption>,
+    content_type: Option<String>,
+    detect_content_type: bool,
+    metadata: HashMap<String, String>,
+    tags: Vec<(String, String)>,
+    cache_control: Option<String>,
+    content_disposition: Option<String>,
+    storage_class: Option<StorageClass>,
+    checksum: Option<Checksum>,
+    retention: Option<ObjectRetention>,
+    legal_hold: bool,
+}
+
+impl Default for UploadOptions {
+    fn default() -> Self {
+        Self {
+            encryption: None,
+            content_type: None,
+            detect_content_type: true,
+            metadata: HashMap::new(),
+            tags: Vec::new(),
+            cache_control: None,
+            content_disposition: None,
+            storage_class: None,
+            checksum: None,
+            retention: None,
+            legal_hold: false,
+        }
+    }
+}
+
+impl UploadOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Encrypts the object this way instead of with the bucket's default encryption.
+    pub fn encryption(mut self, encryption: ServerSideEncryption) -> Self {
+        self.encryption = Some(encryption);
+        self
+    }
+    /// Sets the content type instead of detecting it.
+    pub fn content_type(mut self, content_type: impl Into<String>) -> Self {
+        self.content_type = Some(content_type.into());
+        self
+    }
+    pub fn detect_content_type(mut self, detect: bool) -> Self {
+        self.detect_content_type = detect;
+        self
+    }
+    /// Adds user metadata, stored as an `x-amz-meta-` header. S3 lowercases the name.
+    pub fn metadata(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata.insert(name.into(), value.into());
+        self
+    }
+    /// Adds an object tag; S3 allows up to ten per object.
+    pub fn tag(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.tags.push((key.into(), value.into()));
+        self
+    }
+    /// The Cache-Control header S3 serves the object with, such as `max-age=86400`.
+    pub fn cache_control(mut self, cache_control: impl Into<String>) -> Self {
+        self.cache_control = Some(cache_control.into());
+        self
+    }
+    /// The Content-Disposition header S3 serves the object with, such as
+    /// `attachment; filename="report.pdf"`.
+    pub fn content_disposition(mut self, content_disposition: impl Into<String>) -> Self {
+        self.content_disposition = Some(content_disposition.into());
+        self
+    }
+    /// The storage class by its S3 name, such as `STANDARD_IA`, `INTELLIGENT_TIERING`,
+    /// `GLACIER_IR` or `DEEP_ARCHIVE`. Objects are stored as `STANDARD` otherwise.
+    pub fn storage_class(mut self, storage_class: &str) -> Self {
+        self.storage_class = Some(StorageClass::from(storage_class));
+        self
+    }
+    /// Computes this checksum of the file, or of every part of a multipart upload, and
+    /// sends it along. S3 rejects data that doesn't match and keeps the checksum with the
+    /// object, where downloads check against it.
+    pub fn checksum(mut self, checksum: Checksum) -> Self {
+        self.checksum = Some(checksum);
+        self
+    }
+    /// Locks the object against being overwritten or deleted until the retention ends. The
+    /// bucket needs Object Lock, and since S3 only takes locked uploads that carry a
+    /// checksum, CRC32C is sent unless another [`checksum`](Self::checksum) was chosen.
+    pub fn retention(mut self, retention: ObjectRetention) -> Self {
+        self.retention = Some(retention);
+        self.checksum.get_or_insert(Checksum::Crc32c);
+        self
+    }
+    /// Places a legal hold on the object, which keeps it from being deleted until the hold
+    /// is lifted with [`S3Ops::put_object_legal_hold`], whatever its retention. Like
+    /// [`retention`](Self::retention) it needs Object Lock and a checksum.
+    pub fn legal_hold(mut self, on: bool) -> Self {
+        self.legal_hold = on;
+        if on {
+            self.checksum.get_or_insert(Checksum::Crc32c);
+        }
+        self
+    }
+    fn customer_key(&self) -> Option<&CustomerKey> {
+        self.encryption
+            .as_ref()
+            .and_then(ServerSideEncryption::customer_key)
+    }
+    fn content_type_for(&self, path: &str) -> Option<String> {
+        self.content_type.clone().or_else(|| {
+            self.detect_content_type
+                .then(|| detect_content_type(Path::new(path)))
+                .flatten()
+                .map(|content_type| content_type.to_string())
+        })
+    }
+    fn user_metadata(&self) -> Option<HashMap<String, String>> {
+        (!self.metadata.is_empty()).then(|| self.metadata.clone())
+    }
+    fn tagging(&self) -> Option<String> {
+        tagging_header(&self.tags)
+    }
+}
+
+/// How [`S3Ops::copy_object`], [`S3Ops::move_object`] and [`S3Ops::rename_prefix`] treat the
+/// copies. Unless told otherwise they keep the source's metadata, tags and ACL grants, and
+/// are encrypted and stored as the destination bucket does by default.
+#[derive(Debug, Clone, Default)]
+pub struct CopyOptions {
+    metadata: Option<UploadOptions>,
+    tags: Option<Vec<(String, String)>>,
+    acl: Option<ObjectCannedAcl>,
+    encryption: Option<ServerSideEncryption>,
+    storage_class: Option<StorageClass>,
+}
+
+impl CopyOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Replaces the content type, user metadata, Cache-Control and Content-Disposition with
+    /// those of the options; nothing else is taken from them.
+    pub fn replace_metadata(mut self, metadata: UploadOptions) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+    /// Replaces the tags; an empty list leaves the copies untagged.
+    pub fn replace_tags(mut self, tags: Vec<(String, String)>) -> Self {
+        self.tags = Some(tags);
+        self
+    }
+    /// Gives the copies a canned ACL, such as `private` or `bucket-owner-full-control`,
+    /// instead of the source's grants.
+    pub fn replace_acl(mut self, acl: &str) -> Self {
+        self.acl = Some(ObjectCannedAcl::from(acl));
+        self
+    }
+    pub fn encryption(mut self, encryption: ServerSideEncryption) -> Self {
+        self.encryption = Some(encryption);
+        self
+    }
+    /// The storage class by its S3 name; copies are `STANDARD` otherwise.
+    pub fn storage_class(mut self, storage_class: &str) -> Self {
+        self.storage_class = Some(StorageClass::from(storage_class));
+        self
+    }
+    fn customer_key(&self) -> Option<&CustomerKey> {
+        self.encryption
+            .as_ref()
+            .and_then(ServerSideEncryption::customer_key)
+    }
+}
+
+/// The outcome of [`S3Ops::rename_prefix`].
+#[derive(Debug, Default)]
+pub struct RenameReport {
+    renamed: Vec<String>,
+    failed: Vec<(String, Error)>,
+}
+
+impl RenameReport {
+    /// The source keys that were copied and then deleted.
+    pub fn renamed(&self) -> &[String] {
+        &self.renamed
+    }
+    /// The source keys that weren't renamed, with the reason. A key whose copy succeeded but
+    /// whose deletion failed exists under both prefixes.
+    pub fn failed(&self) -> &[(String, Error)] {
+        &self.failed
+    }
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+/// A checksum computed on this machine next to the one S3 stored for the same object.
+#[derive(Debug, Clone)]
+pub struct TransferChecksum {
+    algorithm: Checksum,
+    computed: String,
+    stored: Option<String>,
+}
+
+impl TransferChecksum {
+    pub fn algorithm(&self) -> Checksum {
+        self.algorithm
+    }
+    /// The base64 checksum, followed by `-` and the part count for multipart objects.
+    pub fn computed(&self) -> &str {
+        &self.computed
+    }
+    /// What S3 reported, or `None` if it didn't report a checksum of this kind.
+    pub fn stored(&self) -> Option<&str> {
+        self.stored.as_deref()
+    }
+    pub fn is_verified(&self) -> bool {
+        self.stored.as_deref() == Some(self.computed.as_str())
+    }
+}
+
+/// The result of an upload or download that was checked end to end.
+#[derive(Debug, Clone)]
+pub struct TransferResult {
+    bucket: String,
+    key: String,
+    size: u64,
+    e_tag: Option<String>,
+    checksum: Option<TransferChecksum>,
+}
+
+impl TransferResult {
+    pub fn bucket(&self) -> &str {
+        &self.bucket
+    }
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+    /// The size of the local file.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+    pub fn e_tag(&self) -> Option<&str> {
+        self.e_tag.as_deref()
+    }
+    /// `None` when no checksum was asked for on upload or stored with a downloaded object.
+    pub fn checksum(&self) -> Option<&TransferChecksum> {
+        self.checksum.as_ref()
+    }
+}
+
+//What every part of one multipart upload is sent with
+#[derive(Clone, Copy, Default)]
+struct PartOptions<'a> {
+    customer_key: Option<&'a CustomerKey>,
+    cipher: Option<&'a ChunkCipher>,
+    checksum: Option<Checksum>,
+}
+
+/// What [`S3Ops::get_object_info`] found out about an object.
+#[derive(Debug, Clone)]
+pub struct ObjectInfo {
+    content_type: Option<String>,
+    content_length: u64,
+    e_tag: Option<String>,
+    last_modified: Option<SystemTime>,
+    metadata: HashMap<String, String>,
+    tags: Vec<(String, String)>,
+    cache_control: Option<String>,
+    content_disposition: Option<String>,
+    storage_class: String,
+    server_side_encryption: Option<String>,
+    kms_key_id: Option<String>,
+    version_id: Option<String>,
+    retention: Option<ObjectRetention>,
+    legal_hold: bool,
+}
+
+impl ObjectInfo {
+    pub fn content_type(&self) -> Option<&str> {
+        self.content_type.as_deref()
+    }
+    pub fn content_length(&self) -> u64 {
+        self.content_length
+    }
+    pub fn e_tag(&self) -> Option<&str> {
+        self.e_tag.as_deref()
+    }
+    pub fn last_modified(&self) -> Option<SystemTime> {
+        self.last_modified
+    }
+    /// User metadata without the `x-amz-meta-` prefix.
+    pub fn metadata(&self) -> &HashMap<String, String> {
+        &self.metadata
+    }
+    pub fn tags(&self) -> &[(String, String)] {
+        &self.tags
+    }
+    pub fn cache_control(&self) -> Option<&str> {
+        self.cache_control.as_deref()
+    }
+    pub fn content_disposition(&self) -> Option<&str> {
+        self.content_disposition.as_deref()
+    }
+    /// The storage class, such as `STANDARD` or `GLACIER_IR`.
+    pub fn storage_class(&self) -> &str {
+        &self.storage_class
+    }
+    /// `AES256`, `aws:kms` or `aws:kms:dsse`; `None` for SSE-C and unencrypted objects.
+    pub fn server_side_encryption(&self) -> Option<&str> {
+        self.server_side_encryption.as_deref()
+    }
+    pub fn kms_key_id(&self) -> Option<&str> {
+        self.kms_key_id.as_deref()
+    }
+    pub fn version_id(&self) -> Option<&str> {
+        self.version_id.as_deref()
+    }
+    /// The Object Lock retention of the version, if it has one.
+    pub fn retention(&self) -> Option<ObjectRetention> {
+        self.retention
+    }
+    pub fn legal_hold(&self) -> bool {
+        self.legal_hold
+    }
+}
+
+/// How [`S3Ops::presign_get`], [`S3Ops::presign_put`] and [`S3Ops::presign_post`] sign a
+/// request, and which artifacts they leave in the output sink.
+#[derive(Debug, Clone)]
+pub struct PresignOptions {
+    expires_in: Duration,
+    content_type: Option<String>,
+    content_length_range: Option<(u64, u64)>,
+    text_artifact: Option<String>,
+    qr_artifact: Option<String>,
+    encryption: Option<ServerSideEncryption>,
+}
+
+impl PresignOptions {
+    /// The URL stays valid for this long, down to the second and up to seven days.
+    pub fn new(expires_in: Duration) -> Self {
+        Self {
+            expires_in,
+            content_type: None,
+            content_length_range: None,
+            text_artifact: None,
+            qr_artifact: None,
+            encryption: None,
+        }
+    }
+    /// The Content-Type an upload must have. A PUT URL signs it as a header the uploader has
+    /// to send; a POST policy adds it as a form field and condition.
+    pub fn content_type(mut self, content_type: impl Into<String>) -> Self {
+        self.content_type = Some(content_type.into());
+        self
+    }
+    /// The smallest and largest upload, in bytes, a POST policy accepts. A PUT URL can't
+    /// limit the size, so [`S3Ops::presign_put`] rejects options that set one.
+    pub fn content_length_range(mut self, min: u64, max: u64) -> Self {
+        self.content_length_range = Some((min, max));
+        self
+    }
+    /// Writes the URL, what to send with it and its expiry to a text artifact of this name.
+    pub fn text_artifact(mut self, name: impl Into<String>) -> Self {
+        self.text_artifact = Some(name.into());
+        self
+    }
+    /// Writes a QR code of the URL to a PNG artifact of this name.
+    pub fn qr_artifact(mut self, name: impl Into<String>) -> Self {
+        self.qr_artifact = Some(name.into());
+        self
+    }
+    /// Signs the encryption headers into a PUT URL, so the upload has to send them. For a GET
+    /// URL only an SSE-C key matters; it has to be sent with the download as well.
+    pub fn encryption(mut self, encryption: ServerSideEncryption) -> Self {
+        self.encryption = Some(encryption);
+        self
+    }
+    fn presigning_config(&self, operation: &'static str) -> Result<PresigningConfig, Error> {
+        PresigningConfig::builder()
+            .start_time(SystemTime::now())
+            .expires_in(self.expires_in)
+            .build()
+            .map_err(|err| Error::invalid_input(operation, err.to_string()))
+    }
+}
+
+/// A presigned request: send [`method`](Self::method) to [`url`](Self::url) together with
+/// [`headers`](Self::headers) before [`expires_at`](Self::expires_at).
+#[derive(Debug, Clone)]
+pub struct PresignedUrl {
+    method: String,
+    url: String,
+    headers: Vec<(String, String)>,
+    expires_at: SystemTime,
+}
+
+impl PresignedUrl {
+    fn new(request: &PresignedRequest, expires_at: SystemTime) -> Self {
+        Self {
+            method: request.method().to_string(),
+            url: request.uri().to_string(),
+            headers: request
+                .headers()
+                .iter()
+                .map(|(name, value)| {
+                    (
+                        name.to_string(),
+                        value.to_str().unwrap_or_default().to_string(),
+                    )
+                })
+                .collect(),
+            expires_at,
+        }
+    }
+    pub fn method(&self) -> &str {
+        &self.method
+    }
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+    /// Headers that were signed along with the URL and must be sent unchanged.
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+    pub fn expires_at(&self) -> SystemTime {
+        self.expires_at
+    }
+}
+
+/// A browser upload form: a `multipart/form-data` POST to [`url`](Self::url) carrying
+/// every one of [`fields`](Self::fields) followed by the `file` field, before
+/// [`expires_at`](Self::expires_at).
+#[derive(Debug, Clone)]
+pub struct PresignedPost {
+    url: String,
+    fields: Vec<(String, String)>,
+    expires_at: SystemTime,
+}
+
+impl PresignedPost {
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+    pub fn fields(&self) -> &[(String, String)] {
+        &self.fields
+    }
+    pub fn expires_at(&self) -> SystemTime {
+        self.expires_at
+    }
+}
+
+/// The outcome of a bulk delete such as [`S3Ops::delete_prefix`].
+#[derive(Debug, Clone, Default)]
+pub struct BulkDeleteReport {
+    deleted: Vec<String>,
+    failed: Vec<DeleteFailure>,
+}
+
+impl BulkDeleteReport {
+    /// The keys that were deleted; a key appears once per deleted version.
+    pub fn deleted(&self) -> &[String] {
+        &self.deleted
+    }
+    pub fn failed(&self) -> &[DeleteFailure] {
+        &self.failed
+    }
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+/// An object S3 refused to delete, with the error code and message it gave, such as
+/// `AccessDenied`.
+#[derive(Debug, Clone)]
+pub struct DeleteFailure {
+    key: String,
+    version_id: Option<String>,
+    code: String,
+    message: String,
+}
+
+impl DeleteFailure {
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+    pub fn version_id(&self) -> Option<&str> {
+        self.version_id.as_deref()
+    }
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+/// What a sync did, or with [`SyncOptions::dry_run`], what it would have done. Paths are
+/// relative to the synced directory and prefix.
+#[derive(Debug, Clone, Default)]
+pub struct SyncReport {
+    transferred: Vec<String>,
+    deleted: Vec<String>,
+    unchanged: usize,
+    dry_run: bool,
+}
+
+impl SyncReport {
+    pub fn transferred(&self) -> &[String] {
+        &self.transferred
+    }
+    pub fn deleted(&self) -> &[String] {
+        &self.deleted
+    }
+    /// How many files were already up to date.
+    pub fn unchanged(&self) -> usize {
+        self.unchanged
+    }
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+}
 /// The core structure for performing operations on the [`S3 client`](https://docs.rs/aws-sdk-s3/latest/aws_sdk_s3/struct.Client.html) eliminates the need for
 /// API users to provide credentials each time they use the service. Instead,
 /// these credentials are abstracted by this structure and its inherent functions
 /// and methods
-#[derive(Debug)]
-pub struct S3Ops<'a> {
-    config: &'a SdkConfig,
+///
+/// The underlying client is built once in [`S3Ops::build`] and shared by every
+/// method, so cloning an `S3Ops` is cheap and the clones reuse the same connection
+/// pool. It can be kept in application state and used from many tasks at once.
+#[derive(Debug, Clone)]
+pub struct S3Ops {
+    config: SdkConfig,
+    client: S3Client,
+    sink: Arc<dyn OutputSink>,
+    multipart: MultipartConfig,
+    conflict_policy: ConflictPolicy,
+    customer_key: Option<CustomerKey>,
+    progress: Option<Arc<dyn ProgressListener>>,
 }
-impl<'a> S3Ops<'a> {
+impl S3Ops {
     /// This function accepts an [`SdkConfig`](https://docs.rs/aws-config/latest/aws_config/struct.SdkConfig.html), retrieves the region name from it if
-    /// available; otherwise, it sets it to an empty string and then constructs a S3Ops instance   
-    pub fn build(config: &'a SdkConfig) -> Self {
-        Self { config: config }
+    /// available; otherwise, it sets it to an empty string and then constructs a S3Ops instance
+    /// along with the S3 client used by all of its methods
+    pub fn build(config: &SdkConfig) -> Self {
+        Self {
+            config: config.clone(),
+            client: S3Client::new(config),
+            sink: Arc::new(TerminalSink),
+            multipart: MultipartConfig::default(),
+            conflict_policy: ConflictPolicy::default(),
+            customer_key: None,
+            progress: None,
+        }
+    }
+    pub fn with_sink(mut self, sink: Arc<dyn OutputSink>) -> Self {
+        self.sink = sink;
+        self
+    }
+    pub fn with_multipart(mut self, multipart: MultipartConfig) -> Self {
+        self.multipart = multipart;
+        self
+    }
+    /// What downloads do when the file an object maps to already exists; they overwrite it
+    /// unless told otherwise.
+    pub fn with_conflict_policy(mut self, policy: ConflictPolicy) -> Self {
+        self.conflict_policy = policy;
+        self
+    }
+    /// The SSE-C key that downloads send, needed to read objects uploaded with
+    /// [`ServerSideEncryption::Customer`].
+    pub fn with_customer_key(mut self, key: CustomerKey) -> Self {
+        self.customer_key = Some(key);
+        self
+    }
+    /// Reports the progress of every upload, download and copy to the listener, such as a
+    /// [`TerminalProgress`](crate::TerminalProgress) bar or a
+    /// [`ChannelProgress`](crate::ChannelProgress) feeding a GUI.
+    pub fn with_progress(mut self, listener: Arc<dyn ProgressListener>) -> Self {
+        self.progress = Some(listener);
+        self
+    }
+    /// Addresses buckets as `endpoint/bucket/key` rather than `bucket.endpoint/key`. MinIO
+    /// and LocalStack need this. [`CredentInitialize::s3_ops`](crate::CredentInitialize::s3_ops)
+    /// turns it on when [`Endpoints::s3_path_style`](crate::Endpoints::s3_path_style) is set.
+    pub fn with_path_style(mut self, enabled: bool) -> Self {
+        let config = S3ConfigBuilder::from(&self.config)
+            .force_path_style(enabled)
+            .build();
+        self.client = S3Client::from_conf(config);
+        self
     }
 
     ///Create a new bucket in your AWS account and ensure you specify the region
     /// name; otherwise, you may receive a panic message from AWS APIs
-    pub async fn create_bucket(&self, bucket_name: &str) {
+    pub async fn create_bucket(&self, bucket_name: &str) -> Result<(), Error> {
+        self.client
+            .create_bucket()
+            .bucket(bucket_name)
+            .create_bucket_configuration(self.bucket_location())
+            .send()
+            .await
+            .map_sdk_err(Service::S3, "create_bucket")?;
+        let colored_bucket = bucket_name.green().bold();
+        report!(
+            self.sink,
+            "Congratulations! The bucket with the name {colored_bucket} has been successfully created\n"
+        );
+        Ok(())
+    }
+    /// Creates a bucket with Object Lock, so its object versions can be stored as WORM
+    /// (write once, read many): locked versions can't be overwritten or deleted until their
+    /// retention ends. S3 turns versioning on for the bucket, and neither can be turned off
+    /// again. With a default retention, every new version is locked for that long unless its
+    /// upload sets its own [`UploadOptions::retention`]; uploads then need a
+    /// [`UploadOptions::checksum`]
+    pub async fn create_bucket_with_object_lock(
+        &self,
+        bucket_name: &str,
+        default_retention: Option<DefaultRetention>,
+    ) -> Result<(), Error> {
+        self.client
+            .create_bucket()
+            .bucket(bucket_name)
+            .create_bucket_configuration(self.bucket_location())
+            .object_lock_enabled_for_bucket(true)
+            .send()
+            .await
+            .map_sdk_err(Service::S3, "create_bucket")?;
+        report!(
+            self.sink,
+            "The bucket {} has been created with Object Lock\n",
+            bucket_name.green().bold()
+        );
+        if let Some(retention) = default_retention {
+            self.put_default_retention(bucket_name, retention).await?;
+        }
+        Ok(())
+    }
+    fn bucket_location(&self) -> CreateBucketConfiguration {
         dotenv().ok();
-        let client = S3Client::new(self.config);
         let region_name = match self.config.region(){
              Some(region) => region.to_string(),
              None => var("REGION").unwrap_or("The region value is read from the .env file in the current directory if it is not provided in the credential file".into())
         };
         let constraint = BucketLocationConstraint::from(region_name.as_str());
-        let location = CreateBucketConfiguration::builder()
+        CreateBucketConfiguration::builder()
             .location_constraint(constraint)
-            .build();
-
-        let client = client
-            .create_bucket()
-            .bucket(bucket_name)
-            .create_bucket_configuration(location);
-        let colored_msg = "Error from create_bucket function".red().bold();
-        client
-            .send()
-            .await
-            .map(|_| {
-                let colored_bucket = bucket_name.green().bold();
-                println!(
-                    "Congratulations! The bucket with the name {colored_bucket} has been successfully created\n"
-                );
-            })
-            .expect(&colored_msg);
+            .build()
     }
 
     /// Return the available buckets in your account as a vector of strings
-    pub async fn get_buckets(&self) -> Vec<String> {
-        let client = S3Client::new(self.config);
-
+    pub async fn get_buckets(&self) -> Result<Vec<String>, Error> {
         let mut bucket_lists = Vec::new();
-        let colored_msg = "Error from get_buckets function\n".red().bold();
-        let output = client.list_buckets().send().await.expect(&colored_msg);
+        let output = self
+            .client
+            .list_buckets()
+            .send()
+            .await
+            .map_sdk_err(Service::S3, "list_buckets")?;
         let bucket_list = output.buckets();
 
         if let Some(bucket_names) = bucket_list {
             bucket_names.iter().for_each(|bucket| {
-                bucket_lists.push(bucket.name().unwrap().to_string());
+                if let Some(name) = bucket.name() {
+                    bucket_lists.push(name.to_string());
+                }
             })
         }
 
-        bucket_lists
+        Ok(bucket_lists)
     }
 
     /// Delete the bucket from your AWS services if the specified bucket is
     ///  available and the credentials have the necessary rights.
-    pub async fn delete_bucket(&self, bucket_name: &str) {
-        let client = S3Client::new(self.config);
-
-        let client = client.delete_bucket().bucket(bucket_name);
-        let colored_msg = "Error from delete_bucket function\n".red().bold();
-        client.send().await.expect(&colored_msg);
-        let current_buckets = self.get_buckets().await;
-        println!("Currently available buckets in your aws account\n");
+    pub async fn delete_bucket(&self, bucket_name: &str) -> Result<(), Error> {
+        self.client
+            .delete_bucket()
+            .bucket(bucket_name)
+            .send()
+            .await
+            .map_sdk_err(Service::S3, "delete_bucket")?;
+        let current_buckets = self.get_buckets().await?;
+        report!(
+            self.sink,
+            "Currently available buckets in your aws account\n"
+        );
         current_buckets.into_iter().for_each(|bucket| {
             let bucket = bucket.green().bold();
-            println!("{bucket}\n");
-        })
+            report!(self.sink, "{bucket}\n");
+        });
+        Ok(())
     }
+    /// Empties a bucket completely and deletes it, even when it's versioned: every object
+    /// version and delete marker is removed and pending multipart uploads are aborted.
+    ///
+    /// Nothing can be recovered afterwards, so `confirmation` must repeat the bucket name;
+    /// anything else is rejected before a single request is sent. If S3 refuses to delete
+    /// some objects, the bucket is kept and the error says how many were left.
+    pub async fn delete_bucket_force(
+        &self,
+        bucket_name: &str,
+        confirmation: &str,
+    ) -> Result<BulkDeleteReport, Error> {
+        if confirmation != bucket_name {
+            return Err(Error::invalid_input(
+                "delete_bucket_force",
+                format!("pass the bucket name '{bucket_name}' as the confirmation to delete it with all its contents"),
+            ));
+        }
+        //Uploads have to be aborted first; their parts aren't object versions
+        let (mut key_marker, mut upload_id_marker) = (None, None);
+        loop {
+            let uploads = self
+                .client
+                .list_multipart_uploads()
+                .bucket(bucket_name)
+                .set_key_marker(key_marker)
+                .set_upload_id_marker(upload_id_marker)
+                .send()
+                .await
+                .map_sdk_err(Service::S3, "list_multipart_uploads")?;
+            for upload in uploads.uploads().unwrap_or_default() {
+                self.client
+                    .abort_multipart_upload()
+                    .bucket(bucket_name)
+                    .key(upload.key().unwrap_or_default())
+                    .upload_id(upload.upload_id().unwrap_or_default())
+                    .send()
+                    .await
+                    .map_sdk_err(Service::S3, "abort_multipart_upload")?;
+                report!(
+                    self.sink,
+                    "abort: the multipart upload of s3://{bucket_name}/{}",
+                    upload.key().unwrap_or_default().red().bold()
+                );
+            }
+            if !uploads.is_truncated() {
+                break;
+            }
+            key_marker = uploads.next_key_marker().map(|marker| marker.to_string());
+            upload_id_marker = uploads
+                .next_upload_id_marker()
+                .map(|marker| marker.to_string());
+        }
 
-    ///These methods work on Ubuntu but not on Windows due to differences in stack size. In Ubuntu, the stack size is larger than in Windows, which is why it causes a stack overflow in Windows. As a result, I tested these methods on Ubuntu successfully but encountered a stack overflow issue on Windows.
-    ///I attempted to use these methods in a different thread with a stack size of (32*1024*1024), but it still resulted in a stack overflow
-    /// Ensure successful execution in release mode by using cargo build --release.
-    /// If you have any solutions or suggestions to address this issue in build time, please let
-    /// me know by leaving a comment [`here`]()
-
-    /// Retrieve the objects/keys from a specified bucket.
-    pub async fn retrieve_keys_in_a_bucket(&self, bucket_name: &str) -> Vec<String> {
-        let client = S3Client::new(self.config);
-
-        let mut objects_in_bucket = Vec::new();
-        let outputs = client
-            .list_objects_v2()
+        let mut delete_report = BulkDeleteReport::default();
+        let (mut key_marker, mut version_id_marker) = (None, None);
+        loop {
+            let page = self
+                .client
+                .list_object_versions()
+                .bucket(bucket_name)
+                .set_key_marker(key_marker)
+                .set_version_id_marker(version_id_marker)
+                .send()
+                .await
+                .map_sdk_err(Service::S3, "list_object_versions")?;
+            let versions = page
+                .versions()
+                .unwrap_or_default()
+                .iter()
+                .map(|version| (version.key(), version.version_id()));
+            let markers = page
+                .delete_markers()
+                .unwrap_or_default()
+                .iter()
+                .map(|marker| (marker.key(), marker.version_id()));
+            let objects = versions
+                .chain(markers)
+                .map(|(key, version_id)| {
+                    ObjectIdentifier::builder()
+                        .key(key.unwrap_or_default())
+                        .set_version_id(version_id.map(|version_id| version_id.to_string()))
+                        .build()
+                })
+                .collect::<Vec<_>>();
+            let deleted = self.delete_identifiers(bucket_name, objects).await?;
+            delete_report.deleted.extend(deleted.deleted);
+            delete_report.failed.extend(deleted.failed);
+            if !page.is_truncated() {
+                break;
+            }
+            key_marker = page.next_key_marker().map(|marker| marker.to_string());
+            version_id_marker = page
+                .next_version_id_marker()
+                .map(|marker| marker.to_string());
+        }
+        if !delete_report.is_complete() {
+            return Err(Error::other(
+                "delete_bucket_force",
+                format!(
+                    "{} object versions could not be deleted, so the bucket '{bucket_name}' was kept",
+                    delete_report.failed.len()
+                ),
+            ));
+        }
+        self.delete_bucket(bucket_name).await?;
+        Ok(delete_report)
+    }
+    /// Sets the encryption S3 applies to new objects that don't ask for any. SSE-C can't be a
+    /// bucket default and an encryption context can't be set for one, so both are rejected
+    pub async fn set_bucket_default_encryption(
+        &self,
+        bucket_name: &str,
+        encryption: &ServerSideEncryption,
+    ) -> Result<(), Error> {
+        let operation = "put_bucket_encryption";
+        let (algorithm, key_id, bucket_key) = match encryption {
+            ServerSideEncryption::S3 => (SseAlgorithm::Aes256, None, None),
+            ServerSideEncryption::Kms {
+                key_id,
+                context,
+                bucket_key,
+            } => {
+                if !context.is_empty() {
+                    return Err(Error::invalid_input(
+                        operation,
+                        "a bucket's default encryption can't have an encryption context",
+                    ));
+                }
+                (SseAlgorithm::AwsKms, key_id.clone(), *bucket_key)
+            }
+            ServerSideEncryption::Customer(_) => {
+                return Err(Error::invalid_input(
+                    operation,
+                    "SSE-C keys are sent with each request and can't be a bucket default",
+                ))
+            }
+        };
+        let by_default = ServerSideEncryptionByDefault::builder()
+            .sse_algorithm(algorithm)
+            .set_kms_master_key_id(key_id)
+            .build();
+        let rule = ServerSideEncryptionRule::builder()
+            .apply_server_side_encryption_by_default(by_default)
+            .set_bucket_key_enabled(bucket_key)
+            .build();
+        self.client
+            .put_bucket_encryption()
             .bucket(bucket_name)
+            .server_side_encryption_configuration(
+                ServerSideEncryptionConfiguration::builder()
+                    .rules(rule)
+                    .build(),
+            )
             .send()
             .await
-            .expect("Error While Retrieving keys in a Bucket\n");
-
-        if let Some(keys) = outputs.contents {
-            keys.into_iter().for_each(|object| {
-                if let Some(key) = object.key {
-                    objects_in_bucket.push(key);
-                }
-            })
+            .map_sdk_err(Service::S3, operation)?;
+        report!(
+            self.sink,
+            "The default encryption of the bucket '{}' is now {}\n",
+            bucket_name.green().bold(),
+            describe_encryption(encryption).green().bold()
+        );
+        Ok(())
+    }
+    /// The bucket's default encryption, or `None` when it has none configured
+    pub async fn get_bucket_default_encryption(
+        &self,
+        bucket_name: &str,
+    ) -> Result<Option<ServerSideEncryption>, Error> {
+        let output = match self
+            .client
+            .get_bucket_encryption()
+            .bucket(bucket_name)
+            .send()
+            .await
+            .map_sdk_err(Service::S3, "get_bucket_encryption")
+        {
+            Ok(output) => output,
+            Err(err) if err.code() == Some("ServerSideEncryptionConfigurationNotFoundError") => {
+                return Ok(None)
+            }
+            Err(err) => return Err(err),
+        };
+        let rule = output
+            .server_side_encryption_configuration()
+            .and_then(|configuration| configuration.rules())
+            .and_then(|rules| rules.first());
+        let Some(rule) = rule else {
+            return Ok(None);
+        };
+        let by_default = rule.apply_server_side_encryption_by_default();
+        let encryption = match by_default.and_then(|by_default| by_default.sse_algorithm()) {
+            Some(SseAlgorithm::Aes256) => ServerSideEncryption::S3,
+            //DSSE-KMS is reported as KMS, the closest this type has
+            Some(_) => ServerSideEncryption::Kms {
+                key_id: by_default
+                    .and_then(|by_default| by_default.kms_master_key_id())
+                    .map(|key_id| key_id.to_string()),
+                context: BTreeMap::new(),
+                bucket_key: Some(rule.bucket_key_enabled()),
+            },
+            None => return Ok(None),
+        };
+        report!(
+            self.sink,
+            "The default encryption of the bucket '{}' is {}",
+            bucket_name.green().bold(),
+            describe_encryption(&encryption).green().bold()
+        );
+        Ok(Some(encryption))
+    }
+    /// Enables or suspends versioning. A bucket that never had versioning enabled can't go
+    /// back to that state
+    pub async fn put_bucket_versioning(
+        &self,
+        bucket_name: &str,
+        versioning: Versioning,
+    ) -> Result<(), Error> {
+        self.client
+            .put_bucket_versioning()
+            .bucket(bucket_name)
+            .versioning_configuration(
+                VersioningConfiguration::builder()
+                    .status(versioning.to_sdk())
+                    .build(),
+            )
+            .send()
+            .await
+            .map_sdk_err(Service::S3, "put_bucket_versioning")?;
+        report!(
+            self.sink,
+            "Versioning of the bucket '{}' is now {}\n",
+            bucket_name.green().bold(),
+            versioning.as_str().green().bold()
+        );
+        Ok(())
+    }
+    /// The bucket's versioning status, or `None` if versioning was never enabled
+    pub async fn get_bucket_versioning(
+        &self,
+        bucket_name: &str,
+    ) -> Result<Option<Versioning>, Error> {
+        let output = self
+            .client
+            .get_bucket_versioning()
+            .bucket(bucket_name)
+            .send()
+            .await
+            .map_sdk_err(Service::S3, "get_bucket_versioning")?;
+        Ok(output.status().and_then(Versioning::from_sdk))
+    }
+    /// Replaces the bucket's lifecycle rules. An empty list removes them, as
+    /// [`delete_bucket_lifecycle`](Self::delete_bucket_lifecycle) does
+    pub async fn put_bucket_lifecycle(
+        &self,
+        bucket_name: &str,
+        rules: &[LifecycleRule],
+    ) -> Result<(), Error> {
+        if rules.is_empty() {
+            return self.delete_bucket_lifecycle(bucket_name).await;
+        }
+        self.client
+            .put_bucket_lifecycle_configuration()
+            .bucket(bucket_name)
+            .lifecycle_configuration(
+                BucketLifecycleConfiguration::builder()
+                    .set_rules(Some(rules.iter().map(LifecycleRule::to_sdk).collect()))
+                    .build(),
+            )
+            .send()
+            .await
+            .map_sdk_err(Service::S3, "put_bucket_lifecycle_configuration")?;
+        report!(
+            self.sink,
+            "The bucket '{}' now has {} lifecycle rules\n",
+            bucket_name.green().bold(),
+            rules.len().to_string().green().bold()
+        );
+        Ok(())
+    }
+    /// The bucket's lifecycle rules, which are empty when it has none
+    pub async fn get_bucket_lifecycle(
+        &self,
+        bucket_name: &str,
+    ) -> Result<Vec<LifecycleRule>, Error> {
+        match self
+            .client
+            .get_bucket_lifecycle_configuration()
+            .bucket(bucket_name)
+            .send()
+            .await
+            .map_sdk_err(Service::S3, "get_bucket_lifecycle_configuration")
+        {
+            Ok(output) => Ok(output
+                .rules()
+                .unwrap_or_default()
+                .iter()
+                .map(LifecycleRule::from_sdk)
+                .collect()),
+            Err(err) if err.code() == Some("NoSuchLifecycleConfiguration") => Ok(Vec::new()),
+            Err(err) => Err(err),
+        }
+    }
+    pub async fn delete_bucket_lifecycle(&self, bucket_name: &str) -> Result<(), Error> {
+        self.client
+            .delete_bucket_lifecycle()
+            .bucket(bucket_name)
+            .send()
+            .await
+            .map_sdk_err(Service::S3, "delete_bucket_lifecycle")?;
+        report!(
+            self.sink,
+            "The lifecycle rules of the bucket '{}' were removed\n",
+            bucket_name.green().bold()
+        );
+        Ok(())
+    }
+    /// Replaces the bucket's CORS rules. An empty list removes them, as
+    /// [`delete_bucket_cors`](Self::delete_bucket_cors) does
+    pub async fn put_bucket_cors(
+        &self,
+        bucket_name: &str,
+        rules: &[CorsRule],
+    ) -> Result<(), Error> {
+        if rules.is_empty() {
+            return self.delete_bucket_cors(bucket_name).await;
+        }
+        self.client
+            .put_bucket_cors()
+            .bucket(bucket_name)
+            .cors_configuration(
+                CorsConfiguration::builder()
+                    .set_cors_rules(Some(rules.iter().map(CorsRule::to_sdk).collect()))
+                    .build(),
+            )
+            .send()
+            .await
+            .map_sdk_err(Service::S3, "put_bucket_cors")?;
+        report!(
+            self.sink,
+            "The bucket '{}' now has {} CORS rules\n",
+            bucket_name.green().bold(),
+            rules.len().to_string().green().bold()
+        );
+        Ok(())
+    }
+    /// The bucket's CORS rules, which are empty when it has none
+    pub async fn get_bucket_cors(&self, bucket_name: &str) -> Result<Vec<CorsRule>, Error> {
+        match self
+            .client
+            .get_bucket_cors()
+            .bucket(bucket_name)
+            .send()
+            .await
+            .map_sdk_err(Service::S3, "get_bucket_cors")
+        {
+            Ok(output) => Ok(output
+                .cors_rules()
+                .unwrap_or_default()
+                .iter()
+                .map(CorsRule::from_sdk)
+                .collect()),
+            Err(err) if err.code() == Some("NoSuchCORSConfiguration") => Ok(Vec::new()),
+            Err(err) => Err(err),
+        }
+    }
+    pub async fn delete_bucket_cors(&self, bucket_name: &str) -> Result<(), Error> {
+        self.client
+            .delete_bucket_cors()
+            .bucket(bucket_name)
+            .send()
+            .await
+            .map_sdk_err(Service::S3, "delete_bucket_cors")?;
+        report!(
+            self.sink,
+            "The CORS rules of the bucket '{}' were removed\n",
+            bucket_name.green().bold()
+        );
+        Ok(())
+    }
+    /// Replaces the bucket policy with a JSON policy document, which is checked to be valid
+    /// JSON before it's sent. S3 rejects policies that grant public access while the bucket's
+    /// public access block has `block_public_policy` on
+    pub async fn put_bucket_policy(&self, bucket_name: &str, policy: &str) -> Result<(), Error> {
+        serde_json::from_str::<serde_json::Value>(policy).map_err(|err| {
+            Error::invalid_input("put_bucket_policy", format!("invalid policy: {err}"))
+        })?;
+        self.client
+            .put_bucket_policy()
+            .bucket(bucket_name)
+            .policy(policy)
+            .send()
+            .await
+            .map_sdk_err(Service::S3, "put_bucket_policy")?;
+        report!(
+            self.sink,
+            "The policy of the bucket '{}' was replaced\n",
+            bucket_name.green().bold()
+        );
+        Ok(())
+    }
+    /// The bucket policy as JSON, or `None` when the bucket has none
+    pub async fn get_bucket_policy(&self, bucket_name: &str) -> Result<Option<String>, Error> {
+        match self
+            .client
+            .get_bucket_policy()
+            .bucket(bucket_name)
+            .send()
+            .await
+            .map_sdk_err(Service::S3, "get_bucket_policy")
+        {
+            Ok(output) => Ok(output.policy().map(|policy| policy.to_string())),
+            Err(err) if err.code() == Some("NoSuchBucketPolicy") => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+    pub async fn delete_bucket_policy(&self, bucket_name: &str) -> Result<(), Error> {
+        self.client
+            .delete_bucket_policy()
+            .bucket(bucket_name)
+            .send()
+            .await
+            .map_sdk_err(Service::S3, "delete_bucket_policy")?;
+        report!(
+            self.sink,
+            "The policy of the bucket '{}' was removed\n",
+            bucket_name.green().bold()
+        );
+        Ok(())
+    }
+    pub async fn put_public_access_block(
+        &self,
+        bucket_name: &str,
+        block: PublicAccessBlock,
+    ) -> Result<(), Error> {
+        self.client
+            .put_public_access_block()
+            .bucket(bucket_name)
+            .public_access_block_configuration(block.to_sdk())
+            .send()
+            .await
+            .map_sdk_err(Service::S3, "put_public_access_block")?;
+        report!(
+            self.sink,
+            "The public access block of the bucket '{}' was updated\n",
+            bucket_name.green().bold()
+        );
+        Ok(())
+    }
+    /// The bucket's public access block, or `None` when it has none and only the account's
+    /// settings apply
+    pub async fn get_public_access_block(
+        &self,
+        bucket_name: &str,
+    ) -> Result<Option<PublicAccessBlock>, Error> {
+        match self
+            .client
+            .get_public_access_block()
+            .bucket(bucket_name)
+            .send()
+            .await
+            .map_sdk_err(Service::S3, "get_public_access_block")
+        {
+            Ok(output) => Ok(output
+                .public_access_block_configuration()
+                .map(PublicAccessBlock::from_sdk)),
+            Err(err) if err.code() == Some("NoSuchPublicAccessBlockConfiguration") => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+    pub async fn delete_public_access_block(&self, bucket_name: &str) -> Result<(), Error> {
+        self.client
+            .delete_public_access_block()
+            .bucket(bucket_name)
+            .send()
+            .await
+            .map_sdk_err(Service::S3, "delete_public_access_block")?;
+        report!(
+            self.sink,
+            "The public access block of the bucket '{}' was removed\n",
+            bucket_name.green().bold()
+        );
+        Ok(())
+    }
+    /// Applies every section the configuration has, which is typically loaded with
+    /// [`BucketConfig::from_file`]. The public access block goes first, so a policy it
+    /// allows isn't rejected by the block that was there before. Sections are applied one
+    /// at a time; when one fails, the sections before it stay applied
+    pub async fn apply_bucket_config(
+        &self,
+        bucket_name: &str,
+        config: &BucketConfig,
+    ) -> Result<(), Error> {
+        let policy = config.policy_document()?;
+        if let Some(block) = config.public_access_block {
+            self.put_public_access_block(bucket_name, block).await?;
+        }
+        if let Some(policy) = policy {
+            self.put_bucket_policy(bucket_name, &policy).await?;
+        }
+        if let Some(versioning) = config.versioning {
+            self.put_bucket_versioning(bucket_name, versioning).await?;
+        }
+        if let Some(rules) = &config.lifecycle {
+            self.put_bucket_lifecycle(bucket_name, rules).await?;
+        }
+        if let Some(rules) = &config.cors {
+            self.put_bucket_cors(bucket_name, rules).await?;
+        }
+        Ok(())
+    }
+    /// Reads every section [`BucketConfig`] covers, leaving out those the bucket doesn't
+    /// have, so the result can be saved with [`BucketConfig::to_file`] and applied elsewhere
+    pub async fn get_bucket_config(&self, bucket_name: &str) -> Result<BucketConfig, Error> {
+        let policy = match self.get_bucket_policy(bucket_name).await? {
+            Some(policy) => Some(serde_json::from_str(&policy).map_err(|err| {
+                Error::other(
+                    "get_bucket_policy",
+                    format!("the policy isn't valid JSON: {err}"),
+                )
+            })?),
+            None => None,
+        };
+        let lifecycle = self.get_bucket_lifecycle(bucket_name).await?;
+        let cors = self.get_bucket_cors(bucket_name).await?;
+        Ok(BucketConfig {
+            versioning: self.get_bucket_versioning(bucket_name).await?,
+            lifecycle: (!lifecycle.is_empty()).then_some(lifecycle),
+            cors: (!cors.is_empty()).then_some(cors),
+            policy,
+            public_access_block: self.get_public_access_block(bucket_name).await?,
+        })
+    }
+    /// Sets the retention new object versions get by default, turning Object Lock on if the
+    /// bucket doesn't have it yet, which S3 only allows for buckets with versioning enabled
+    pub async fn put_default_retention(
+        &self,
+        bucket_name: &str,
+        retention: DefaultRetention,
+    ) -> Result<(), Error> {
+        self.client
+            .put_object_lock_configuration()
+            .bucket(bucket_name)
+            .object_lock_configuration(retention.to_sdk())
+            .send()
+            .await
+            .map_sdk_err(Service::S3, "put_object_lock_configuration")?;
+        let period = match retention.period() {
+            RetentionPeriod::Days(days) => format!("{days} days"),
+            RetentionPeriod::Years(years) => format!("{years} years"),
+        };
+        report!(
+            self.sink,
+            "New objects in the bucket '{}' are retained in {} mode for {}\n",
+            bucket_name.green().bold(),
+            retention.mode().as_str(),
+            period.green().bold()
+        );
+        Ok(())
+    }
+    /// The default retention of a bucket, or `None` when it has none or no Object Lock
+    pub async fn get_default_retention(
+        &self,
+        bucket_name: &str,
+    ) -> Result<Option<DefaultRetention>, Error> {
+        match self
+            .client
+            .get_object_lock_configuration()
+            .bucket(bucket_name)
+            .send()
+            .await
+            .map_sdk_err(Service::S3, "get_object_lock_configuration")
+        {
+            Ok(output) => Ok(output
+                .object_lock_configuration()
+                .and_then(|config| config.rule())
+                .and_then(|rule| rule.default_retention())
+                .and_then(DefaultRetention::from_sdk)),
+            Err(err) if err.code() == Some("ObjectLockConfigurationNotFoundError") => Ok(None),
+            Err(err) => Err(err),
         }
-        objects_in_bucket
     }
-    /// The first path is the prefix itself, so it's removed and returns everything after the period. This way, you don't need to use skip(1) when iterating
+
+    ///These methods work on Ubuntu but not on Windows due to differences in stack size. In Ubuntu, the stack size is larger than in Windows, which is why it causes a stack overflow in Windows. As a result, I tested these methods on Ubuntu successfully but encountered a stack overflow issue on Windows.
+    ///I attempted to use these methods in a different thread with a stack size of (32*1024*1024), but it still resulted in a stack overflow
+    /// Ensure successful execution in release mode by using cargo build --release.
+    /// If you have any solutions or suggestions to address this issue in build time, please let
+    /// me know by leaving a comment [`here`]()
+
+    /// Retrieve the objects/keys from a specified bucket.
+    pub async fn retrieve_keys_in_a_bucket(&self, bucket_name: &str) -> Result<Vec<String>, Error> {
+        let objects = collect_items(self.list_objects_stream(bucket_name, None)).await?;
+        Ok(objects
+            .into_iter()
+            .filter_map(|object| object.key)
+            .collect())
+    }
+    /// The keys under the prefix, leaving out the object named after the prefix itself (the "folder" the console creates) if there is one. This way, you don't need to use skip(1) when iterating
     pub async fn list_objects_given_prefix(
         &self,
         bucket_name: &str,
         path_prefix: &str,
-    ) -> Vec<String> {
-        let client = S3Client::new(self.config);
-        let mut keys_in_the_prefix = Vec::new();
-        let outputs = client
+    ) -> Result<Vec<String>, Error> {
+        let objects =
+            collect_items(self.list_objects_stream(bucket_name, Some(path_prefix))).await?;
+        let keys_in_the_prefix = objects
+            .into_iter()
+            .filter_map(|object| object.key)
+            .filter(|key| key != path_prefix)
+            .collect();
+        Ok(keys_in_the_prefix)
+    }
+    /// Lists every object in the bucket, optionally limited to a prefix, fetching the next
+    /// page of up to 1000 keys only when the stream reaches it
+    pub fn list_objects_stream(
+        &self,
+        bucket_name: &str,
+        path_prefix: Option<&str>,
+    ) -> ItemStream<Object> {
+        let pages = self
+            .client
             .list_objects_v2()
-            .prefix(path_prefix)
             .bucket(bucket_name)
+            .set_prefix(path_prefix.map(|prefix| prefix.to_string()))
+            .into_paginator()
             .send()
-            .await
-            .expect("Error while listing objects give path prefix\n");
-        if let Some(keys) = outputs.contents {
-            keys.into_iter().for_each(|object_key| {
-                let key = object_key.key;
-                if let Some(key_) = key {
-                    keys_in_the_prefix.push(key_);
+            .map(|page| page.map_sdk_err(Service::S3, "list_objects_v2"));
+        items_of_pages(pages, |page| page.contents.unwrap_or_default())
+    }
+    /// Pages through the bucket, or the part of it under [`UsageOptions::prefix`], and adds up
+    /// where its storage goes: totals per prefix and storage class, the largest objects, how
+    /// old the data is and the multipart uploads that were never finished. The objects are
+    /// counted as they're listed, so buckets of any size can be analysed. Write the report out
+    /// with [`create_bucket_usage_pdf`](crate::create_bucket_usage_pdf),
+    /// [`BucketUsageReport::to_json`] or [`BucketUsageReport::to_csv`]
+    pub async fn bucket_usage(
+        &self,
+        bucket_name: &str,
+        options: &UsageOptions,
+    ) -> Result<BucketUsageReport, Error> {
+        let scope = options.scope();
+        let mut usage = UsageAggregator::new(options, SystemTime::now());
+        let mut objects =
+            self.list_objects_stream(bucket_name, (!scope.is_empty()).then_some(scope));
+        while let Some(object) = objects.next().await {
+            let object = object?;
+            usage.add_object(
+                object.key().unwrap_or_default(),
+                object.size().max(0) as u64,
+                object
+                    .storage_class()
+                    .map_or("STANDARD", |class| class.as_str()),
+                object
+                    .last_modified()
+                    .and_then(|time| SystemTime::try_from(*time).ok()),
+            );
+        }
+
+        if options.includes_incomplete_uploads() {
+            let (mut key_marker, mut upload_id_marker) = (None, None);
+            loop {
+                let uploads = self
+                    .client
+                    .list_multipart_uploads()
+                    .bucket(bucket_name)
+                    .set_prefix((!scope.is_empty()).then(|| scope.to_string()))
+                    .set_key_marker(key_marker)
+                    .set_upload_id_marker(upload_id_marker)
+                    .send()
+                    .await
+                    .map_sdk_err(Service::S3, "list_multipart_uploads")?;
+                for upload in uploads.uploads().unwrap_or_default() {
+                    let key = upload.key().unwrap_or_default();
+                    let upload_id = upload.upload_id().unwrap_or_default();
+                    let pages = self
+                        .client
+                        .list_parts()
+                        .bucket(bucket_name)
+                        .key(key)
+                        .upload_id(upload_id)
+                        .into_paginator()
+                        .send()
+                        .map(|page| page.map_sdk_err(Service::S3, "list_parts"));
+                    let parts = collect_items(items_of_pages(pages, |page: ListPartsOutput| {
+                        page.parts.unwrap_or_default()
+                    }))
+                    .await?;
+                    usage.add_incomplete_upload(IncompleteUpload::new(
+                        key,
+                        upload_id,
+                        upload
+                            .initiated()
+                            .and_then(|time| SystemTime::try_from(*time).ok()),
+                        parts.len() as u64,
+                        parts.iter().map(|part| part.size().max(0) as u64).sum(),
+                    ));
                 }
-            });
+                if !uploads.is_truncated() {
+                    break;
+                }
+                key_marker = uploads.next_key_marker().map(|marker| marker.to_string());
+                upload_id_marker = uploads
+                    .next_upload_id_marker()
+                    .map(|marker| marker.to_string());
+            }
+        }
+
+        let usage_report = usage.finish(bucket_name);
+        report!(
+            self.sink,
+            "usage: s3://{bucket_name}/{scope} holds {} objects in {}",
+            usage_report.totals().objects().to_string().green().bold(),
+            format_bytes(usage_report.totals().bytes() as f64)
+                .green()
+                .bold()
+        );
+        if !usage_report.incomplete_uploads().is_empty() {
+            report!(
+                self.sink,
+                "usage: {} incomplete multipart uploads hold another {}",
+                usage_report
+                    .incomplete_uploads()
+                    .len()
+                    .to_string()
+                    .red()
+                    .bold(),
+                format_bytes(usage_report.incomplete_upload_bytes() as f64)
+                    .red()
+                    .bold()
+            );
         }
-        keys_in_the_prefix.remove(0);
-        keys_in_the_prefix
+        Ok(usage_report)
     }
 
     /// Store the content in the S3
     ///  storage with the specified bucket name (which should already exist),
-    /// key name (to retrieve data later), and path to the data.
+    /// key name (to retrieve data later), and path to the data. Files at or above the
+    /// [`MultipartConfig::threshold`] are sent with [`mulitpart_upload`](Self::mulitpart_upload)
     pub async fn upload_content_to_a_bucket(
         &self,
         bucket_name: &str,
         data_path: &str,
         name_of_object: &str,
-    ) {
-        let client = S3Client::new(self.config);
-
+    ) -> Result<(), Error> {
+        self.upload_with_options(
+            bucket_name,
+            data_path,
+            name_of_object,
+            &UploadOptions::default(),
+        )
+        .await
+        .map(|_| ())
+    }
+    /// [`upload_content_to_a_bucket`](Self::upload_content_to_a_bucket) with the encryption
+    /// and other settings of the options applied to the object, multipart uploads included.
+    /// With a [`checksum`](UploadOptions::checksum) the result holds the one computed here
+    /// and the one S3 stored, and a mismatch between them is an [`Error::Integrity`]
+    pub async fn upload_with_options(
+        &self,
+        bucket_name: &str,
+        data_path: &str,
+        name_of_object: &str,
+        options: &UploadOptions,
+    ) -> Result<TransferResult, Error> {
+        let file_size = file_size(data_path, "upload_content_to_a_bucket")?;
+        if file_size >= self.multipart.threshold {
+            return self
+                .start_multipart_upload(bucket_name, name_of_object, data_path, options)
+                .await;
+        }
+        let checksum = options
+            .checksum
+            .map(|checksum| {
+                checksum_of_file(Path::new(data_path), checksum, None)
+                    .map(|value| (checksum, value))
+                    .map_err(|err| Error::io("upload_content_to_a_bucket", data_path, err))
+            })
+            .transpose()?;
         let build_body_data = ByteStream::read_from()
             .path(data_path)
             .build()
             .await
-            .unwrap();
-        /*
-                use filesize::PathExt;
-        use std::path::Path;let path = Path::new(&data_path);
-        let file_size = match path.symlink_metadata() {
-            Ok(metadata) => match path.size_on_disk_fast(&metadata) {
-                Ok(realsize) => Some(realsize),
-                Err(_) => None,
-            },
-            Err(_) => None,
-        };
-        match file_size {
-            Some(size) => {
-                let msg = format!(
-                    "Uploading the file '{}' and its size is: {} Mb\n",
-                    data_path.green().bold(),
-                    (size / (1024 * 1024)).to_string().green().bold()
-                );
-                println!("{msg}");
-                let size_in_mb = size / (1024 * 1024);
-                if size_in_mb < 50 {
-                    println!(
-                        "{}\n",
-                        "It will take less than a minute to upload the content"
-                            .yellow()
-                            .bold()
-                    );
-                } else if size_in_mb*1024 < 1048576 {
-                    let guessed_minutes = size_in_mb / (50 * 50);
-                    println!("It will take approximately '{}' minutes to upload content, so please be patient\n",guessed_minutes.to_string().yellow().bold());
-                } else if size_in_mb*1024 > 1048576 {
-                    let guessed_hours = size_in_mb / (50 * 50);
-                    println!("It will take approximately '{}' hours to upload content, so you can accomplish other tasks while it's uploading\n",guessed_hours.to_string().yellow().bold());
-                }
-            }
-            None => {
-                let msg = format!("Uploading the file'{}'\n", data_path.green().bold());
-                println!("{msg}");
-                println!("{}\n","No file size information is available; you can either wait or engage in other tasks while the uploading process is in progress".yellow().bold());
-            }
-        };
-        let start_time = SystemTime::now();*/
-        client
+            .map_err(|err| Error::io("upload_content_to_a_bucket", data_path, err.into()))?;
+        let request = self
+            .client
             .put_object()
             .bucket(bucket_name)
             .key(name_of_object)
-            .body(build_body_data)
-            .send()
-            .await
-            .map(|_| {
-                println!(
-                    "The provided object {} has been successfully updated in the bucket {}\n",
-                    data_path.green().bold(),
-                    bucket_name.green().bold()
-                );
-                /*let end_time = start_time
-                    .elapsed()
-                    .expect("Error while converting to duration from system time\n");
-                if end_time.as_secs() < 60 {
-                    println!(
-                        "It took '{}' seconds to update the file",
-                        end_time.as_secs().to_string().yellow().bold()
-                    );
-                } else if end_time.as_secs() < 36000 {
-                    println!(
-                        "Uploading the provided content required '{}' minutes",
-                        (end_time.as_secs() / 60).to_string().yellow().bold()
-                    );
-                } else {
-                    println!(
-                        "Uploading the provided content required '{}' hours",
-                        (end_time.as_secs() / (60 * 60)).to_string().yellow().bold()
-                    );
-                }; */
-            })
-            .expect("Error while uploading content to a bucket\n");
+            .body(build_body_data);
+        let request = with_upload_options!(
+            request,
+            options,
+            options.content_type_for(data_path),
+            options.user_metadata()
+        );
+        let progress = self.track(
+            TransferDirection::Upload,
+            bucket_name,
+            name_of_object,
+            file_size,
+            0,
+        );
+        let output = progress.finish(
+            with_checksum!(request, checksum.clone())
+                .send()
+                .await
+                .map_sdk_err(Service::S3, "put_object"),
+        )?;
+        progress.advance(file_size);
+        report!(
+            self.sink,
+            "The provided object {} has been successfully updated in the bucket {}\n",
+            data_path.green().bold(),
+            bucket_name.green().bold()
+        );
         /*
         let current_objects = self.retrieve_keys_in_a_bucket(bucket_name).await;
-        println!("Currently available keys/objects in your {bucket_name} bucket\n");
+        report!(self.sink, "Currently available keys/objects in your {bucket_name} bucket\n");
         current_objects.into_iter().for_each(|key| {
             let key = key.green().bold();
-            println!("{key}\n");
+            report!(self.sink, "{key}\n");
         }); */
+        let stored = checksum
+            .as_ref()
+            .and_then(|(algorithm, _)| stored_checksum!(output, algorithm));
+        Ok(TransferResult {
+            bucket: bucket_name.to_string(),
+            key: name_of_object.to_string(),
+            size: file_size,
+            e_tag: output.e_tag().map(|e_tag| e_tag.to_string()),
+            checksum: self.verify_upload_checksum(data_path, checksum, stored)?,
+        })
     }
-    pub async fn put_object_acl(
+    /// Encrypts the file on this machine before it's uploaded, so S3 only ever sees
+    /// ciphertext. Each object gets its own random AES-256 data key, which is wrapped by the
+    /// `wrapper` and stored in the object's metadata together with the IV. The file is sealed
+    /// with AES-256-GCM in 64 KiB chunks as it's read, so large files are sent as a multipart
+    /// upload without being held in memory; a failed one is always aborted.
+    ///
+    /// The options apply as in [`upload_with_options`](Self::upload_with_options), so the
+    /// ciphertext can be encrypted at rest by S3 as well, and a checksum covers the
+    /// ciphertext. Read the object back with [`download_decrypted`](Self::download_decrypted).
+    pub async fn upload_encrypted(
         &self,
         bucket_name: &str,
+        data_path: &str,
         name_of_object: &str,
-        acl_permission: &str,
-    ) {
-        let client = S3Client::new(self.config);
-        let acl_permission_build = ObjectCannedAcl::from(acl_permission);
-        let acl_permission_str = acl_permission_build.as_str().to_owned();
-        client
-            .put_object_acl()
-            .key(name_of_object)
+        wrapper: &dyn KeyWrapper,
+        options: &UploadOptions,
+    ) -> Result<TransferResult, Error> {
+        let operation = "upload_encrypted";
+        let file_size = file_size(data_path, operation)?;
+        let mut data_key = Zeroizing::new([0; 32]);
+        let mut iv = [0; 12];
+        random_bytes(data_key.as_mut_slice())?;
+        random_bytes(&mut iv)?;
+        let wrapped_key = wrapper.wrap_key(data_key.as_slice()).await?;
+        let cipher = ChunkCipher::for_plaintext(data_key.as_slice(), iv, file_size)?;
+        let mut metadata = options.metadata.clone();
+        metadata.extend(envelope_metadata(wrapper.algorithm(), &wrapped_key, &iv));
+        //A detected type would describe the plaintext, which the object isn't
+        let content_type = options.content_type.clone();
+
+        if file_size < self.multipart.threshold {
+            let plaintext = Zeroizing::new(read_part(data_path, 0, file_size)?);
+            let body = cipher.seal(0, &plaintext)?;
+            let checksum = options
+                .checksum
+                .map(|checksum| (checksum, checksum_of(checksum, &body)));
+            let request = self
+                .client
+                .put_object()
+                .bucket(bucket_name)
+                .key(name_of_object)
+                .content_length(body.len() as i64)
+                .body(ByteStream::from(body));
+            let request = with_upload_options!(request, options, content_type, Some(metadata));
+            let progress = self.track(
+                TransferDirection::Upload,
+                bucket_name,
+                name_of_object,
+                file_size,
+                0,
+            );
+            let output = progress.finish(
+                with_checksum!(request, checksum.clone())
+                    .send()
+                    .await
+                    .map_sdk_err(Service::S3, "put_object"),
+            )?;
+            progress.advance(file_size);
+            report!(
+                self.sink,
+                "The provided object {} has been encrypted and uploaded to the bucket {}\n",
+                data_path.green().bold(),
+                bucket_name.green().bold()
+            );
+            let stored = checksum
+                .as_ref()
+                .and_then(|(algorithm, _)| stored_checksum!(output, algorithm));
+            return Ok(TransferResult {
+                bucket: bucket_name.to_string(),
+                key: name_of_object.to_string(),
+                size: file_size,
+                e_tag: output.e_tag().map(|e_tag| e_tag.to_string()),
+                checksum: self.verify_upload_checksum(data_path, checksum, stored)?,
+            });
+        }
+        let request = self
+            .client
+            .create_multipart_upload()
             .bucket(bucket_name)
-            .acl(acl_permission_build)
+            .key(name_of_object);
+        let output = with_upload_options!(request, options, content_type, Some(metadata))
+            .set_checksum_algorithm(options.checksum.map(|checksum| checksum.algorithm()))
             .send()
             .await
-            .expect("Error while putting ACL on bucket\n");
-        println!(
-            "The ACL permission '{}' has been successfully applied to the object key '{}' within the '{}' bucket\n",
-            acl_permission_str.green().bold(),
-            name_of_object.green().bold(),
-            bucket_name.green().bold()
+            .map_sdk_err(Service::S3, "create_multipart_upload")?;
+        let upload_id = output.upload_id().ok_or(Error::missing(
+            Service::S3,
+            "create_multipart_upload",
+            "upload id",
+        ))?;
+        //Parts have to start on a chunk boundary to be sealed on their own
+        let part_size = self.multipart.part_size_for(file_size);
+        let part_size = (part_size + CHUNK_SIZE - 1) / CHUNK_SIZE * CHUNK_SIZE;
+        report!(
+            self.sink,
+            "Encrypting and uploading '{}' to the bucket '{}' as the multipart upload '{}'",
+            data_path.green().bold(),
+            bucket_name.green().bold(),
+            upload_id.yellow().bold()
         );
-        match acl_permission {
-            "public-read" => {
-                let msg =format!("The permission is set to '{}' making the object accessible to anyone who has the object's URL",acl_permission_str);
-                println!("{}\n", msg.green().bold());
+        self.upload_parts(
+            bucket_name,
+            name_of_object,
+            data_path,
+            upload_id,
+            file_size,
+            part_size,
+            Vec::new(),
+            PartOptions {
+                customer_key: options.customer_key(),
+                cipher: Some(&cipher),
+                checksum: options.checksum,
+            },
+        )
+        .await
+    }
+    /// Downloads an object uploaded with [`upload_encrypted`](Self::upload_encrypted) and
+    /// decrypts it into `path` as it arrives. The data key is unwrapped with `wrapper`, which
+    /// has to use the same scheme the object was uploaded with.
+    ///
+    /// Every chunk is authenticated before it's written. If any chunk fails its tag check,
+    /// because the object was altered, truncated or sealed under another key, the partial
+    /// file is deleted and an [`Error::Integrity`] is returned; nothing unverified is left
+    /// at `path`.
+    pub async fn download_decrypted(
+        &self,
+        bucket_name: &str,
+        object_name: &str,
+        path: impl AsRef<Path>,
+        wrapper: &dyn KeyWrapper,
+    ) -> Result<(), Error> {
+        let operation = "download_decrypted";
+        let path = path.as_ref();
+        let request = self
+            .client
+            .get_object()
+            .bucket(bucket_name)
+            .key(object_name);
+        let mut output = with_customer_key!(request, self.customer_key.as_ref())
+            .send()
+            .await
+            .map_sdk_err(Service::S3, "get_object")?;
+        let (wrapped_key, iv) =
+            read_envelope_metadata(operation, output.metadata(), wrapper.algorithm())?;
+        let data_key = wrapper.unwrap_key(&wrapped_key).await?;
+        let size = output.content_length().max(0) as u64;
+        let cipher = ChunkCipher::for_ciphertext(&data_key, iv, size)?;
+        let progress = self.track(
+            TransferDirection::Download,
+            bucket_name,
+            object_name,
+            size,
+            0,
+        );
+
+        let partial = with_suffix(path, ".part");
+        let display = path.display().to_string();
+        let io_err = |path: &Path, err| Error::io(operation, path.display().to_string(), err);
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent).map_err(|err| io_err(parent, err))?;
+        }
+        let mut file = File::create(&partial).map_err(|err| io_err(&partial, err))?;
+        let decrypt = async {
+            let mut buffer = Vec::new();
+            let mut index = 0;
+            let open_chunk = |index: u64, chunk: &mut [u8], file: &mut File| {
+                let length = cipher.open(index, chunk).ok_or_else(|| {
+                    Error::integrity(
+                        operation,
+                        &display,
+                        format!("chunk {index} to pass its GCM tag check"),
+                        "a chunk that was altered or sealed under another key",
+                    )
+                })?;
+                file.write_all(&chunk[..length])
+                    .map_err(|err| io_err(&partial, err))
+            };
+            while let Some(bytes) = TryStreamExt::try_next(&mut output.body)
+                .await
+                .map_err(|err| io_err(&partial, err.into()))?
+            {
+                progress.advance(bytes.len() as u64);
+                buffer.extend_from_slice(&bytes);
+                //The last chunk is opened once the body ends, since it may be a full one too
+                while buffer.len() as u64 >= SEALED_CHUNK_SIZE && index + 1 < cipher.chunk_count() {
+                    let mut chunk = buffer
+                        .drain(..SEALED_CHUNK_SIZE as usize)
+                        .collect::<Vec<_>>();
+                    open_chunk(index, &mut chunk, &mut file)?;
+                    index += 1;
+                }
             }
-            "public-read-write" => {
-                let msg = format!("The permission is configured as '{}' granting read and write access to anyone who accesses the object's URL",acl_permission_str);
-                println!("{}\n", msg.green().bold());
+            if index + 1 != cipher.chunk_count() {
+                return Err(Error::integrity(
+                    operation,
+                    &display,
+                    format!("{} chunks", cipher.chunk_count()),
+                    format!("{} chunks", index + 1),
+                ));
             }
-            _ => {}
+            open_chunk(index, &mut buffer, &mut file)?;
+            file.flush().map_err(|err| io_err(&partial, err))
+        };
+        let decrypted = progress.finish(decrypt.await);
+        drop(file);
+        if let Err(err) = decrypted {
+            let _ = fs::remove_file(&partial);
+            if matches!(err, Error::Integrity { .. }) {
+                report!(
+                    self.sink,
+                    "{}: {err}",
+                    format!("'{object_name}' failed authentication and was not saved")
+                        .red()
+                        .bold()
+                );
+            }
+            return Err(err);
+        }
+        fs::rename(&partial, path).map_err(|err| io_err(path, err))?;
+        report!(
+            self.sink,
+            "The object {} has been decrypted and saved to '{}'\n",
+            object_name.green().bold(),
+            display
+        );
+        Ok(())
+    }
+    /// Collects an object's headers and tags, including what
+    /// [`upload_with_options`](Self::upload_with_options) set. Objects encrypted with SSE-C
+    /// need the key from [`with_customer_key`](Self::with_customer_key)
+    pub async fn get_object_info(
+        &self,
+        bucket_name: &str,
+        object_name: &str,
+    ) -> Result<ObjectInfo, Error> {
+        let request = self
+            .client
+            .head_object()
+            .bucket(bucket_name)
+            .key(object_name);
+        let head = with_customer_key!(request, self.customer_key.as_ref())
+            .send()
+            .await
+            .map_sdk_err(Service::S3, "head_object")?;
+        let tags = self.object_tags(bucket_name, object_name).await?;
+        Ok(ObjectInfo {
+            content_type: head
+                .content_type()
+                .map(|content_type| content_type.to_string()),
+            content_length: head.content_length().max(0) as u64,
+            e_tag: head.e_tag().map(|e_tag| e_tag.to_string()),
+            last_modified: head
+                .last_modified()
+                .and_then(|time| SystemTime::try_from(*time).ok()),
+            metadata: head.metadata().cloned().unwrap_or_default(),
+            tags,
+            cache_control: head.cache_control().map(|value| value.to_string()),
+            content_disposition: head.content_disposition().map(|value| value.to_string()),
+            //HeadObject leaves the header out for STANDARD objects
+            storage_class: head
+                .storage_class()
+                .map(|class| class.as_str().to_string())
+                .unwrap_or_else(|| "STANDARD".to_string()),
+            server_side_encryption: head
+                .server_side_encryption()
+                .map(|sse| sse.as_str().to_string()),
+            kms_key_id: head.ssekms_key_id().map(|key_id| key_id.to_string()),
+            version_id: head.version_id().map(|version_id| version_id.to_string()),
+            retention: head
+                .object_lock_mode()
+                .and_then(RetentionMode::from_object_mode)
+                .zip(
+                    head.object_lock_retain_until_date()
+                        .and_then(|time| SystemTime::try_from(*time).ok()),
+                )
+                .map(|(mode, retain_until)| ObjectRetention::new(mode, retain_until)),
+            legal_hold: head.object_lock_legal_hold_status()
+                == Some(&ObjectLockLegalHoldStatus::On),
+        })
+    }
+    /// Sets or extends the Object Lock retention of the current version of an object.
+    /// Shortening a governance-mode retention, or turning it into none, needs
+    /// `bypass_governance` and the `s3:BypassGovernanceRetention` permission; a
+    /// compliance-mode retention can't be shortened at all
+    pub async fn put_object_retention(
+        &self,
+        bucket_name: &str,
+        object_name: &str,
+        retention: ObjectRetention,
+        bypass_governance: bool,
+    ) -> Result<(), Error> {
+        self.client
+            .put_object_retention()
+            .bucket(bucket_name)
+            .key(object_name)
+            .retention(retention.to_sdk())
+            .bypass_governance_retention(bypass_governance)
+            .send()
+            .await
+            .map_sdk_err(Service::S3, "put_object_retention")?;
+        let retain_until = DateTime::<Utc>::from(retention.retain_until());
+        report!(
+            self.sink,
+            "s3://{bucket_name}/{} is retained in {} mode until {}\n",
+            object_name.green().bold(),
+            retention.mode().as_str(),
+            retain_until.to_rfc3339().green().bold()
+        );
+        Ok(())
+    }
+    /// The Object Lock retention of the current version of an object, or `None` when it has
+    /// none
+    pub async fn get_object_retention(
+        &self,
+        bucket_name: &str,
+        object_name: &str,
+    ) -> Result<Option<ObjectRetention>, Error> {
+        match self
+            .client
+            .get_object_retention()
+            .bucket(bucket_name)
+            .key(object_name)
+            .send()
+            .await
+            .map_sdk_err(Service::S3, "get_object_retention")
+        {
+            Ok(output) => Ok(output.retention().and_then(ObjectRetention::from_sdk)),
+            Err(err) if err.code() == Some("NoSuchObjectLockConfiguration") => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+    /// Places or lifts a legal hold on the current version of an object. A held version
+    /// can't be deleted or overwritten, independently of its retention
+    pub async fn put_object_legal_hold(
+        &self,
+        bucket_name: &str,
+        object_name: &str,
+        on: bool,
+    ) -> Result<(), Error> {
+        self.client
+            .put_object_legal_hold()
+            .bucket(bucket_name)
+            .key(object_name)
+            .legal_hold(
+                ObjectLockLegalHold::builder()
+                    .status(legal_hold_status(on))
+                    .build(),
+            )
+            .send()
+            .await
+            .map_sdk_err(Service::S3, "put_object_legal_hold")?;
+        report!(
+            self.sink,
+            "The legal hold on s3://{bucket_name}/{} was {}\n",
+            object_name.green().bold(),
+            if on { "placed" } else { "lifted" }
+        );
+        Ok(())
+    }
+    /// Whether the current version of an object is under a legal hold
+    pub async fn get_object_legal_hold(
+        &self,
+        bucket_name: &str,
+        object_name: &str,
+    ) -> Result<bool, Error> {
+        match self
+            .client
+            .get_object_legal_hold()
+            .bucket(bucket_name)
+            .key(object_name)
+            .send()
+            .await
+            .map_sdk_err(Service::S3, "get_object_legal_hold")
+        {
+            Ok(output) => Ok(output.legal_hold().and_then(|hold| hold.status())
+                == Some(&ObjectLockLegalHoldStatus::On)),
+            Err(err) if err.code() == Some("NoSuchObjectLockConfiguration") => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+    /// Runs an S3 Select query, such as `SELECT s.id FROM S3Object s WHERE s.status = 'failed'`,
+    /// over a CSV, JSON or Parquet object, so only the matching rows are downloaded instead of
+    /// the whole object. The results are read from the returned stream as S3 sends them,
+    /// which also reports the bytes S3 scanned, processed and returned once it's done.
+    /// Objects encrypted with SSE-C need the key from [`with_customer_key`](Self::with_customer_key)
+    pub async fn select_object_content(
+        &self,
+        bucket_name: &str,
+        object_name: &str,
+        sql: &str,
+        input_format: &SelectInput,
+        output_format: &SelectOutput,
+    ) -> Result<SelectStream, Error> {
+        if input_format.is_parquet() && input_format.compression_type() != SelectCompression::None {
+            return Err(Error::invalid_input(
+                "select_object_content",
+                "Parquet objects can't be compressed as a whole; their columns are compressed within the file",
+            ));
+        }
+        let request = self
+            .client
+            .select_object_content()
+            .bucket(bucket_name)
+            .key(object_name)
+            .expression(sql)
+            .expression_type(ExpressionType::Sql)
+            .input_serialization(input_format.to_sdk())
+            .output_serialization(output_format.to_sdk())
+            .request_progress(RequestProgress::builder().enabled(true).build());
+        let output = with_customer_key!(request, self.customer_key.as_ref())
+            .send()
+            .await
+            .map_sdk_err(Service::S3, "select_object_content")?;
+        Ok(SelectStream::new(
+            output,
+            output_format,
+            format!("s3://{bucket_name}/{object_name}"),
+            self.sink.clone(),
+        ))
+    }
+    async fn object_tags(
+        &self,
+        bucket_name: &str,
+        object_name: &str,
+    ) -> Result<Vec<(String, String)>, Error> {
+        let tagging = self
+            .client
+            .get_object_tagging()
+            .bucket(bucket_name)
+            .key(object_name)
+            .send()
+            .await
+            .map_sdk_err(Service::S3, "get_object_tagging")?;
+        Ok(tagging
+            .tag_set()
+            .unwrap_or_default()
+            .iter()
+            .map(|tag| {
+                (
+                    tag.key().unwrap_or_default().to_string(),
+                    tag.value().unwrap_or_default().to_string(),
+                )
+            })
+            .collect())
+    }
+    pub async fn put_object_acl(
+        &self,
+        bucket_name: &str,
+        name_of_object: &str,
+        acl_permission: &str,
+    ) -> Result<(), Error> {
+        let acl_permission_build = ObjectCannedAcl::from(acl_permission);
+        let acl_permission_str = acl_permission_build.as_str().to_owned();
+        self.client
+            .put_object_acl()
+            .key(name_of_object)
+            .bucket(bucket_name)
+            .acl(acl_permission_build)
+            .send()
+            .await
+            .map_sdk_err(Service::S3, "put_object_acl")?;
+        report!(
+            self.sink,
+            "The ACL permission '{}' has been successfully applied to the object key '{}' within the '{}' bucket\n",
+            acl_permission_str.green().bold(),
+            name_of_object.green().bold(),
+            bucket_name.green().bold()
+        );
+        match acl_permission {
+            "public-read" => {
+                let msg =format!("The permission is set to '{}' making the object accessible to anyone who has the object's URL",acl_permission_str);
+                report!(self.sink, "{}\n", msg.green().bold());
+            }
+            "public-read-write" => {
+                let msg = format!("The permission is configured as '{}' granting read and write access to anyone who accesses the object's URL",acl_permission_str);
+                report!(self.sink, "{}\n", msg.green().bold());
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    ///Upload large files using chunks instead of uploading the entire file, while
+    /// accepting the same parameters as the method above. The file is read and sent
+    /// part by part as configured by [`MultipartConfig`], so only the parts in flight are
+    /// held in memory
+    pub async fn mulitpart_upload(
+        &self,
+        bucket_name: &str,
+        object_name: &str,
+        data_path: &str,
+    ) -> Result<(), Error> {
+        self.start_multipart_upload(
+            bucket_name,
+            object_name,
+            data_path,
+            &UploadOptions::default(),
+        )
+        .await
+        .map(|_| ())
+    }
+    async fn start_multipart_upload(
+        &self,
+        bucket_name: &str,
+        object_name: &str,
+        data_path: &str,
+        options: &UploadOptions,
+    ) -> Result<TransferResult, Error> {
+        let file_size = file_size(data_path, "mulitpart_upload")?;
+        let request = self
+            .client
+            .create_multipart_upload()
+            .bucket(bucket_name)
+            .key(object_name);
+        let mulit_part = with_upload_options!(
+            request,
+            options,
+            options.content_type_for(data_path),
+            options.user_metadata()
+        )
+        .set_checksum_algorithm(options.checksum.map(|checksum| checksum.algorithm()))
+        .send()
+        .await
+        .map_sdk_err(Service::S3, "create_multipart_upload")?;
+
+        let upload_id = mulit_part.upload_id().ok_or(Error::missing(
+            Service::S3,
+            "create_multipart_upload",
+            "upload id",
+        ))?;
+        report!(
+            self.sink,
+            "Uploading '{}' to the bucket '{}' as the multipart upload '{}'",
+            data_path.green().bold(),
+            bucket_name.green().bold(),
+            upload_id.yellow().bold()
+        );
+        self.upload_parts(
+            bucket_name,
+            object_name,
+            data_path,
+            upload_id,
+            file_size,
+            self.multipart.part_size_for(file_size),
+            Vec::new(),
+            PartOptions {
+                customer_key: options.customer_key(),
+                checksum: options.checksum,
+                ..Default::default()
+            },
+        )
+        .await
+    }
+    /// Continues an interrupted [`mulitpart_upload`](Self::mulitpart_upload) of the same
+    /// file under its upload ID. The parts S3 already has are listed and skipped, and their
+    /// size is reused so the remaining parts line up with them. Pass the options the upload
+    /// was started with; the remaining parts need its SSE-C key and checksum, if it had them.
+    /// With a checksum, parts already uploaded are checked against the file and sent again
+    /// when they differ
+    pub async fn resume_multipart_upload(
+        &self,
+        bucket_name: &str,
+        object_name: &str,
+        data_path: &str,
+        upload_id: &str,
+        options: &UploadOptions,
+    ) -> Result<TransferResult, Error> {
+        let file_size = file_size(data_path, "resume_multipart_upload")?;
+        let pages = self
+            .client
+            .list_parts()
+            .bucket(bucket_name)
+            .key(object_name)
+            .upload_id(upload_id)
+            .into_paginator()
+            .send()
+            .map(|page| page.map_sdk_err(Service::S3, "list_parts"));
+        let uploaded = collect_items(items_of_pages(pages, |page: ListPartsOutput| {
+            page.parts.unwrap_or_default()
+        }))
+        .await?;
+        let part_size = uploaded
+            .iter()
+            .find(|part| part.part_number() == 1)
+            .map(|part| part.size() as u64)
+            .unwrap_or_else(|| self.multipart.part_size_for(file_size));
+        //A part whose size or checksum doesn't match the file is sent again
+        let mut completed = Vec::new();
+        for part in &uploaded {
+            let (offset, length) = part_range(part.part_number(), part_size, file_size);
+            if part.size() as u64 != length {
+                continue;
+            }
+            let checksum = match options.checksum {
+                Some(checksum) => {
+                    let local = checksum_of(checksum, &read_part(data_path, offset, length)?);
+                    if stored_checksum!(part, checksum).as_deref() != Some(local.as_str()) {
+                        continue;
+                    }
+                    Some((checksum, local))
+                }
+                None => None,
+            };
+            let completed_part = CompletedPart::builder()
+                .e_tag(part.e_tag().unwrap_or_default())
+                .part_number(part.part_number());
+            completed.push(with_checksum!(completed_part, checksum).build());
+        }
+        report!(
+            self.sink,
+            "Resuming the multipart upload '{}' of '{}'; {} parts are already uploaded",
+            upload_id.yellow().bold(),
+            data_path.green().bold(),
+            completed.len().to_string().green().bold()
+        );
+        self.upload_parts(
+            bucket_name,
+            object_name,
+            data_path,
+            upload_id,
+            file_size,
+            part_size,
+            completed,
+            PartOptions {
+                customer_key: options.customer_key(),
+                checksum: options.checksum,
+                ..Default::default()
+            },
+        )
+        .await
+    }
+    #[allow(clippy::too_many_arguments)]
+    async fn upload_parts(
+        &self,
+        bucket_name: &str,
+        object_name: &str,
+        data_path: &str,
+        upload_id: &str,
+        file_size: u64,
+        part_size: u64,
+        mut completed: Vec<CompletedPart>,
+        part_options: PartOptions<'_>,
+    ) -> Result<TransferResult, Error> {
+        let part_count = ((file_size + part_size - 1) / part_size).max(1) as i32;
+        let done = completed
+            .iter()
+            .map(|part| part.part_number())
+            .collect::<HashSet<_>>();
+        let resumed = done
+            .iter()
+            .map(|part_number| part_range(*part_number, part_size, file_size).1)
+            .sum();
+        let progress = &self.track(
+            TransferDirection::Upload,
+            bucket_name,
+            object_name,
+            file_size,
+            resumed,
+        );
+        let pending = (1..=part_count)
+            .filter(|part_number| !done.contains(part_number))
+            .map(|part_number| async move {
+                let (offset, length) = part_range(part_number, part_size, file_size);
+                let part = self
+                    .upload_part(
+                        bucket_name,
+                        object_name,
+                        data_path,
+                        upload_id,
+                        part_number,
+                        offset,
+                        length,
+                        part_options,
+                    )
+                    .await?;
+                progress.advance(length);
+                Ok(part)
+            });
+        let uploaded = futures_util::StreamExt::buffer_unordered(
+            stream::iter(pending),
+            self.multipart.concurrency,
+        )
+        .try_collect::<Vec<_>>()
+        .await;
+        let mut computed = None;
+        let result = match uploaded {
+            Ok(parts) => {
+                completed.extend(parts);
+                completed.sort_by_key(|part| part.part_number());
+                computed = part_options.checksum.and_then(|checksum| {
+                    let part_checksums = completed
+                        .iter()
+                        .map(|part| stored_checksum!(part, checksum).unwrap_or_default())
+                        .collect::<Vec<_>>();
+                    composite_checksum(checksum, part_checksums.iter().map(|part| part.as_str()))
+                        .map(|value| (checksum, value))
+                });
+                let completed_multipart = CompletedMultipartUpload::builder()
+                    .set_parts(Some(completed))
+                    .build();
+                let request = self
+                    .client
+                    .complete_multipart_upload()
+                    .bucket(bucket_name)
+                    .key(object_name)
+                    .multipart_upload(completed_multipart)
+                    .upload_id(upload_id);
+                with_customer_key!(request, part_options.customer_key)
+                    .send()
+                    .await
+                    .map_sdk_err(Service::S3, "complete_multipart_upload")
+            }
+            Err(err) => Err(err),
+        };
+        match progress.finish(result) {
+            Ok(output) => {
+                report!(
+                    self.sink,
+                    "The provided object {} has been successfully uploaded to the bucket {} in {} parts\n",
+                    data_path.green().bold(),
+                    bucket_name.green().bold(),
+                    part_count.to_string().green().bold()
+                );
+                let stored = computed
+                    .as_ref()
+                    .and_then(|(checksum, _)| stored_checksum!(output, checksum));
+                Ok(TransferResult {
+                    bucket: bucket_name.to_string(),
+                    key: object_name.to_string(),
+                    size: file_size,
+                    e_tag: output.e_tag().map(|e_tag| e_tag.to_string()),
+                    checksum: self.verify_upload_checksum(data_path, computed, stored)?,
+                })
+            }
+            //Encrypted parts can't be resumed, since only this call holds the data key
+            Err(err) if self.multipart.abort_on_failure || part_options.cipher.is_some() => {
+                //The parts of an upload that is neither completed nor aborted are billed
+                //until a lifecycle rule removes them
+                if let Err(abort_err) = self
+                    .client
+                    .abort_multipart_upload()
+                    .bucket(bucket_name)
+                    .key(object_name)
+                    .upload_id(upload_id)
+                    .send()
+                    .await
+                    .map_sdk_err(Service::S3, "abort_multipart_upload")
+                {
+                    report!(
+                        self.sink,
+                        "{}: {abort_err}",
+                        format!("The multipart upload '{upload_id}' could not be aborted")
+                            .red()
+                            .bold()
+                    );
+                }
+                Err(err)
+            }
+            Err(err) => {
+                report!(
+                    self.sink,
+                    "The multipart upload '{}' was kept; pass it to resume_multipart_upload to continue\n",
+                    upload_id.yellow().bold()
+                );
+                Err(err)
+            }
+        }
+    }
+    #[allow(clippy::too_many_arguments)]
+    async fn upload_part(
+        &self,
+        bucket_name: &str,
+        object_name: &str,
+        data_path: &str,
+        upload_id: &str,
+        part_number: i32,
+        offset: u64,
+        length: u64,
+        part_options: PartOptions<'_>,
+    ) -> Result<CompletedPart, Error> {
+        let mut attempt = 1;
+        loop {
+            let mut body = read_part(data_path, offset, length)?;
+            if let Some(cipher) = part_options.cipher {
+                body = cipher.seal(offset / CHUNK_SIZE, &Zeroizing::new(body))?;
+            }
+            let checksum = part_options
+                .checksum
+                .map(|checksum| (checksum, checksum_of(checksum, &body)));
+            let request = self
+                .client
+                .upload_part()
+                .bucket(bucket_name)
+                .key(object_name)
+                .upload_id(upload_id)
+                .part_number(part_number)
+                .content_length(body.len() as i64)
+                .body(ByteStream::from(body));
+            let request = with_checksum!(request, checksum.clone());
+            let result = with_customer_key!(request, part_options.customer_key)
+                .send()
+                .await
+                .map_sdk_err(Service::S3, "upload_part");
+            match result {
+                Ok(output) => {
+                    let completed_part = CompletedPart::builder()
+                        .e_tag(output.e_tag().unwrap_or_default())
+                        .part_number(part_number);
+                    return Ok(with_checksum!(completed_part, checksum).build());
+                }
+                Err(err) if err.is_retryable() && attempt < self.multipart.max_attempts => {
+                    report!(
+                        self.sink,
+                        "Part {} failed on attempt {}, retrying: {err}",
+                        part_number.to_string().yellow().bold(),
+                        attempt
+                    );
+                    self.backoff(attempt).await;
+                    attempt += 1;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+    #[allow(clippy::too_many_arguments)]
+    async fn download_range(
+        &self,
+        bucket_name: &str,
+        object_name: &str,
+        e_tag: &str,
+        partial: &Path,
+        state: &Path,
+        part: u64,
+        range: Option<(u64, u64)>,
+        progress: &ProgressTracker,
+    ) -> Result<(), Error> {
+        let mut attempt = 1;
+        loop {
+            match self
+                .fetch_range(bucket_name, object_name, e_tag, partial, range, progress)
+                .await
+            {
+                Ok(()) => {
+                    return OpenOptions::new()
+                        .append(true)
+                        .open(state)
+                        .and_then(|mut file| writeln!(file, "{part}"))
+                        .map_err(|err| {
+                            Error::io("download_to_file", state.display().to_string(), err)
+                        })
+                }
+                //Besides the retryable service errors, a connection dropped in the middle of
+                //the body surfaces as an I/O error
+                Err(err)
+                    if (err.is_retryable() || matches!(err, Error::Io { .. }))
+                        && attempt < self.multipart.max_attempts =>
+                {
+                    report!(
+                        self.sink,
+                        "Part {} of '{}' failed on attempt {}, retrying: {err}",
+                        (part + 1).to_string().yellow().bold(),
+                        object_name,
+                        attempt
+                    );
+                    self.backoff(attempt).await;
+                    attempt += 1;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+    async fn fetch_range(
+        &self,
+        bucket_name: &str,
+        object_name: &str,
+        e_tag: &str,
+        partial: &Path,
+        range: Option<(u64, u64)>,
+        progress: &ProgressTracker,
+    ) -> Result<(), Error> {
+        let io_err = |err| Error::io("download_to_file", partial.display().to_string(), err);
+        //If-Match fails the request if the object was replaced since the download began
+        let request = self
+            .client
+            .get_object()
+            .bucket(bucket_name)
+            .key(object_name);
+        let mut request = with_customer_key!(request, self.customer_key.as_ref());
+        if !e_tag.is_empty() {
+            request = request.if_match(e_tag);
+        }
+        if let Some((offset, length)) = range {
+            request = request.range(format!("bytes={}-{}", offset, offset + length - 1));
+        }
+        let mut output = request
+            .send()
+            .await
+            .map_sdk_err(Service::S3, "get_object")?;
+        let mut file = OpenOptions::new()
+            .write(true)
+            .open(partial)
+            .map_err(io_err)?;
+        file.seek(SeekFrom::Start(
+            range.map(|(offset, _)| offset).unwrap_or(0),
+        ))
+        .map_err(io_err)?;
+        //What a failed attempt wrote is fetched again by the next one
+        let mut written = 0;
+        let fetched = async {
+            while let Some(bytes) = TryStreamExt::try_next(&mut output.body)
+                .await
+                .map_err(|err| io_err(err.into()))?
+            {
+                file.write_all(&bytes).map_err(io_err)?;
+                written += bytes.len() as u64;
+                progress.advance(bytes.len() as u64);
+            }
+            file.flush().map_err(io_err)
+        }
+        .await;
+        if fetched.is_err() {
+            progress.rewind(written);
+        }
+        fetched
+    }
+    async fn verify_download(
+        &self,
+        bucket_name: &str,
+        object_name: &str,
+        head: &HeadObjectOutput,
+        partial: &Path,
+        size: u64,
+    ) -> Result<Option<TransferChecksum>, Error> {
+        let display = partial.display().to_string();
+        let written = fs::metadata(partial)
+            .map_err(|err| Error::io("download_to_file", &display, err))?
+            .len();
+        if written != size {
+            return Err(Error::integrity(
+                "download_to_file",
+                display,
+                format!("{size} bytes"),
+                format!("{written} bytes"),
+            ));
+        }
+        //A checksum stored with the object is checked rather than the ETag, since it holds
+        //whatever the encryption
+        let stored = [Checksum::Sha256, Checksum::Crc32c]
+            .into_iter()
+            .find_map(|checksum| stored_checksum!(head, checksum).map(|stored| (checksum, stored)));
+        if let Some((checksum, stored)) = stored {
+            let part_size = match stored.split_once('-') {
+                None => None,
+                Some((_, parts)) => {
+                    match self
+                        .uniform_part_size(bucket_name, object_name, size, parts)
+                        .await?
+                    {
+                        Some(part_size) => Some(part_size),
+                        None => return Ok(None),
+                    }
+                }
+            };
+            let computed = checksum_of_file(partial, checksum, part_size)
+                .map_err(|err| Error::io("download_to_file", &display, err))?;
+            if computed != stored {
+                return Err(Error::integrity(
+                    "download_to_file",
+                    display,
+                    format!("{} {stored}", checksum.as_str()),
+                    computed,
+                ));
+            }
+            return Ok(Some(TransferChecksum {
+                algorithm: checksum,
+                computed,
+                stored: Some(stored),
+            }));
+        }
+        //The ETag is only an MD5 digest for objects that aren't encrypted with KMS or SSE-C
+        let kms = head
+            .server_side_encryption()
+            .map(|sse| sse.as_str().starts_with("aws:kms"))
+            .unwrap_or(false);
+        let e_tag = head.e_tag().unwrap_or_default().trim_matches('"');
+        if kms || head.sse_customer_algorithm().is_some() || e_tag.is_empty() {
+            return Ok(None);
+        }
+        let digest = match e_tag.split_once('-') {
+            None => md5_of_file(partial, None),
+            //Multipart ETags are the MD5 of the parts' digests
+            Some((_, parts)) => {
+                match self
+                    .uniform_part_size(bucket_name, object_name, size, parts)
+                    .await?
+                {
+                    Some(part_size) => md5_of_file(partial, Some(part_size)),
+                    None => return Ok(None),
+                }
+            }
+        }
+        .map_err(|err| Error::io("download_to_file", &display, err))?;
+        if digest != e_tag {
+            return Err(Error::integrity(
+                "download_to_file",
+                display,
+                format!("ETag {e_tag}"),
+                digest,
+            ));
+        }
+        Ok(None)
+    }
+    /// The size a multipart object was uploaded with, which its first part tells, or `None`
+    /// when the parts differ in size and the object's checksum or ETag can't be recomputed
+    async fn uniform_part_size(
+        &self,
+        bucket_name: &str,
+        object_name: &str,
+        size: u64,
+        parts: &str,
+    ) -> Result<Option<u64>, Error> {
+        let request = self
+            .client
+            .head_object()
+            .bucket(bucket_name)
+            .key(object_name)
+            .part_number(1);
+        let first_part = with_customer_key!(request, self.customer_key.as_ref())
+            .send()
+            .await
+            .map_sdk_err(Service::S3, "head_object")?;
+        let part_size = first_part.content_length().max(1) as u64;
+        if ((size + part_size - 1) / part_size).to_string() != parts {
+            report!(
+                self.sink,
+                "The parts of '{}' differ in size, so it can't be checked against its checksum or ETag",
+                object_name
+            );
+            return Ok(None);
+        }
+        Ok(Some(part_size))
+    }
+    //An upload whose checksum S3 stored differently than computed here is an error, even
+    //though the object was written
+    fn verify_upload_checksum(
+        &self,
+        data_path: &str,
+        computed: Option<(Checksum, String)>,
+        stored: Option<String>,
+    ) -> Result<Option<TransferChecksum>, Error> {
+        let Some((algorithm, computed)) = computed else {
+            return Ok(None);
+        };
+        match &stored {
+            Some(stored) if *stored != computed => {
+                return Err(Error::integrity(
+                    "upload_content_to_a_bucket",
+                    data_path,
+                    format!("{} {computed}", algorithm.as_str()),
+                    stored.clone(),
+                ))
+            }
+            Some(_) => report!(
+                self.sink,
+                "The {} checksum of '{}' was verified by S3: {}",
+                algorithm.as_str(),
+                data_path.green().bold(),
+                computed.green().bold()
+            ),
+            None => report!(
+                self.sink,
+                "{}",
+                format!(
+                    "S3 returned no {} checksum to compare with '{data_path}'",
+                    algorithm.as_str()
+                )
+                .yellow()
+                .bold()
+            ),
+        }
+        Ok(Some(TransferChecksum {
+            algorithm,
+            computed,
+            stored,
+        }))
+    }
+    /// The objects under a prefix keyed by the rest of their key. Folder placeholders,
+    /// whose keys end with `/`, are left out.
+    async fn remote_objects(
+        &self,
+        bucket_name: &str,
+        prefix: &str,
+    ) -> Result<BTreeMap<String, Object>, Error> {
+        let objects = collect_items(self.list_objects_stream(
+            bucket_name,
+            Some(prefix).filter(|prefix| !prefix.is_empty()),
+        ))
+        .await?;
+        Ok(objects
+            .into_iter()
+            .filter_map(|object| {
+                let relative = object.key()?.strip_prefix(prefix)?.to_string();
+                (!relative.is_empty() && !relative.ends_with('/')).then_some((relative, object))
+            })
+            .collect())
+    }
+    /// Deletes the objects with `delete_objects`, which takes up to 1000 of them per request,
+    /// and collects the ones S3 refused instead of stopping at them.
+    async fn delete_identifiers(
+        &self,
+        bucket_name: &str,
+        objects: Vec<ObjectIdentifier>,
+    ) -> Result<BulkDeleteReport, Error> {
+        let mut delete_report = BulkDeleteReport::default();
+        for chunk in objects.chunks(1000) {
+            let output = self
+                .client
+                .delete_objects()
+                .bucket(bucket_name)
+                .delete(
+                    Delete::builder()
+                        .set_objects(Some(chunk.to_vec()))
+                        .quiet(true)
+                        .build(),
+                )
+                .send()
+                .await
+                .map_sdk_err(Service::S3, "delete_objects")?;
+            //Quiet mode lists only the objects that couldn't be deleted
+            let failed = output
+                .errors()
+                .unwrap_or_default()
+                .iter()
+                .map(|error| DeleteFailure {
+                    key: error.key().unwrap_or_default().to_string(),
+                    version_id: error.version_id().map(|version| version.to_string()),
+                    code: error.code().unwrap_or_default().to_string(),
+                    message: error.message().unwrap_or_default().to_string(),
+                })
+                .collect::<Vec<_>>();
+            for object in chunk {
+                let key = object.key().unwrap_or_default();
+                let refused = failed.iter().any(|failure| {
+                    failure.key == key && failure.version_id.as_deref() == object.version_id()
+                });
+                if !refused {
+                    report!(self.sink, "delete: s3://{bucket_name}/{}", key.red().bold());
+                    delete_report.deleted.push(key.to_string());
+                }
+            }
+            for failure in &failed {
+                report!(
+                    self.sink,
+                    "{} s3://{bucket_name}/{}: {} {}",
+                    "delete failed:".red().bold(),
+                    failure.key.yellow().bold(),
+                    failure.code,
+                    failure.message
+                );
+            }
+            delete_report.failed.extend(failed);
+        }
+        Ok(delete_report)
+    }
+    fn report_sync(&self, sync_report: &SyncReport) {
+        let dry_run = if sync_report.dry_run { "(dryrun) " } else { "" };
+        report!(
+            self.sink,
+            "{dry_run}{} transferred, {} deleted, {} already up to date\n",
+            sync_report.transferred.len().to_string().green().bold(),
+            sync_report.deleted.len().to_string().red().bold(),
+            sync_report.unchanged.to_string().green().bold()
+        );
+    }
+    fn track(
+        &self,
+        direction: TransferDirection,
+        bucket_name: &str,
+        object_name: &str,
+        total_bytes: u64,
+        resumed_bytes: u64,
+    ) -> ProgressTracker {
+        ProgressTracker::start(
+            self.progress.clone(),
+            direction,
+            bucket_name,
+            object_name,
+            total_bytes,
+            resumed_bytes,
+        )
+    }
+    async fn backoff(&self, attempt: u32) {
+        if let Some(sleep) = self.config.sleep_impl().or_else(default_async_sleep) {
+            sleep.sleep(Duration::from_millis(200 << attempt)).await;
+        }
+    }
+    /// Download the content through the output sink. The key's prefixes become directories
+    /// under `path_prefix` (or the sink's own location), as mapped by [`KeyPathMapper`], and
+    /// an existing file is handled according to [`S3Ops::with_conflict_policy`]
+    pub async fn download_content_from_bcuket(
+        &self,
+        bucket_name: &str,
+        object_name: &str,
+        path_prefix: Option<&str>,
+        print_info: bool,
+    ) -> Result<(), Error> {
+        let mapper =
+            KeyPathMapper::new(path_prefix.unwrap_or_default()).on_conflict(self.conflict_policy);
+        let file_path = mapper
+            .local_path(object_name)
+            .ok_or_else(|| {
+                Error::invalid_input(
+                    "download_content_from_bcuket",
+                    format!("'{object_name}' doesn't map to a file name"),
+                )
+            })?
+            .to_string_lossy()
+            .into_owned();
+        //Sinks that write to disk get the object streamed into the file; the others get
+        //its bytes
+        let (content_type, content_length, last_modified, location) =
+            match self.sink.artifact_path(&file_path) {
+                Some(path) => {
+                    let Some(path) = mapper.resolve(path) else {
+                        report!(
+                            self.sink,
+                            "Skipping '{}', which already exists\n",
+                            file_path.yellow().bold()
+                        );
+                        return Ok(());
+                    };
+                    let head = self
+                        .download_to_file(bucket_name, object_name, &path)
+                        .await?;
+                    (
+                        head.content_type,
+                        head.content_length,
+                        head.last_modified,
+                        format!("'{}'", path.display()),
+                    )
+                }
+                None => {
+                    let request = self
+                        .client
+                        .get_object()
+                        .bucket(bucket_name)
+                        .key(object_name);
+                    let mut get_body_data = with_customer_key!(request, self.customer_key.as_ref())
+                        .send()
+                        .await
+                        .map_sdk_err(Service::S3, "get_object")?;
+                    let progress = self.track(
+                        TransferDirection::Download,
+                        bucket_name,
+                        object_name,
+                        get_body_data.content_length.max(0) as u64,
+                        0,
+                    );
+                    let mut bytes = Vec::new();
+                    while let Some(chunk) = progress.finish(
+                        TryStreamExt::try_next(&mut get_body_data.body)
+                            .await
+                            .map_err(|err| {
+                                Error::io("download_content_from_bcuket", &file_path, err.into())
+                            }),
+                    )? {
+                        progress.advance(chunk.len() as u64);
+                        bytes.extend_from_slice(&chunk);
+                    }
+                    let location = match self.sink.write_artifact(&file_path, &bytes) {
+                        Ok(location) => location,
+                        Err(err) => {
+                            if print_info {
+                                report!(self.sink, "{}\n", "Error while writing\n".red().bold());
+                            }
+                            return Err(Error::io("download_content_from_bcuket", file_path, err));
+                        }
+                    };
+                    (
+                        get_body_data.content_type,
+                        get_body_data.content_length,
+                        get_body_data.last_modified,
+                        location,
+                    )
+                }
+            };
+        if print_info {
+            if let Some(content_type) = content_type {
+                report!(
+                    self.sink,
+                    "Content type of response body: {}",
+                    content_type.green().bold()
+                );
+            }
+
+            let content_length = content_length as f64 * 0.000001;
+            let content_length_colored = content_length.to_string().green().bold();
+            report!(
+                self.sink,
+                "The content length/size of data in MB: {content_length_colored:.3}mb"
+            );
+            let last_modified = last_modified
+                .map(|format| {
+                    format
+                        .fmt(aws_sdk_memorydb::primitives::DateTimeFormat::HttpDate)
+                        .ok()
+                })
+                .flatten();
+            if let Some(time) = last_modified {
+                report!(self.sink, "Last Modified: {}\n", time.green().bold());
+            }
+            report!(self.sink, "{}\n", "Writing data...".bright_green().bold());
+            let colored_key_name = object_name.green().bold();
+            report!(
+                self.sink,
+                "The content of the {colored_key_name} is saved in {location}\n"
+            );
+        }
+        Ok(())
+    }
+    /// Downloads an object straight into a file without holding it in memory, returning
+    /// the object's metadata. Objects at or above the [`MultipartConfig::threshold`] are
+    /// fetched as parallel byte ranges.
+    ///
+    /// The data is written to `<path>.part` first. If the download is interrupted, calling
+    /// this again with the same path fetches only the missing ranges, provided the object's
+    /// ETag hasn't changed in between. The finished file is checked against the object's
+    /// size and the CRC32C or SHA-256 checksum stored with it, or without one and unless the
+    /// object is encrypted with KMS or a customer key, against the MD5 digest in its ETag
+    /// before it's moved to `path`.
+    pub async fn download_to_file(
+        &self,
+        bucket_name: &str,
+        object_name: &str,
+        path: impl AsRef<Path>,
+    ) -> Result<HeadObjectOutput, Error> {
+        self.download_object(bucket_name, object_name, path.as_ref())
+            .await
+            .map(|(head, _)| head)
+    }
+    /// [`download_to_file`](Self::download_to_file), reporting the checksum it computed
+    /// next to the one stored with the object
+    pub async fn download_verified(
+        &self,
+        bucket_name: &str,
+        object_name: &str,
+        path: impl AsRef<Path>,
+    ) -> Result<TransferResult, Error> {
+        let (head, checksum) = self
+            .download_object(bucket_name, object_name, path.as_ref())
+            .await?;
+        Ok(TransferResult {
+            bucket: bucket_name.to_string(),
+            key: object_name.to_string(),
+            size: head.content_length().max(0) as u64,
+            e_tag: head.e_tag().map(|e_tag| e_tag.to_string()),
+            checksum,
+        })
+    }
+    async fn download_object(
+        &self,
+        bucket_name: &str,
+        object_name: &str,
+        path: &Path,
+    ) -> Result<(HeadObjectOutput, Option<TransferChecksum>), Error> {
+        let request = self
+            .client
+            .head_object()
+            .bucket(bucket_name)
+            .key(object_name)
+            .checksum_mode(ChecksumMode::Enabled);
+        let head = with_customer_key!(request, self.customer_key.as_ref())
+            .send()
+            .await
+            .map_sdk_err(Service::S3, "head_object")?;
+        let size = head.content_length().max(0) as u64;
+        let e_tag = head.e_tag().unwrap_or_default().to_string();
+        let partial = with_suffix(path, ".part");
+        let state = with_suffix(path, ".part.state");
+        let io_err =
+            |path: &Path, err| Error::io("download_to_file", path.display().to_string(), err);
+
+        let part_size = if size >= self.multipart.threshold {
+            self.multipart.part_size_for(size)
+        } else {
+            size.max(1)
+        };
+        let part_count = ((size + part_size - 1) / part_size).max(1);
+        let mut done = read_download_state(&state, &e_tag);
+        if done.is_empty() || !partial.exists() {
+            done.clear();
+            if let Some(parent) = path
+                .parent()
+                .filter(|parent| !parent.as_os_str().is_empty())
+            {
+                fs::create_dir_all(parent).map_err(|err| io_err(parent, err))?;
+            }
+            File::create(&partial)
+                .and_then(|file| file.set_len(size))
+                .map_err(|err| io_err(&partial, err))?;
+            fs::write(&state, format!("{e_tag}\n")).map_err(|err| io_err(&state, err))?;
+        } else {
+            report!(
+                self.sink,
+                "Resuming the download of '{}'; {} of {} parts are already on disk",
+                object_name.green().bold(),
+                done.len().to_string().green().bold(),
+                part_count
+            );
+        }
+        let resumed = done
+            .iter()
+            .map(|part| part_size.min(size - part * part_size))
+            .sum();
+        let progress = &self.track(
+            TransferDirection::Download,
+            bucket_name,
+            object_name,
+            size,
+            resumed,
+        );
+        let pending = (0..part_count)
+            .filter(|part| !done.contains(part))
+            .map(|part| {
+                let offset = part * part_size;
+                let length = part_size.min(size - offset);
+                //A single part is fetched without a range so empty objects work too
+                let range = (part_count > 1).then_some((offset, length));
+                self.download_range(
+                    bucket_name,
+                    object_name,
+                    &e_tag,
+                    &partial,
+                    &state,
+                    part,
+                    range,
+                    progress,
+                )
+            });
+        let downloaded = futures_util::StreamExt::buffer_unordered(
+            stream::iter(pending),
+            self.multipart.concurrency,
+        )
+        .try_collect::<Vec<_>>()
+        .await;
+        progress.finish(downloaded)?;
+
+        let checksum = match self
+            .verify_download(bucket_name, object_name, &head, &partial, size)
+            .await
+        {
+            Ok(checksum) => checksum,
+            Err(err) => {
+                //Resuming would only keep the bad data, so start over next time
+                let _ = fs::remove_file(&partial);
+                let _ = fs::remove_file(&state);
+                return progress.finish(Err(err));
+            }
+        };
+        fs::rename(&partial, path).map_err(|err| io_err(path, err))?;
+        let _ = fs::remove_file(&state);
+        Ok((head, checksum))
+    }
+    /// Uploads the files of a local directory that are missing or changed under the prefix,
+    /// like `aws s3 sync <dir> s3://<bucket>/<prefix>`. A file has changed when its size
+    /// differs from the object's, or when it's newer than the object and its MD5 digest
+    /// doesn't match the object's ETag. Each file goes through
+    /// [`upload_content_to_a_bucket`](Self::upload_content_to_a_bucket)
+    pub async fn sync_up(
+        &self,
+        local_dir: impl AsRef<Path>,
+        bucket_name: &str,
+        prefix: &str,
+        options: &SyncOptions,
+    ) -> Result<SyncReport, Error> {
+        let local_dir = local_dir.as_ref();
+        let prefix = key_prefix(prefix);
+        let local = local_files(local_dir, self.sink.as_ref())?;
+        let remote = self.remote_objects(bucket_name, &prefix).await?;
+        let mut sync_report = SyncReport {
+            dry_run: options.dry_run,
+            ..Default::default()
+        };
+        for (relative, file) in local
+            .iter()
+            .filter(|(relative, _)| options.is_included(relative))
+        {
+            let changed = match remote.get(relative) {
+                Some(object) => needs_transfer(file, object, true)?,
+                None => true,
+            };
+            if !changed {
+                sync_report.unchanged += 1;
+                continue;
+            }
+            let key = format!("{prefix}{relative}");
+            let data_path = file.path_str("sync")?;
+            if options.dry_run {
+                report!(
+                    self.sink,
+                    "(dryrun) upload: {} to s3://{bucket_name}/{key}",
+                    data_path.green().bold()
+                );
+            } else {
+                self.upload_content_to_a_bucket(bucket_name, data_path, &key)
+                    .await?;
+            }
+            sync_report.transferred.push(relative.clone());
+        }
+        if options.delete {
+            sync_report.deleted = remote
+                .keys()
+                .filter(|relative| options.is_included(relative) && !local.contains_key(*relative))
+                .cloned()
+                .collect();
+            let keys = sync_report
+                .deleted
+                .iter()
+                .map(|relative| format!("{prefix}{relative}"))
+                .collect::<Vec<_>>();
+            if options.dry_run {
+                keys.iter().for_each(|key| {
+                    report!(
+                        self.sink,
+                        "(dryrun) delete: s3://{bucket_name}/{}",
+                        key.red().bold()
+                    )
+                });
+            } else {
+                let objects = keys
+                    .iter()
+                    .map(|key| ObjectIdentifier::builder().key(key).build())
+                    .collect();
+                let deleted = self.delete_identifiers(bucket_name, objects).await?;
+                if let Some(failure) = deleted.failed().first() {
+                    return Err(Error::other(
+                        "delete_objects",
+                        format!(
+                            "'{}' could not be deleted: {}",
+                            failure.key, failure.message
+                        ),
+                    ));
+                }
+            }
+        }
+        self.report_sync(&sync_report);
+        Ok(sync_report)
+    }
+    /// Downloads the objects under the prefix that are missing or changed in a local
+    /// directory, like `aws s3 sync s3://<bucket>/<prefix> <dir>`, comparing them the same
+    /// way as [`sync_up`](Self::sync_up). Each object goes through
+    /// [`download_to_file`](Self::download_to_file), at the path [`KeyPathMapper`] gives it
+    pub async fn sync_down(
+        &self,
+        bucket_name: &str,
+        prefix: &str,
+        local_dir: impl AsRef<Path>,
+        options: &SyncOptions,
+    ) -> Result<SyncReport, Error> {
+        let local_dir = local_dir.as_ref();
+        let prefix = key_prefix(prefix);
+        let remote = self.remote_objects(bucket_name, &prefix).await?;
+        let local = if local_dir.exists() {
+            local_files(local_dir, self.sink.as_ref())?
+        } else {
+            BTreeMap::new()
+        };
+        let mut sync_report = SyncReport {
+            dry_run: options.dry_run,
+            ..Default::default()
+        };
+        //The objects keyed by the sanitized path they are stored at, relative to the directory
+        let mapper = KeyPathMapper::new(local_dir);
+        let mut mapped = BTreeMap::new();
+        for (relative, object) in remote
+            .iter()
+            .filter(|(relative, _)| options.is_included(relative))
+        {
+            match mapper.local_path(relative) {
+                Some(path) => {
+                    let local_relative = path
+                        .strip_prefix(local_dir)
+                        .unwrap_or(&path)
+                        .iter()
+                        .map(|component| component.to_string_lossy())
+                        .collect::<Vec<_>>()
+                        .join("/");
+                    mapped.insert(local_relative, (relative, object, path));
+                }
+                None => report!(
+                    self.sink,
+                    "Skipping '{}', which can't be stored under '{}'",
+                    relative.yellow().bold(),
+                    local_dir.display()
+                ),
+            }
+        }
+        for (local_relative, (relative, object, path)) in &mapped {
+            let changed = match local.get(local_relative) {
+                Some(file) => needs_transfer(file, object, false)?,
+                None => true,
+            };
+            if !changed {
+                sync_report.unchanged += 1;
+                continue;
+            }
+            let key = format!("{prefix}{relative}");
+            if options.dry_run {
+                report!(
+                    self.sink,
+                    "(dryrun) download: s3://{bucket_name}/{key} to {}",
+                    path.display().to_string().green().bold()
+                );
+            } else {
+                self.download_to_file(bucket_name, &key, path).await?;
+                report!(
+                    self.sink,
+                    "download: s3://{bucket_name}/{key} to {}",
+                    path.display().to_string().green().bold()
+                );
+            }
+            sync_report.transferred.push(local_relative.clone());
+        }
+        if options.delete {
+            sync_report.deleted = local
+                .keys()
+                .filter(|relative| options.is_included(relative) && !mapped.contains_key(*relative))
+                .cloned()
+                .collect();
+            for relative in &sync_report.deleted {
+                let path = &local[relative].path;
+                if options.dry_run {
+                    report!(
+                        self.sink,
+                        "(dryrun) delete: {}",
+                        path.display().to_string().red().bold()
+                    );
+                } else {
+                    fs::remove_file(path)
+                        .map_err(|err| Error::io("sync_down", path.display().to_string(), err))?;
+                }
+            }
+        }
+        self.report_sync(&sync_report);
+        Ok(sync_report)
+    }
+    /// Publishes a local directory, such as the build output of a documentation site, as the
+    /// bucket's static website and returns the endpoint it's served at:
+    ///
+    /// - the changed files are uploaded with detected content types and the `Cache-Control`
+    ///   [`SiteOptions`] gives them, assets first and HTML pages last, so no page is served
+    ///   before what it links to
+    /// - objects the directory no longer has are deleted, unless [`SiteOptions::delete_stale`]
+    ///   turns that off
+    /// - website hosting is configured with the index and error documents and redirect rules
+    /// - the bucket is made publicly readable by a policy that only allows `s3:GetObject`;
+    ///   the public access block keeps ACLs from making anything public
+    ///
+    /// As guard rails, a directory without the index document is refused, and so is a bucket
+    /// that holds objects but isn't a website yet, unless [`SiteOptions::adopt_existing`]
+    /// says it's meant to be taken over
+    pub async fn deploy_static_site(
+        &self,
+        local_dir: impl AsRef<Path>,
+        bucket_name: &str,
+        options: &SiteOptions,
+    ) -> Result<SiteDeployment, Error> {
+        let local_dir = local_dir.as_ref();
+        let local = local_files(local_dir, self.sink.as_ref())?;
+        if !local.contains_key(options.index()) {
+            return Err(Error::invalid_input(
+                "deploy_static_site",
+                format!(
+                    "'{}' has no '{}' at its top, so it doesn't look like a site",
+                    local_dir.display(),
+                    options.index()
+                ),
+            ));
+        }
+        let remote = self.remote_objects(bucket_name, "").await?;
+        if !remote.is_empty() && !options.adopts_existing() && !self.is_website(bucket_name).await?
+        {
+            return Err(Error::invalid_input(
+                "deploy_static_site",
+                format!("the bucket '{bucket_name}' holds {} objects but isn't a website; deploying would make them public, so allow it with SiteOptions::adopt_existing", remote.len()),
+            ));
+        }
+
+        let mut files = SyncReport::default();
+        let mut changed = Vec::new();
+        for (relative, file) in &local {
+            match remote.get(relative) {
+                Some(object) if !needs_transfer(file, object, true)? => files.unchanged += 1,
+                _ => changed.push((relative, file)),
+            }
+        }
+        //Stable, so the pages and the assets each keep their order
+        changed
+            .sort_by_key(|(relative, _)| relative.ends_with(".html") || relative.ends_with(".htm"));
+        for (relative, file) in changed {
+            let upload_options =
+                UploadOptions::new().cache_control(options.cache_control_of(relative));
+            self.upload_with_options(
+                bucket_name,
+                file.path_str("deploy_static_site")?,
+                relative,
+                &upload_options,
+            )
+            .await?;
+            files.transferred.push(relative.clone());
+        }
+        if options.deletes_stale() {
+            files.deleted = remote
+                .keys()
+                .filter(|relative| !local.contains_key(*relative))
+                .cloned()
+                .collect();
+            let objects = files
+                .deleted
+                .iter()
+                .map(|key| ObjectIdentifier::builder().key(key).build())
+                .collect();
+            let deleted = self.delete_identifiers(bucket_name, objects).await?;
+            if let Some(failure) = deleted.failed().first() {
+                return Err(Error::other(
+                    "delete_objects",
+                    format!(
+                        "'{}' could not be deleted: {}",
+                        failure.key, failure.message
+                    ),
+                ));
+            }
+        }
+        self.report_sync(&files);
+
+        self.client
+            .put_bucket_website()
+            .bucket(bucket_name)
+            .website_configuration(options.website_configuration())
+            .send()
+            .await
+            .map_sdk_err(Service::S3, "put_bucket_website")?;
+        self.put_public_access_block(
+            bucket_name,
+            PublicAccessBlock {
+                block_public_acls: true,
+                ignore_public_acls: true,
+                block_public_policy: false,
+                restrict_public_buckets: false,
+            },
+        )
+        .await?;
+        self.put_bucket_policy(bucket_name, &public_read_policy(bucket_name))
+            .await?;
+
+        let location = self
+            .client
+            .get_bucket_location()
+            .bucket(bucket_name)
+            .send()
+            .await
+            .map_sdk_err(Service::S3, "get_bucket_location")?;
+        //Buckets in us-east-1 have no location constraint, and old ones in eu-west-1 have `EU`
+        let region = match location
+            .location_constraint()
+            .map(|location| location.as_str())
+        {
+            None | Some("") => "us-east-1",
+            Some("EU") => "eu-west-1",
+            Some(region) => region,
+        };
+        let endpoint = website_endpoint(bucket_name, region);
+        report!(
+            self.sink,
+            "The site in '{}' is served at {}\n",
+            local_dir.display(),
+            endpoint.green().bold()
+        );
+        Ok(SiteDeployment::new(endpoint, files))
+    }
+    async fn is_website(&self, bucket_name: &str) -> Result<bool, Error> {
+        match self
+            .client
+            .get_bucket_website()
+            .bucket(bucket_name)
+            .send()
+            .await
+            .map_sdk_err(Service::S3, "get_bucket_website")
+        {
+            Ok(_) => Ok(true),
+            Err(err) if err.code() == Some("NoSuchWebsiteConfiguration") => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+    /// Downloads every object under a prefix into a local directory, keeping the key
+    /// hierarchy below the prefix as mapped by [`KeyPathMapper`]. Up to `concurrency`
+    /// objects are downloaded at the same time, each through
+    /// [`download_to_file`](Self::download_to_file), and the paths written are returned
+    pub async fn download_prefix(
+        &self,
+        bucket_name: &str,
+        prefix: &str,
+        local_dir: impl AsRef<Path>,
+        concurrency: usize,
+    ) -> Result<Vec<PathBuf>, Error> {
+        let prefix = key_prefix(prefix);
+        let mapper = KeyPathMapper::new(local_dir.as_ref())
+            .strip_prefix(prefix.as_str())
+            .on_conflict(self.conflict_policy);
+        let remote = self.remote_objects(bucket_name, &prefix).await?;
+        let mut downloads = Vec::new();
+        //The downloads run at the same time, so no two keys may be given the same path
+        let mut given = HashSet::new();
+        for relative in remote.keys() {
+            let key = format!("{prefix}{relative}");
+            match mapper.map_unique(&key, &mut given) {
+                Some(path) => downloads.push((key, path)),
+                None => report!(
+                    self.sink,
+                    "Skipping '{}', which has no usable file name or already exists\n",
+                    key.yellow().bold()
+                ),
+            }
+        }
+        let pending = downloads.into_iter().map(|(key, path)| async move {
+            self.download_to_file(bucket_name, &key, &path).await?;
+            report!(
+                self.sink,
+                "download: s3://{bucket_name}/{key} to {}",
+                path.display().to_string().green().bold()
+            );
+            Ok::<_, Error>(path)
+        });
+        let paths =
+            futures_util::StreamExt::buffer_unordered(stream::iter(pending), concurrency.max(1))
+                .try_collect::<Vec<_>>()
+                .await?;
+        report!(
+            self.sink,
+            "{} objects were downloaded from s3://{bucket_name}/{prefix}\n",
+            paths.len().to_string().green().bold()
+        );
+        Ok(paths)
+    }
+    /// Deletes every object under a prefix with the batched `DeleteObjects` API, listing and
+    /// deleting 1000 keys at a time. Keys S3 refuses to delete, for example because of a
+    /// bucket policy, don't stop the others and are listed in the returned report. An empty
+    /// prefix is rejected so a whole bucket isn't emptied by accident
+    pub async fn delete_prefix(
+        &self,
+        bucket_name: &str,
+        prefix: &str,
+    ) -> Result<BulkDeleteReport, Error> {
+        let prefix = key_prefix(prefix);
+        if prefix.is_empty() {
+            return Err(Error::invalid_input(
+                "delete_prefix",
+                "the prefix is empty, which would delete the whole bucket",
+            ));
+        }
+        let mut pages = futures_util::StreamExt::chunks(
+            self.list_objects_stream(bucket_name, Some(&prefix)),
+            1000,
+        );
+        let mut delete_report = BulkDeleteReport::default();
+        while let Some(page) = pages.next().await {
+            let objects = page
+                .into_iter()
+                .map(|object| {
+                    object.map(|object| {
+                        ObjectIdentifier::builder()
+                            .key(object.key().unwrap_or_default())
+                            .build()
+                    })
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+            let deleted = self.delete_identifiers(bucket_name, objects).await?;
+            delete_report.deleted.extend(deleted.deleted);
+            delete_report.failed.extend(deleted.failed);
         }
+        report!(
+            self.sink,
+            "{} objects were deleted from s3://{bucket_name}/{prefix}, {} could not be\n",
+            delete_report.deleted.len().to_string().green().bold(),
+            delete_report.failed.len().to_string().red().bold()
+        );
+        Ok(delete_report)
     }
-
-    ///Upload large files using chunks instead of uploading the entire file, while
-    /// accepting the same parameters as the method above.
-    pub async fn mulitpart_upload(&self, bucket_name: &str, object_name: &str, data_path: &str) {
-        let client = S3Client::new(self.config);
-
-        let colored_msg = "Error from multipart_upload function\n".red().bold();
-        let mulit_part = client
-            .create_multipart_upload()
-            .bucket(bucket_name)
-            .key(object_name)
-            .send()
-            .await
-            .expect(&colored_msg);
-
-        let data = ByteStream::from_path(data_path).await.unwrap();
-
-        let upload_id = mulit_part.upload_id().unwrap();
-
-        let upload_part_result = client
-            .upload_part()
-            .bucket(mulit_part.bucket().unwrap())
-            .key(mulit_part.key().unwrap())
-            .body(data)
-            .upload_id(upload_id)
-            .part_number(30)
+    /// Copies an object within S3, between buckets too, without downloading it. Objects
+    /// over 5 GiB are copied as a multipart upload of `UploadPartCopy` ranges, which keeps
+    /// the metadata and tags the same way. Objects encrypted with SSE-C are read with the key
+    /// from [`with_customer_key`](Self::with_customer_key)
+    pub async fn copy_object(
+        &self,
+        source_bucket: &str,
+        source_key: &str,
+        dest_bucket: &str,
+        dest_key: &str,
+        options: &CopyOptions,
+    ) -> Result<(), Error> {
+        let request = self
+            .client
+            .head_object()
+            .bucket(source_bucket)
+            .key(source_key);
+        let head = with_customer_key!(request, self.customer_key.as_ref())
             .send()
             .await
-            .unwrap();
-
-        let mut completed_part = Vec::new();
-        completed_part.push(
-            CompletedPart::builder()
-                .e_tag(upload_part_result.e_tag().unwrap_or_default())
-                .part_number(30)
-                .build(),
+            .map_sdk_err(Service::S3, "head_object")?;
+        let copy_source = format!(
+            "{source_bucket}/{}",
+            utf8_percent_encode(source_key, COPY_SOURCE_ENCODE_SET)
         );
-
-        let completed_multipart = CompletedMultipartUpload::builder()
-            .set_parts(Some(completed_part))
-            .build();
-
-        client
-            .complete_multipart_upload()
-            .bucket(bucket_name)
-            .key(object_name)
-            .multipart_upload(completed_multipart)
-            .upload_id(upload_id)
+        let size = head.content_length().max(0) as u64;
+        let progress = self.track(TransferDirection::Copy, dest_bucket, dest_key, size, 0);
+        if size > MAX_COPY_SIZE {
+            let tagging = match &options.tags {
+                Some(tags) => tagging_header(tags),
+                None => tagging_header(&self.object_tags(source_bucket, source_key).await?),
+            };
+            let copied = self
+                .multipart_copy(
+                    &copy_source,
+                    &head,
+                    tagging,
+                    dest_bucket,
+                    dest_key,
+                    options,
+                    &progress,
+                )
+                .await;
+            progress.finish(copied)?;
+        } else {
+            let directive = |replace: bool| match replace {
+                true => (MetadataDirective::Replace, TaggingDirective::Replace),
+                false => (MetadataDirective::Copy, TaggingDirective::Copy),
+            };
+            let request = self
+                .client
+                .copy_object()
+                .bucket(dest_bucket)
+                .key(dest_key)
+                .copy_source(&copy_source)
+                .metadata_directive(directive(options.metadata.is_some()).0)
+                .tagging_directive(directive(options.tags.is_some()).1)
+                .set_tagging(options.tags.as_deref().and_then(tagging_header))
+                .set_acl(options.acl.clone())
+                .set_storage_class(options.storage_class.clone());
+            let request = match &options.metadata {
+                Some(metadata) => request
+                    .set_content_type(metadata.content_type.clone())
+                    .set_metadata(metadata.user_metadata())
+                    .set_cache_control(metadata.cache_control.clone())
+                    .set_content_disposition(metadata.content_disposition.clone()),
+                None => request,
+            };
+            let request = with_copy_source_key!(request, self.customer_key.as_ref());
+            progress.finish(
+                with_encryption!(request, options.encryption.as_ref())
+                    .send()
+                    .await
+                    .map_sdk_err(Service::S3, "copy_object"),
+            )?;
+            progress.advance(size);
+        }
+        if options.acl.is_none() {
+            self.copy_acl(source_bucket, source_key, dest_bucket, dest_key)
+                .await?;
+        }
+        report!(
+            self.sink,
+            "copy: s3://{source_bucket}/{source_key} to s3://{dest_bucket}/{}",
+            dest_key.green().bold()
+        );
+        Ok(())
+    }
+    /// Copies an object with [`copy_object`](Self::copy_object) and deletes the source once
+    /// the copy exists. Moving an object onto itself is rejected, since the delete would
+    /// remove it
+    pub async fn move_object(
+        &self,
+        source_bucket: &str,
+        source_key: &str,
+        dest_bucket: &str,
+        dest_key: &str,
+        options: &CopyOptions,
+    ) -> Result<(), Error> {
+        if source_bucket == dest_bucket && source_key == dest_key {
+            return Err(Error::invalid_input(
+                "move_object",
+                format!("s3://{source_bucket}/{source_key} can't be moved onto itself"),
+            ));
+        }
+        self.copy_object(source_bucket, source_key, dest_bucket, dest_key, options)
+            .await?;
+        self.client
+            .delete_object()
+            .bucket(source_bucket)
+            .key(source_key)
             .send()
             .await
-            .unwrap();
+            .map_sdk_err(Service::S3, "delete_object")?;
+        report!(
+            self.sink,
+            "delete: s3://{source_bucket}/{}",
+            source_key.red().bold()
+        );
+        Ok(())
     }
-    /// Download the content to the current directory, using the name of the content
-    /// file being downloaded. This process accepts a bucket name and key to retrieve
-    /// the actual data
-    pub async fn download_content_from_bcuket(
+    /// Moves every object under a prefix to another prefix, in the same bucket or another
+    /// one, so `reports/2023/q1.csv` becomes `archive/2023/q1.csv`. Up to `concurrency`
+    /// objects are copied at the same time; the sources that were copied are then deleted in
+    /// batches. Objects that fail don't stop the others and are listed in the returned report.
+    /// An empty source prefix is rejected so a whole bucket isn't moved by accident, and so
+    /// are prefixes in the same bucket where one contains the other, whose copies would
+    /// overwrite sources that are then deleted
+    pub async fn rename_prefix(
         &self,
-        bucket_name: &str,
-        object_name: &str,
-        path_prefix: Option<&str>,
-        print_info: bool,
-    ) {
-        let client = S3Client::new(self.config);
-
-        let client = client.get_object().bucket(bucket_name).key(object_name);
-
-        let colored_msg = "Error from download_content_from_bucket function\n"
-            .red()
-            .bold();
-        let get_body_data = client.send().await.expect(&colored_msg);
-
-        //The regex engine doesn't support look-arounds, including look-aheads and look-behinds. Therefore,
-        //this option is used as a secondary condition. This ensures that even if it matches the dot, it won't
-        // have a chance to retrieve values with dots, as the first match takes precedence.
-        let have_slash_and_dot_pattern =
-            Regex::new(r#"([^./]+)\.([^/]+)"#).expect("Error while parsing Regex Syntax\n");
-        let have_slash_but_no_extension_pattern =
-            Regex::new(r#"/([^/]+)$"#).expect("Error while parsing regex syntax");
-        let no_slash_no_dot_pattern =
-            Regex::new(r#"^[^./]*$"#).expect("Error while parsing Regex syntax");
-        let file_name: Vec<String> = if have_slash_and_dot_pattern.is_match(object_name) {
-            have_slash_and_dot_pattern
-                .find_iter(object_name)
-                .map(|string| string.as_str().to_string())
-                .collect()
-        } else if have_slash_but_no_extension_pattern.is_match(object_name) {
-            have_slash_but_no_extension_pattern
-                .find_iter(object_name)
-                .map(|string| string.as_str().to_string())
-                .collect()
-        } else {
-            no_slash_no_dot_pattern
-                .find_iter(object_name)
-                .map(|string| string.as_str().to_string())
-                .collect()
-        };
-        let mut file_name = file_name.join("");
-        if file_name.starts_with("/") {
-            file_name.remove(0);
+        source_bucket: &str,
+        source_prefix: &str,
+        dest_bucket: &str,
+        dest_prefix: &str,
+        options: &CopyOptions,
+        concurrency: usize,
+    ) -> Result<RenameReport, Error> {
+        let source_prefix = key_prefix(source_prefix);
+        let dest_prefix = key_prefix(dest_prefix);
+        if source_prefix.is_empty() {
+            return Err(Error::invalid_input(
+                "rename_prefix",
+                "the source prefix is empty, which would move the whole bucket",
+            ));
+        }
+        if source_bucket == dest_bucket && prefixes_overlap(&source_prefix, &dest_prefix) {
+            return Err(Error::invalid_input(
+                "rename_prefix",
+                format!("the prefixes '{source_prefix}' and '{dest_prefix}' overlap"),
+            ));
+        }
+        let objects =
+            collect_items(self.list_objects_stream(source_bucket, Some(&source_prefix))).await?;
+        let copies = objects
+            .into_iter()
+            .filter_map(|object| object.key)
+            .map(|key| {
+                let dest_key = format!(
+                    "{dest_prefix}{}",
+                    key.strip_prefix(source_prefix.as_str()).unwrap_or(&key)
+                );
+                async move {
+                    let copied = self
+                        .copy_object(source_bucket, &key, dest_bucket, &dest_key, options)
+                        .await;
+                    (key, copied)
+                }
+            });
+        let copied = futures_util::StreamExt::collect::<Vec<_>>(
+            futures_util::StreamExt::buffer_unordered(stream::iter(copies), concurrency.max(1)),
+        )
+        .await;
+        let mut rename_report = RenameReport::default();
+        let mut sources = Vec::new();
+        for (key, copied) in copied {
+            match copied {
+                Ok(()) => sources.push(ObjectIdentifier::builder().key(key).build()),
+                Err(err) => rename_report.failed.push((key, err)),
+            }
+        }
+        let deleted = self.delete_identifiers(source_bucket, sources).await?;
+        rename_report.renamed = deleted.deleted;
+        rename_report
+            .failed
+            .extend(deleted.failed.into_iter().map(|failure| {
+                let message = format!("{}: {}", failure.code, failure.message);
+                (failure.key, Error::other("delete_objects", message))
+            }));
+        report!(
+            self.sink,
+            "{} objects were moved from s3://{source_bucket}/{source_prefix} to s3://{dest_bucket}/{dest_prefix}, {} could not be\n",
+            rename_report.renamed.len().to_string().green().bold(),
+            rename_report.failed.len().to_string().red().bold()
+        );
+        Ok(rename_report)
+    }
+    /// Copies a large object part by part. `UploadPartCopy` carries only the data, so the
+    /// metadata and tags `CopyObject` would keep are taken from the source's head and tags
+    /// and set on the new upload. A failed copy is always aborted
+    #[allow(clippy::too_many_arguments)]
+    async fn multipart_copy(
+        &self,
+        copy_source: &str,
+        head: &HeadObjectOutput,
+        tagging: Option<String>,
+        dest_bucket: &str,
+        dest_key: &str,
+        options: &CopyOptions,
+        progress: &ProgressTracker,
+    ) -> Result<(), Error> {
+        let size = head.content_length().max(0) as u64;
+        let request = self
+            .client
+            .create_multipart_upload()
+            .bucket(dest_bucket)
+            .key(dest_key)
+            .set_tagging(tagging)
+            .set_acl(options.acl.clone())
+            .set_storage_class(options.storage_class.clone());
+        let request = match &options.metadata {
+            Some(metadata) => request
+                .set_content_type(metadata.content_type.clone())
+                .set_metadata(metadata.user_metadata())
+                .set_cache_control(metadata.cache_control.clone())
+                .set_content_disposition(metadata.content_disposition.clone()),
+            None => request
+                .set_content_type(head.content_type().map(|value| value.to_string()))
+                .set_metadata(head.metadata().cloned())
+                .set_cache_control(head.cache_control().map(|value| value.to_string()))
+                .set_content_disposition(head.content_disposition().map(|value| value.to_string()))
+                .set_content_encoding(head.content_encoding().map(|value| value.to_string()))
+                .set_content_language(head.content_language().map(|value| value.to_string())),
         };
-        if print_info {
-            let content_type = get_body_data.content_type().unwrap().green().bold();
-            println!("Content type of response body: {}", content_type);
-
-            let content_length = get_body_data.content_length() as f64 * 0.000001;
-            let content_length_colored = content_length.to_string().green().bold();
-            println!("The content length/size of data in MB: {content_length_colored:.3}mb");
-            let last_modified = get_body_data
-                .last_modified
-                .map(|format| {
-                    format
-                        .fmt(aws_sdk_memorydb::primitives::DateTimeFormat::HttpDate)
-                        .ok()
-                })
-                .flatten();
-            if let Some(time) = last_modified {
-                println!("Last Modified: {}\n", time.green().bold());
-            }
-        }
-        let mut file = match path_prefix {
-            Some(prefix) => {
-                let file_path = format!("{prefix}{file_name}");
-                OpenOptions::new()
-                    .create(true)
-                    .read(true)
-                    .write(true)
-                    .open(file_path)
-                    .expect(
-                        "Error while creating file In Downloading Content from Bucket Function\n",
+        let upload = with_encryption!(request, options.encryption.as_ref())
+            .send()
+            .await
+            .map_sdk_err(Service::S3, "create_multipart_upload")?;
+        let upload_id = upload.upload_id().ok_or(Error::missing(
+            Service::S3,
+            "create_multipart_upload",
+            "upload id",
+        ))?;
+        let part_size = COPY_PART_SIZE.max((size + MAX_PARTS - 1) / MAX_PARTS);
+        let part_count = ((size + part_size - 1) / part_size) as i32;
+        //If-Match makes every part fail if the source is replaced while it's being copied
+        let e_tag = head.e_tag().unwrap_or_default();
+        let pending = (1..=part_count).map(|part_number| async move {
+            let range = part_range(part_number, part_size, size);
+            let part = self
+                .copy_part(
+                    copy_source,
+                    e_tag,
+                    dest_bucket,
+                    dest_key,
+                    upload_id,
+                    part_number,
+                    range,
+                    options.customer_key(),
+                )
+                .await?;
+            progress.advance(range.1);
+            Ok(part)
+        });
+        let copied = futures_util::StreamExt::buffer_unordered(
+            stream::iter(pending),
+            self.multipart.concurrency,
+        )
+        .try_collect::<Vec<_>>()
+        .await;
+        let completed = match copied {
+            Ok(mut parts) => {
+                parts.sort_by_key(|part| part.part_number());
+                let request = self
+                    .client
+                    .complete_multipart_upload()
+                    .bucket(dest_bucket)
+                    .key(dest_key)
+                    .multipart_upload(
+                        CompletedMultipartUpload::builder()
+                            .set_parts(Some(parts))
+                            .build(),
                     )
+                    .upload_id(upload_id);
+                with_customer_key!(request, options.customer_key())
+                    .send()
+                    .await
+                    .map_sdk_err(Service::S3, "complete_multipart_upload")
             }
-            None => File::create(file_name).expect("Error while creating file\n"),
+            Err(err) => Err(err),
         };
-        let bytes = get_body_data.body.collect().await.unwrap();
-        let bytes = bytes.into_bytes();
-        match file.write_all(&*bytes) {
-            Ok(_) => {
-                if print_info {
-                    println!("{}\n", "Writing data...".bright_green().bold());
-                    let colored_key_name = object_name.green().bold();
-                    println!(
-                        "The content of the {colored_key_name} is saved in the current directory\n"
-                    );
-                }
+        if let Err(err) = completed {
+            if let Err(abort_err) = self
+                .client
+                .abort_multipart_upload()
+                .bucket(dest_bucket)
+                .key(dest_key)
+                .upload_id(upload_id)
+                .send()
+                .await
+                .map_sdk_err(Service::S3, "abort_multipart_upload")
+            {
+                report!(
+                    self.sink,
+                    "{}: {abort_err}",
+                    format!("The multipart copy '{upload_id}' could not be aborted")
+                        .red()
+                        .bold()
+                );
             }
-
-            Err(_) => {
-                if print_info {
-                    println!("{}\n", "Error while writing\n".red().bold());
+            return Err(err);
+        }
+        Ok(())
+    }
+    #[allow(clippy::too_many_arguments)]
+    async fn copy_part(
+        &self,
+        copy_source: &str,
+        e_tag: &str,
+        dest_bucket: &str,
+        dest_key: &str,
+        upload_id: &str,
+        part_number: i32,
+        (offset, length): (u64, u64),
+        customer_key: Option<&CustomerKey>,
+    ) -> Result<CompletedPart, Error> {
+        let mut attempt = 1;
+        loop {
+            let request = self
+                .client
+                .upload_part_copy()
+                .bucket(dest_bucket)
+                .key(dest_key)
+                .upload_id(upload_id)
+                .part_number(part_number)
+                .copy_source(copy_source)
+                .copy_source_if_match(e_tag)
+                .copy_source_range(format!("bytes={}-{}", offset, offset + length - 1));
+            let request = with_copy_source_key!(request, self.customer_key.as_ref());
+            let result = with_customer_key!(request, customer_key)
+                .send()
+                .await
+                .map_sdk_err(Service::S3, "upload_part_copy");
+            match result {
+                Ok(output) => {
+                    return Ok(CompletedPart::builder()
+                        .e_tag(
+                            output
+                                .copy_part_result()
+                                .and_then(|result| result.e_tag())
+                                .unwrap_or_default(),
+                        )
+                        .part_number(part_number)
+                        .build())
                 }
+                Err(err) if err.is_retryable() && attempt < self.multipart.max_attempts => {
+                    report!(
+                        self.sink,
+                        "Part {} of the copy failed on attempt {}, retrying: {err}",
+                        part_number.to_string().yellow().bold(),
+                        attempt
+                    );
+                    self.backoff(attempt).await;
+                    attempt += 1;
+                }
+                Err(err) => return Err(err),
             }
         }
     }
-    pub async fn download_transcription_results(&self, bucket_name: &str) {
-        let client = S3Client::new(self.config);
+    /// Gives the copy the source's ACL grants, which S3 never copies by itself
+    async fn copy_acl(
+        &self,
+        source_bucket: &str,
+        source_key: &str,
+        dest_bucket: &str,
+        dest_key: &str,
+    ) -> Result<(), Error> {
+        let acl = self
+            .client
+            .get_object_acl()
+            .bucket(source_bucket)
+            .key(source_key)
+            .send()
+            .await
+            .map_sdk_err(Service::S3, "get_object_acl")?;
+        let policy = AccessControlPolicy::builder()
+            .set_grants(acl.grants().map(|grants| grants.to_vec()))
+            .set_owner(acl.owner().cloned())
+            .build();
+        match self
+            .client
+            .put_object_acl()
+            .bucket(dest_bucket)
+            .key(dest_key)
+            .access_control_policy(policy)
+            .send()
+            .await
+            .map_sdk_err(Service::S3, "put_object_acl")
+        {
+            //With ACLs disabled on the destination, the bucket owner owns everything and
+            //there are no grants to keep
+            Err(err) if err.code() == Some("AccessControlListNotSupported") => Ok(()),
+            result => result.map(|_| ()),
+        }
+    }
+    pub async fn download_transcription_results(&self, bucket_name: &str) -> Result<(), Error> {
         let keys = self
             .list_objects_given_prefix(bucket_name, "transcribe_outputs/")
-            .await;
-        create_dir("TranscribeOutputs/").expect("Error while creating TranscribeOutputs/");
+            .await?;
+        let mapper = KeyPathMapper::new("TranscribeOutputs")
+            .strip_prefix("transcribe_outputs/")
+            .on_conflict(self.conflict_policy);
         for key in keys.into_iter() {
-            let get_object = client
-                .get_object()
-                .key(&key)
-                .bucket(bucket_name)
+            let Some(file_name) = mapper.local_path(&key) else {
+                continue;
+            };
+            let file_name = file_name.to_string_lossy().into_owned();
+            if let Some(path) = self.sink.artifact_path(&file_name) {
+                let Some(path) = mapper.resolve(path) else {
+                    report!(
+                        self.sink,
+                        "Skipping '{}', which already exists",
+                        file_name.yellow().bold()
+                    );
+                    continue;
+                };
+                self.download_to_file(bucket_name, &key, &path).await?;
+                report!(
+                    self.sink,
+                    "The file named '{}' has been saved to '{}'",
+                    file_name.green().bold(),
+                    path.display()
+                );
+                continue;
+            }
+            let request = self.client.get_object().key(&key).bucket(bucket_name);
+            let get_object = with_customer_key!(request, self.customer_key.as_ref())
                 .send()
                 .await
-                .expect("Error while getting objects\n");
-            let bytes = get_object.body.collect().await.unwrap();
+                .map_sdk_err(Service::S3, "get_object")?;
+            let bytes = get_object
+                .body
+                .collect()
+                .await
+                .map_err(|err| Error::io("download_transcription_results", &key, err.into()))?;
             let bytes = bytes.into_bytes();
-
-            let have_slash_and_dot_pattern =
-                Regex::new(r#"([^./]+)\.([^/]+)"#).expect("Error while parsing Regex Syntax\n");
-            let have_slash_but_no_extension_pattern =
-                Regex::new(r#"/([^/]+)$"#).expect("Error while parsing regex syntax");
-            let file_name: Vec<String> = if have_slash_and_dot_pattern.is_match(&key) {
-                have_slash_and_dot_pattern
-                    .find_iter(&key)
-                    .map(|string| string.as_str().to_string())
-                    .collect()
-            } else {
-                have_slash_but_no_extension_pattern
-                    .find_iter(&key)
-                    .map(|string| string.as_str().to_string())
-                    .collect()
-            };
-            let mut file_name = file_name.join("");
-            if file_name.starts_with("/") {
-                file_name.remove(0);
-            };
-            let file_name = format!("TranscribeOutputs/{}", file_name);
-            let mut file = OpenOptions::new()
-                .create(true)
-                .read(true)
-                .write(true)
-                .open(&file_name)
-                .expect("Error while creating file\n");
-            match file.write_all(&*bytes) {
-                Ok(_) => println!(
-                    "The file named '{}' has been saved to the '{}{}' directory in the current path",
-                    file_name.green().bold(),"TranscribeOutputs/".green().bold(),file_name.green().bold()
+            match self.sink.write_artifact(&file_name, &bytes) {
+                Ok(location) => report!(
+                    self.sink,
+                    "The file named '{}' has been saved to the '{}' directory in {}",
+                    file_name.green().bold(),
+                    "TranscribeOutputs/".green().bold(),
+                    location
                 ),
-                Err(_) => println!("{}\n","Error while writing file".red().bold()),
+                Err(err) => {
+                    report!(self.sink, "{}\n", "Error while writing file".red().bold());
+                    return Err(Error::io("download_transcription_results", file_name, err));
+                }
             }
         }
+        Ok(())
     }
 
+    /// Presigns a download URL valid for `end_time` hours and writes it to `uri.txt` along
+    /// with a QR code in `uri_qr.png`. Use [`presign_get`](Self::presign_get) for expiries
+    /// in seconds or to choose the artifacts
     pub async fn get_presigned_url_for_an_object(
         &self,
         bucket_name: &str,
         object_name: &str,
         end_time: u64,
-    ) {
-        use chrono::prelude::*;
-        use fast_qr::convert::{image::ImageBuilder, Builder, Shape};
-        use fast_qr::qr::QRBuilder;
-
-        let client = S3Client::new(self.config);
-
-        let start_time = SystemTime::now();
-        let utc: DateTime<Utc> = Utc::now();
-
-        //converting to seconds from hour given by end_time
-        let hour_to_secs = end_time * 60 * 60;
-        let expire_time = Duration::from_secs(hour_to_secs);
+    ) -> Result<PresignedUrl, Error> {
+        let options = PresignOptions::new(Duration::from_secs(end_time * 60 * 60))
+            .text_artifact("uri.txt")
+            .qr_artifact("uri_qr.png");
+        let presigned = self.presign_get(bucket_name, object_name, &options).await?;
 
-        let presigning_config = PresigningConfig::builder()
-            .start_time(start_time)
-            .expires_in(expire_time)
-            .build()
-            .unwrap();
-
-        let expired_in = presigning_config.expires();
-
-        let get_hour = (60 * 60 * end_time) / expired_in.as_secs();
-
-        let colored_msg = "Error from get_presigned_url_for_an_object\n".red().bold();
-        let presigned_req = client
-            .get_object()
-            .bucket(bucket_name)
-            .key(object_name)
-            .presigned(presigning_config)
-            .await;
-
-        let presigned_info = presigned_req.expect(&colored_msg);
-
-        let method_of_content = presigned_info.method().as_str();
-        let colored_method = method_of_content.green().bold();
-        println!("http method of the content: {}\n", colored_method);
+        let colored_method = presigned.method().green().bold();
+        report!(
+            self.sink,
+            "http method of the content: {}\n",
+            colored_method
+        );
 
-        let content_url = presigned_info.uri().to_string();
-        let colored_uri = content_url.blue().bold();
+        let colored_uri = presigned.url().blue().bold();
         let colored_end_time = end_time.to_string().green().bold();
-        println!(
+        report!(
+            self.sink,
             "The URI for the content is: {}\n and the expiration time is: {} hour from now\n",
-            colored_uri, colored_end_time
+            colored_uri,
+            colored_end_time
         );
-        println!("{}\n","Press and hold Ctrl while clicking the link to open it, and it will automatically begin downloading".green().bold());
-        println!(
+        report!(self.sink, "{}\n","Press and hold Ctrl while clicking the link to open it, and it will automatically begin downloading".green().bold());
+        report!(
+            self.sink,
             "{}\n",
             "Visit https://tinyurl.com/app to shorten your URL"
                 .blue()
                 .bold()
         );
+        Ok(presigned)
+    }
+    /// A URL anyone can download the object from until it expires
+    pub async fn presign_get(
+        &self,
+        bucket_name: &str,
+        object_name: &str,
+        options: &PresignOptions,
+    ) -> Result<PresignedUrl, Error> {
+        let config = options.presigning_config("presign_get")?;
+        let expires_at = config.start_time() + config.expires();
+        let request = self
+            .client
+            .get_object()
+            .bucket(bucket_name)
+            .key(object_name);
+        let customer_key = options
+            .encryption
+            .as_ref()
+            .and_then(ServerSideEncryption::customer_key);
+        let request = with_customer_key!(request, customer_key)
+            .presigned(config)
+            .await
+            .map_sdk_err(Service::S3, "get_object")?;
+        let presigned = PresignedUrl::new(&request, expires_at);
+        self.write_presign_artifacts(
+            "presign_get",
+            presigned.url(),
+            &presigned.headers,
+            expires_at,
+            options,
+        )?;
+        Ok(presigned)
+    }
+    /// A URL anyone can upload the object to with an HTTP PUT until it expires, sending the
+    /// returned headers along
+    pub async fn presign_put(
+        &self,
+        bucket_name: &str,
+        object_name: &str,
+        options: &PresignOptions,
+    ) -> Result<PresignedUrl, Error> {
+        if options.content_length_range.is_some() {
+            return Err(Error::invalid_input(
+                "presign_put",
+                "a PUT URL can't limit the upload size; use presign_post for a content-length-range",
+            ));
+        }
+        let config = options.presigning_config("presign_put")?;
+        let expires_at = config.start_time() + config.expires();
+        let request = self
+            .client
+            .put_object()
+            .bucket(bucket_name)
+            .key(object_name)
+            .set_content_type(options.content_type.clone());
+        let request = with_encryption!(request, options.encryption.as_ref())
+            .presigned(config)
+            .await
+            .map_sdk_err(Service::S3, "put_object")?;
+        let presigned = PresignedUrl::new(&request, expires_at);
+        self.write_presign_artifacts(
+            "presign_put",
+            presigned.url(),
+            &presigned.headers,
+            expires_at,
+            options,
+        )?;
+        Ok(presigned)
+    }
+    /// A browser-based upload form signed with a POST policy. A key ending with `/` is used
+    /// as a prefix, and the uploaded file's own name is appended to it through S3's
+    /// `${filename}` variable. The policy enforces the content type and size range of the
+    /// options
+    pub async fn presign_post(
+        &self,
+        bucket_name: &str,
+        object_name: &str,
+        options: &PresignOptions,
+    ) -> Result<PresignedPost, Error> {
+        let operation = "presign_post";
+        let config = options.presigning_config(operation)?;
+        let signed_at = config.start_time();
+        let expires_at = signed_at + config.expires();
+        let credentials = self
+            .config
+            .credentials_provider()
+            .ok_or_else(|| {
+                Error::invalid_input(operation, "no credentials provider is configured")
+            })?
+            .provide_credentials()
+            .await
+            .map_err(|err| Error::other(operation, err))?;
+        let region = self
+            .config
+            .region()
+            .map(|region| region.to_string())
+            .ok_or_else(|| Error::invalid_input(operation, "no region is configured"))?;
+        //The form goes to the bucket's URL. Presigning a placeholder key and cutting it off
+        //gets it right for custom endpoints and path-style addressing too
+        let bucket_request = self
+            .client
+            .put_object()
+            .bucket(bucket_name)
+            .key("k")
+            .presigned(config)
+            .await
+            .map_sdk_err(Service::S3, "put_object")?;
+        let uri = bucket_request.uri();
+        let url = format!(
+            "{}://{}{}",
+            uri.scheme_str().unwrap_or("https"),
+            uri.authority()
+                .map(|authority| authority.as_str())
+                .unwrap_or_default(),
+            uri.path().strip_suffix('k').unwrap_or("/")
+        );
 
-        // Generating text file
-        let mut file = File::create("./uri.txt").unwrap();
-        let year = utc.year();
-        let month = utc.month();
-        let week_day = utc.weekday();
-        let day = utc.day();
-        let minute = utc.minute();
-        let hour = utc.hour();
-        let secs = utc.second();
-        let format_string_to_write_into = 
-        format!("The URL for the content is: {content_url}\n\n\nStarted at\nyear: {year}\nmonth: {month}\nweek_day: {week_day}\nday: {day}\nminutes: {minute}\nhours: {hour}\nseconds: {secs}\n\nExpired at: {get_hour} h");
-        file.write_all(format_string_to_write_into.as_bytes())
-            .unwrap();
-        println!(
-            "{}\n",
-            r#"The content has been written to "uri.txt" in the current directory."#
-                .green()
-                .bold()
+        let signed_at_utc = DateTime::<Utc>::from(signed_at);
+        let amz_date = signed_at_utc.format("%Y%m%dT%H%M%SZ").to_string();
+        let credential = format!(
+            "{}/{}/{region}/s3/aws4_request",
+            credentials.access_key_id(),
+            signed_at_utc.format("%Y%m%d")
         );
+        let mut fields = Vec::new();
+        let mut conditions = vec![serde_json::json!({ "bucket": bucket_name })];
+        if object_name.ends_with('/') {
+            fields.push(("key".to_string(), format!("{object_name}${{filename}}")));
+            conditions.push(serde_json::json!(["starts-with", "$key", object_name]));
+        } else {
+            fields.push(("key".to_string(), object_name.to_string()));
+            conditions.push(serde_json::json!({ "key": object_name }));
+        }
+        if let Some(content_type) = &options.content_type {
+            fields.push(("Content-Type".to_string(), content_type.clone()));
+            conditions.push(serde_json::json!({ "Content-Type": content_type }));
+        }
+        if let Some((min, max)) = options.content_length_range {
+            conditions.push(serde_json::json!(["content-length-range", min, max]));
+        }
+        let mut signing_fields = vec![
+            ("x-amz-algorithm", "AWS4-HMAC-SHA256".to_string()),
+            ("x-amz-credential", credential),
+            ("x-amz-date", amz_date),
+        ];
+        if let Some(token) = credentials.session_token() {
+            signing_fields.push(("x-amz-security-token", token.to_string()));
+        }
+        for (name, value) in signing_fields {
+            conditions.push(serde_json::json!({ name: value }));
+            fields.push((name.to_string(), value));
+        }
+        let policy = serde_json::json!({
+            "expiration": DateTime::<Utc>::from(expires_at).format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string(),
+            "conditions": conditions,
+        });
+        let policy = base64::encode(policy.to_string());
+        let signing_key =
+            generate_signing_key(credentials.secret_access_key(), signed_at, &region, "s3");
+        let signature = calculate_signature(signing_key, policy.as_bytes());
+        fields.push(("policy".to_string(), policy));
+        fields.push(("x-amz-signature".to_string(), signature));
+
+        self.write_presign_artifacts(operation, &url, &fields, expires_at, options)?;
+        Ok(PresignedPost {
+            url,
+            fields,
+            expires_at,
+        })
+    }
+    fn write_presign_artifacts(
+        &self,
+        operation: &'static str,
+        url: &str,
+        fields: &[(String, String)],
+        expires_at: SystemTime,
+        options: &PresignOptions,
+    ) -> Result<(), Error> {
+        use fast_qr::convert::{image::ImageBuilder, Builder, Shape};
+        use fast_qr::qr::QRBuilder;
 
-        //generating qr image for the uri
-        let qrcode = QRBuilder::new(content_url.as_str()).build().unwrap();
-        ImageBuilder::default()
-            .shape(Shape::Square)
-            .background_color([255, 255, 255, 0])
-            .fit_width(600)
-            .fit_height(600)
-            .to_file(&qrcode, "./uri_qr.png")
-            .unwrap();
+        if let Some(name) = &options.text_artifact {
+            let mut contents = format!("The URL for the content is: {url}\n\n");
+            for (field, value) in fields {
+                //The SSE-C key is a secret and doesn't belong in a file next to the URL
+                let value =
+                    if field.eq_ignore_ascii_case("x-amz-server-side-encryption-customer-key") {
+                        "<your SSE-C key, base64-encoded>"
+                    } else {
+                        value
+                    };
+                contents.push_str(&format!("{field}: {value}\n"));
+            }
+            contents.push_str(&format!(
+                "\nExpires at: {}\n",
+                DateTime::<Utc>::from(expires_at).to_rfc3339()
+            ));
+            let location = self
+                .sink
+                .write_artifact(name, contents.as_bytes())
+                .map_err(|err| Error::io(operation, name, err))?;
+            let msg = format!(r#"The content has been written to "{name}" in {location}."#);
+            report!(self.sink, "{}\n", msg.green().bold());
+        }
 
-        println!("{}\n","A QR code has been generated for the content's URL and is saved in the current directory as 'uri_qr.png'".green().bold());
+        if let Some(name) = &options.qr_artifact {
+            let qrcode = QRBuilder::new(url)
+                .build()
+                .map_err(|err| Error::other(operation, format!("{err:?}")))?;
+            let qr_image = ImageBuilder::default()
+                .shape(Shape::Square)
+                .background_color([255, 255, 255, 0])
+                .fit_width(600)
+                .fit_height(600)
+                .to_bytes(&qrcode)
+                .map_err(|err| Error::other(operation, format!("{err:?}")))?;
+            let location = self
+                .sink
+                .write_artifact(name, &qr_image)
+                .map_err(|err| Error::io(operation, name, err))?;
+            let msg = format!("A QR code has been generated for the content's URL and is saved in {location} as '{name}'");
+            report!(self.sink, "{}\n", msg.green().bold());
+        }
+        Ok(())
     }
 
     /// Delete the content or key in the provided bucket. Please be cautious, as
     /// this action will permanently remove the content from the service
-    pub async fn delete_content_in_a_bucket(&self, bucket_name: &str, object_name: &str) {
-        let client = S3Client::new(self.config);
-        let colored_msg = "Error from delete_content_in_a_bucket function\n"
-            .red()
-            .bold();
-        client
+    pub async fn delete_content_in_a_bucket(
+        &self,
+        bucket_name: &str,
+        object_name: &str,
+    ) -> Result<(), Error> {
+        self.client
             .delete_object()
             .bucket(bucket_name)
             .key(object_name)
             .send()
             .await
-            .map(|_| {
-           let colored_key_name = object_name.red().bold();
-           let colored_bucket_name = bucket_name.red().bold();
-            println!( "The object {colored_key_name} in bucket {colored_bucket_name} has been deleted");
-            })
-            .expect(&colored_msg);
+            .map_sdk_err(Service::S3, "delete_object")?;
+        let colored_key_name = object_name.red().bold();
+        let colored_bucket_name = bucket_name.red().bold();
+        report!(
+            self.sink,
+            "The object {colored_key_name} in bucket {colored_bucket_name} has been deleted"
+        );
 
-        let current_objects = self.retrieve_keys_in_a_bucket(bucket_name).await;
-        println!("Currently available keys/objects in your {bucket_name} bucket\n");
+        let current_objects = self.retrieve_keys_in_a_bucket(bucket_name).await?;
+        report!(
+            self.sink,
+            "Currently available keys/objects in your {bucket_name} bucket\n"
+        );
         current_objects.into_iter().for_each(|key| {
             let key = key.green().bold();
-            println!("{key}\n");
+            report!(self.sink, "{key}\n");
         });
+        Ok(())
+    }
+}
+
+//KMS takes the encryption context as base64-encoded JSON
+fn kms_context(context: &BTreeMap<String, String>) -> Option<String> {
+    (!context.is_empty()).then(|| base64::encode(serde_json::json!(context).to_string()))
+}
+
+fn describe_encryption(encryption: &ServerSideEncryption) -> String {
+    match encryption {
+        ServerSideEncryption::S3 => "SSE-S3".to_string(),
+        ServerSideEncryption::Kms {
+            key_id: Some(key_id),
+            ..
+        } => format!("SSE-KMS with the key {key_id}"),
+        ServerSideEncryption::Kms { key_id: None, .. } => "SSE-KMS with the aws/s3 key".to_string(),
+        ServerSideEncryption::Customer(_) => "SSE-C".to_string(),
+    }
+}
+
+//Tags as the URL query string the x-amz-tagging header takes
+fn tagging_header(tags: &[(String, String)]) -> Option<String> {
+    let encode = |text: &str| utf8_percent_encode(text, TAG_ENCODE_SET).to_string();
+    (!tags.is_empty()).then(|| {
+        tags.iter()
+            .map(|(key, value)| format!("{}={}", encode(key), encode(value)))
+            .collect::<Vec<_>>()
+            .join("&")
+    })
+}
+
+fn file_size(path: &str, operation: &'static str) -> Result<u64, Error> {
+    std::fs::metadata(path)
+        .map(|metadata| metadata.len())
+        .map_err(|err| Error::io(operation, path, err))
+}
+
+/// The offset and length of a part, counting part numbers from 1 as S3 does.
+fn part_range(part_number: i32, part_size: u64, file_size: u64) -> (u64, u64) {
+    let offset = (part_number as u64 - 1) * part_size;
+    (offset, part_size.min(file_size.saturating_sub(offset)))
+}
+
+fn read_part(path: &str, offset: u64, length: u64) -> Result<Vec<u8>, Error> {
+    let mut file = File::open(path).map_err(|err| Error::io("upload_part", path, err))?;
+    let mut buffer = vec![0; length as usize];
+    file.seek(SeekFrom::Start(offset))
+        .and_then(|_| file.read_exact(&mut buffer))
+        .map_err(|err| Error::io("upload_part", path, err))?;
+    Ok(buffer)
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// The parts of an earlier download that are already on disk. The state file starts with
+/// the ETag the download began with; parts of another version of the object don't count.
+fn read_download_state(state: &Path, e_tag: &str) -> HashSet<u64> {
+    let Ok(file) = File::open(state) else {
+        return HashSet::new();
+    };
+    let mut lines = BufReader::new(file).lines().map_while(Result::ok);
+    match lines.next() {
+        Some(first) if first == e_tag => lines.filter_map(|line| line.parse().ok()).collect(),
+        _ => HashSet::new(),
+    }
+}
+
+/// The MD5 digest of a file in hex, or when a part size is given, the digest S3 computes
+/// for a multipart upload: the MD5 of the parts' digests followed by the part count.
+fn md5_of_file(path: &Path, part_size: Option<u64>) -> std::io::Result<String> {
+    let mut file = BufReader::new(File::open(path)?);
+    let mut buffer = vec![0; 1024 * 1024];
+    let mut part_digests = Vec::new();
+    let mut hasher = Md5::new();
+    let mut in_part = 0;
+    loop {
+        let wanted = match part_size {
+            Some(part_size) => buffer.len().min((part_size - in_part) as usize),
+            None => buffer.len(),
+        };
+        let read = file.read(&mut buffer[..wanted])?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        in_part += read as u64;
+        if Some(in_part) == part_size {
+            part_digests.extend_from_slice(&hasher.finalize_reset());
+            in_part = 0;
+        }
+    }
+    match part_size {
+        None => Ok(hex::encode(hasher.finalize())),
+        Some(_) => {
+            if in_part > 0 {
+                part_digests.extend_from_slice(&hasher.finalize_reset());
+            }
+            let parts = part_digests.len() / 16;
+            Ok(format!(
+                "{}-{parts}",
+                hex::encode(Md5::digest(&part_digests))
+            ))
+        }
+    }
+}
+
+/// A prefix that keys can be appended to: empty, or ending with `/`.
+fn key_prefix(prefix: &str) -> String {
+    match prefix.trim_start_matches('/') {
+        "" => String::new(),
+        prefix if prefix.ends_with('/') => prefix.to_string(),
+        prefix => format!("{prefix}/"),
+    }
+}
+
+/// Whether one prefix contains the other, so moving objects between them in one bucket would
+/// write some keys that are also being moved.
+fn prefixes_overlap(source_prefix: &str, dest_prefix: &str) -> bool {
+    source_prefix.starts_with(dest_prefix) || dest_prefix.starts_with(source_prefix)
+}
+
+struct LocalFile {
+    path: PathBuf,
+    size: u64,
+    modified: SystemTime,
+}
+
+impl LocalFile {
+    //Every name under the directory is UTF-8, but the directory's own path may not be
+    fn path_str(&self, operation: &'static str) -> Result<&str, Error> {
+        self.path.to_str().ok_or_else(|| {
+            Error::invalid_input(
+                operation,
+                format!("'{}' isn't valid UTF-8", self.path.display()),
+            )
+        })
+    }
+}
+
+/// The files under a directory keyed by their path relative to it, joined with `/`.
+fn local_files(dir: &Path, sink: &dyn OutputSink) -> Result<BTreeMap<String, LocalFile>, Error> {
+    let mut files = BTreeMap::new();
+    let mut pending = vec![(dir.to_path_buf(), String::new())];
+    while let Some((dir, relative_dir)) = pending.pop() {
+        let entries =
+            fs::read_dir(&dir).map_err(|err| Error::io("sync", dir.display().to_string(), err))?;
+        for entry in entries {
+            let entry = entry.map_err(|err| Error::io("sync", dir.display().to_string(), err))?;
+            let path = entry.path();
+            let Some(name) = entry.file_name().to_str().map(|name| name.to_string()) else {
+                report!(
+                    sink,
+                    "Skipping '{}', whose name isn't valid UTF-8",
+                    path.display()
+                );
+                continue;
+            };
+            let metadata = fs::metadata(&path)
+                .map_err(|err| Error::io("sync", path.display().to_string(), err))?;
+            let relative = format!("{relative_dir}{name}");
+            if metadata.is_dir() {
+                pending.push((path, format!("{relative}/")));
+            } else if metadata.is_file() {
+                let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                files.insert(
+                    relative,
+                    LocalFile {
+                        path,
+                        size: metadata.len(),
+                        modified,
+                    },
+                );
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// Whether a file and an object differ, checked the way `aws s3 sync` does: by size, then
+/// by whether the source side is newer. A newer source of the same size is compared by its
+/// MD5 digest when the ETag is one, so touching a file doesn't send it again.
+fn needs_transfer(file: &LocalFile, object: &Object, upload: bool) -> Result<bool, Error> {
+    if object.size().max(0) as u64 != file.size {
+        return Ok(true);
+    }
+    let source_is_newer = match object
+        .last_modified()
+        .and_then(|last_modified| SystemTime::try_from(*last_modified).ok())
+    {
+        Some(remote) if upload => file.modified > remote,
+        Some(remote) => remote > file.modified,
+        None => true,
+    };
+    if !source_is_newer {
+        return Ok(false);
+    }
+    let e_tag = object.e_tag().unwrap_or_default().trim_matches('"');
+    if e_tag.len() != 32 || e_tag.contains('-') {
+        return Ok(true);
+    }
+    let digest = md5_of_file(&file.path, None)
+        .map_err(|err| Error::io("sync", file.path.display().to_string(), err))?;
+    Ok(digest != e_tag)
+}
+
+/// Matches a path against a pattern where `*` stands for any run of characters and `?` for
+/// exactly one.
+pub(crate) fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let text = text.chars().collect::<Vec<_>>();
+    let (mut p, mut t) = (0, 0);
+    //Where the last `*` was seen and how much text it has swallowed so far
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&ch) if ch == '?' || ch == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((star_p, star_t)) => {
+                    p = star_p + 1;
+                    t = star_t + 1;
+                    star = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&ch| ch == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use aws_sdk_s3::primitives::DateTime as SdkDateTime;
+
+    use super::*;
+
+    #[test]
+    fn glob_matches_stars_and_question_marks() {
+        assert!(glob_match("*.log", "app.log"));
+        assert!(glob_match("*.log", "logs/app.log"));
+        assert!(!glob_match("*.log", "app.log.gz"));
+        assert!(glob_match("a?c", "abc"));
+        assert!(!glob_match("a?c", "ac"));
+        assert!(glob_match("a*b*c", "axxbyybc"));
+        assert!(!glob_match("a*b*c", "axxbyyb"));
+        assert!(glob_match("assets/*", "assets/"));
+        assert!(glob_match("**", ""));
+        assert!(glob_match("", ""));
+        assert!(!glob_match("", "a"));
+    }
+
+    #[test]
+    fn detects_overlapping_prefixes() {
+        //Moving `a/b/` to its ancestor `a/` would write `a/b/b/x` onto the source `a/b/x`
+        assert!(prefixes_overlap("a/b/", "a/"));
+        assert!(prefixes_overlap("a/", "a/b/"));
+        assert!(prefixes_overlap("a/", "a/"));
+        assert!(prefixes_overlap("a/", ""));
+        assert!(!prefixes_overlap("a/", "ab/"));
+        assert!(!prefixes_overlap("a/b/", "a/c/"));
+    }
+
+    const CONTENT: &[u8] = b"hello world";
+    const CONTENT_MD5: &str = "5eb63bbbe01eeed093cb22bb8f5acdc3";
+
+    fn local_file(name: &str, modified: SystemTime) -> LocalFile {
+        let path = std::env::temp_dir().join(format!("aws_apis-{}-{name}", std::process::id()));
+        fs::write(&path, CONTENT).unwrap();
+        LocalFile {
+            path,
+            size: CONTENT.len() as u64,
+            modified,
+        }
+    }
+
+    fn object(size: i64, last_modified: SystemTime, e_tag: &str) -> Object {
+        Object::builder()
+            .size(size)
+            .last_modified(SdkDateTime::from(last_modified))
+            .e_tag(format!("\"{e_tag}\""))
+            .build()
+    }
+
+    #[test]
+    fn computes_the_etag_of_single_and_multipart_uploads() {
+        let file = local_file("etag", SystemTime::now());
+        assert_eq!(md5_of_file(&file.path, None).unwrap(), CONTENT_MD5);
+        assert_eq!(
+            md5_of_file(&file.path, Some(5)).unwrap(),
+            "df349a9519959b17a605009540f4b31d-3"
+        );
+        fs::remove_file(&file.path).unwrap();
+    }
+
+    #[test]
+    fn transfers_files_of_another_size() {
+        let now = SystemTime::now();
+        let file = local_file("size", now);
+        let other_size = object(CONTENT.len() as i64 + 1, now, CONTENT_MD5);
+        assert!(needs_transfer(&file, &other_size, true).unwrap());
+        assert!(needs_transfer(&file, &other_size, false).unwrap());
+        fs::remove_file(&file.path).unwrap();
+    }
+
+    #[test]
+    fn skips_files_whose_source_is_older() {
+        let now = SystemTime::now();
+        let hour = Duration::from_secs(3600);
+        let file = local_file("older", now);
+        let size = CONTENT.len() as i64;
+        let changed = "0".repeat(32);
+        assert!(!needs_transfer(&file, &object(size, now + hour, &changed), true).unwrap());
+        assert!(!needs_transfer(&file, &object(size, now - hour, &changed), false).unwrap());
+        fs::remove_file(&file.path).unwrap();
+    }
+
+    #[test]
+    fn compares_newer_files_by_md5() {
+        let now = SystemTime::now();
+        let hour = Duration::from_secs(3600);
+        let file = local_file("md5", now);
+        let size = CONTENT.len() as i64;
+        assert!(!needs_transfer(&file, &object(size, now - hour, CONTENT_MD5), true).unwrap());
+        assert!(!needs_transfer(&file, &object(size, now + hour, CONTENT_MD5), false).unwrap());
+        let changed = "0".repeat(32);
+        assert!(needs_transfer(&file, &object(size, now - hour, &changed), true).unwrap());
+        //A multipart ETag isn't an MD5 digest of the content, so it can't rule a change out
+        let multipart = format!("{CONTENT_MD5}-2");
+        assert!(needs_transfer(&file, &object(size, now - hour, &multipart), true).unwrap());
+        fs::remove_file(&file.path).unwrap();
     }
 }
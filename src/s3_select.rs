@@ -0,0 +1,548 @@
+use std::{mem, sync::Arc};
+
+use aws_sdk_s3::{
+    operation::select_object_content::SelectObjectContentOutput,
+    types::{
+        CompressionType, CsvInput, CsvOutput, FileHeaderInfo, InputSerialization, JsonInput,
+        JsonOutput, JsonType, OutputSerialization, ParquetInput, Progress, QuoteFields,
+        SelectObjectContentEventStream, Stats,
+    },
+};
+use colored::Colorize;
+
+use crate::{
+    error::{Error, Service, StreamResultExt},
+    output::{report, OutputSink},
+    progress::format_bytes,
+};
+
+//Rows are split on this, so it isn't configurable
+const RECORD_DELIMITER: u8 = b'\n';
+
+/// What the first line of a CSV object holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CsvHeader {
+    /// There's no header; columns are referred to as `_1`, `_2`, ...
+    None,
+    /// The first line is a header, which is skipped; columns are still referred to by position.
+    Ignore,
+    /// The first line names the columns, so queries can refer to them as `s.name`.
+    Use,
+}
+
+impl CsvHeader {
+    fn to_sdk(self) -> FileHeaderInfo {
+        match self {
+            Self::None => FileHeaderInfo::None,
+            Self::Ignore => FileHeaderInfo::Ignore,
+            Self::Use => FileHeaderInfo::Use,
+        }
+    }
+}
+
+/// How a CSV or JSON object is compressed. Parquet objects compress their columns themselves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SelectCompression {
+    #[default]
+    None,
+    Gzip,
+    Bzip2,
+}
+
+impl SelectCompression {
+    fn to_sdk(self) -> CompressionType {
+        match self {
+            Self::None => CompressionType::None,
+            Self::Gzip => CompressionType::Gzip,
+            Self::Bzip2 => CompressionType::Bzip2,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum InputFormat {
+    Csv {
+        header: CsvHeader,
+        field_delimiter: char,
+        quoted_record_delimiters: bool,
+    },
+    JsonLines,
+    JsonDocument,
+    Parquet,
+}
+
+/// The format of the object [`S3Ops::select_object_content`](crate::S3Ops::select_object_content)
+/// queries.
+#[derive(Debug, Clone)]
+pub struct SelectInput {
+    format: InputFormat,
+    compression: SelectCompression,
+}
+
+impl SelectInput {
+    /// Comma-separated values without a header line.
+    pub fn csv() -> Self {
+        Self::of(InputFormat::Csv {
+            header: CsvHeader::None,
+            field_delimiter: ',',
+            quoted_record_delimiters: false,
+        })
+    }
+    /// One JSON value per line.
+    pub fn json_lines() -> Self {
+        Self::of(InputFormat::JsonLines)
+    }
+    /// A single JSON document, usually queried with a path such as `S3Object[*].items[*]`.
+    pub fn json_document() -> Self {
+        Self::of(InputFormat::JsonDocument)
+    }
+    pub fn parquet() -> Self {
+        Self::of(InputFormat::Parquet)
+    }
+    /// What the first line of a CSV object holds; other formats ignore it.
+    pub fn header(mut self, header: CsvHeader) -> Self {
+        if let InputFormat::Csv {
+            header: current, ..
+        } = &mut self.format
+        {
+            *current = header;
+        }
+        self
+    }
+    /// The character between the fields of a CSV object, such as `\t` or `;`; other formats
+    /// ignore it.
+    pub fn field_delimiter(mut self, delimiter: char) -> Self {
+        if let InputFormat::Csv {
+            field_delimiter, ..
+        } = &mut self.format
+        {
+            *field_delimiter = delimiter;
+        }
+        self
+    }
+    /// Lets quoted CSV fields span several lines, which makes S3 scan the object more slowly.
+    pub fn quoted_record_delimiters(mut self, allow: bool) -> Self {
+        if let InputFormat::Csv {
+            quoted_record_delimiters,
+            ..
+        } = &mut self.format
+        {
+            *quoted_record_delimiters = allow;
+        }
+        self
+    }
+    pub fn compression(mut self, compression: SelectCompression) -> Self {
+        self.compression = compression;
+        self
+    }
+    fn of(format: InputFormat) -> Self {
+        Self {
+            format,
+            compression: SelectCompression::None,
+        }
+    }
+    pub(crate) fn is_parquet(&self) -> bool {
+        matches!(self.format, InputFormat::Parquet)
+    }
+    pub(crate) fn compression_type(&self) -> SelectCompression {
+        self.compression
+    }
+    pub(crate) fn to_sdk(&self) -> InputSerialization {
+        let input = InputSerialization::builder();
+        let input = match &self.format {
+            InputFormat::Csv {
+                header,
+                field_delimiter,
+                quoted_record_delimiters,
+            } => input.csv(
+                CsvInput::builder()
+                    .file_header_info(header.to_sdk())
+                    .field_delimiter(field_delimiter.to_string())
+                    .allow_quoted_record_delimiter(*quoted_record_delimiters)
+                    .build(),
+            ),
+            InputFormat::JsonLines => {
+                input.json(JsonInput::builder().r#type(JsonType::Lines).build())
+            }
+            InputFormat::JsonDocument => {
+                input.json(JsonInput::builder().r#type(JsonType::Document).build())
+            }
+            InputFormat::Parquet => input.parquet(ParquetInput::builder().build()),
+        };
+        match self.format {
+            //Whole-object compression doesn't apply to Parquet, so it's left out
+            InputFormat::Parquet => input.build(),
+            _ => input.compression_type(self.compression.to_sdk()).build(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    Csv,
+    Json,
+}
+
+/// The format S3 sends the selected rows back in, one row per line.
+#[derive(Debug, Clone)]
+pub struct SelectOutput {
+    format: OutputFormat,
+    field_delimiter: char,
+}
+
+impl SelectOutput {
+    /// Comma-separated values, read back as [`SelectRow::Csv`].
+    pub fn csv() -> Self {
+        Self {
+            format: OutputFormat::Csv,
+            field_delimiter: ',',
+        }
+    }
+    /// A JSON object per row, read back as [`SelectRow::Json`].
+    pub fn json() -> Self {
+        Self {
+            format: OutputFormat::Json,
+            field_delimiter: ',',
+        }
+    }
+    /// The character between CSV fields; JSON output ignores it.
+    pub fn field_delimiter(mut self, delimiter: char) -> Self {
+        self.field_delimiter = delimiter;
+        self
+    }
+    pub(crate) fn to_sdk(&self) -> OutputSerialization {
+        let record_delimiter = char::from(RECORD_DELIMITER).to_string();
+        match self.format {
+            OutputFormat::Csv => OutputSerialization::builder()
+                .csv(
+                    CsvOutput::builder()
+                        .quote_fields(QuoteFields::Asneeded)
+                        .field_delimiter(self.field_delimiter.to_string())
+                        .record_delimiter(record_delimiter)
+                        .build(),
+                )
+                .build(),
+            OutputFormat::Json => OutputSerialization::builder()
+                .json(
+                    JsonOutput::builder()
+                        .record_delimiter(record_delimiter)
+                        .build(),
+                )
+                .build(),
+        }
+    }
+}
+
+/// One row of the results.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SelectRow {
+    /// The fields of a CSV row, unquoted.
+    Csv(Vec<String>),
+    Json(serde_json::Value),
+}
+
+/// How much of the object S3 went through for a query. S3 Select is billed by the bytes
+/// scanned and returned.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SelectStats {
+    bytes_scanned: u64,
+    bytes_processed: u64,
+    bytes_returned: u64,
+}
+
+impl SelectStats {
+    /// The bytes read from the object, compressed.
+    pub fn bytes_scanned(&self) -> u64 {
+        self.bytes_scanned
+    }
+    /// The bytes the query ran over, after decompression.
+    pub fn bytes_processed(&self) -> u64 {
+        self.bytes_processed
+    }
+    pub fn bytes_returned(&self) -> u64 {
+        self.bytes_returned
+    }
+    fn from_stats(stats: &Stats) -> Self {
+        Self {
+            bytes_scanned: stats.bytes_scanned().max(0) as u64,
+            bytes_processed: stats.bytes_processed().max(0) as u64,
+            bytes_returned: stats.bytes_returned().max(0) as u64,
+        }
+    }
+    fn from_progress(progress: &Progress) -> Self {
+        Self {
+            bytes_scanned: progress.bytes_scanned().max(0) as u64,
+            bytes_processed: progress.bytes_processed().max(0) as u64,
+            bytes_returned: progress.bytes_returned().max(0) as u64,
+        }
+    }
+}
+
+/// The results of [`S3Ops::select_object_content`](crate::S3Ops::select_object_content),
+/// read as S3 sends them, either as rows with [`next_row`](Self::next_row) or as the raw
+/// bytes of the output format with [`next_chunk`](Self::next_chunk). The two can be mixed;
+/// a chunk starts with what's left of a partly read row.
+#[derive(Debug)]
+pub struct SelectStream {
+    output: SelectObjectContentOutput,
+    rows: RowSplitter,
+    location: String,
+    sink: Arc<dyn OutputSink>,
+    stats: Option<SelectStats>,
+    finished: bool,
+}
+
+impl SelectStream {
+    pub(crate) fn new(
+        output: SelectObjectContentOutput,
+        select_output: &SelectOutput,
+        location: String,
+        sink: Arc<dyn OutputSink>,
+    ) -> Self {
+        Self {
+            output,
+            rows: RowSplitter::new(select_output.format, select_output.field_delimiter),
+            location,
+            sink,
+            stats: None,
+            finished: false,
+        }
+    }
+    /// The next bytes of the results, or `None` once S3 has sent them all.
+    pub async fn next_chunk(&mut self) -> Result<Option<Vec<u8>>, Error> {
+        if !self.rows.pending.is_empty() {
+            return Ok(Some(mem::take(&mut self.rows.pending)));
+        }
+        self.next_records().await
+    }
+    /// The next row of the results, or `None` once S3 has sent them all.
+    pub async fn next_row(&mut self) -> Result<Option<SelectRow>, Error> {
+        loop {
+            if let Some(row) = self.rows.next_row()? {
+                return Ok(Some(row));
+            }
+            match self.next_records().await? {
+                Some(records) => self.rows.pending.extend_from_slice(&records),
+                None => return self.rows.last_row(),
+            }
+        }
+    }
+    /// Reads the remaining rows.
+    pub async fn collect_rows(mut self) -> Result<Vec<SelectRow>, Error> {
+        let mut rows = Vec::new();
+        while let Some(row) = self.next_row().await? {
+            rows.push(row);
+        }
+        Ok(rows)
+    }
+    /// Reads the remaining results as bytes.
+    pub async fn collect_bytes(mut self) -> Result<Vec<u8>, Error> {
+        let mut bytes = Vec::new();
+        while let Some(chunk) = self.next_chunk().await? {
+            bytes.extend_from_slice(&chunk);
+        }
+        Ok(bytes)
+    }
+    /// The bytes scanned, processed and returned so far. S3 sends them now and then while the
+    /// query runs and in full at the end, so they're final once the results are read.
+    pub fn stats(&self) -> Option<SelectStats> {
+        self.stats
+    }
+    /// Whether S3 has sent all the results.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+    async fn next_records(&mut self) -> Result<Option<Vec<u8>>, Error> {
+        while !self.finished {
+            let event = self
+                .output
+                .payload
+                .recv()
+                .await
+                .map_stream_err(Service::S3, "select_object_content")?;
+            match event {
+                Some(SelectObjectContentEventStream::Records(records)) => {
+                    if let Some(payload) = records.payload {
+                        return Ok(Some(payload.into_inner()));
+                    }
+                }
+                Some(SelectObjectContentEventStream::Progress(progress)) => {
+                    if let Some(details) = &progress.details {
+                        self.stats = Some(SelectStats::from_progress(details));
+                    }
+                }
+                Some(SelectObjectContentEventStream::Stats(stats)) => {
+                    if let Some(details) = &stats.details {
+                        self.stats = Some(SelectStats::from_stats(details));
+                    }
+                }
+                Some(SelectObjectContentEventStream::End(_)) => {
+                    self.finished = true;
+                    let stats = self.stats.unwrap_or_default();
+                    report!(
+                        self.sink,
+                        "select: scanned {} of {}, processed {} and returned {}\n",
+                        format_bytes(stats.bytes_scanned as f64).green().bold(),
+                        self.location,
+                        format_bytes(stats.bytes_processed as f64),
+                        format_bytes(stats.bytes_returned as f64).green().bold()
+                    );
+                }
+                //Continuation events only keep the connection open
+                Some(_) => {}
+                None => {
+                    return Err(Error::other(
+                        "select_object_content",
+                        format!(
+                            "the results of {} ended before S3 finished sending them",
+                            self.location
+                        ),
+                    ))
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Cuts the records S3 sends into rows. A row may be split across several `Records`
+/// events, so the start of one is kept until its delimiter arrives.
+#[derive(Debug)]
+struct RowSplitter {
+    format: OutputFormat,
+    field_delimiter: char,
+    pending: Vec<u8>,
+}
+
+impl RowSplitter {
+    fn new(format: OutputFormat, field_delimiter: char) -> Self {
+        Self {
+            format,
+            field_delimiter,
+            pending: Vec::new(),
+        }
+    }
+    /// The next complete row, skipping blank lines.
+    fn next_row(&mut self) -> Result<Option<SelectRow>, Error> {
+        while let Some(end) = self.row_end() {
+            let line = self.pending.drain(..=end).collect::<Vec<_>>();
+            if let Some(row) = self.parse_row(&line[..end])? {
+                return Ok(Some(row));
+            }
+        }
+        Ok(None)
+    }
+    //The last row may lack a delimiter
+    fn last_row(&mut self) -> Result<Option<SelectRow>, Error> {
+        let line = mem::take(&mut self.pending);
+        self.parse_row(&line)
+    }
+    //The index of the delimiter ending the first complete row; CSV fields may quote one
+    fn row_end(&self) -> Option<usize> {
+        let mut quoted = false;
+        for (index, byte) in self.pending.iter().enumerate() {
+            match byte {
+                b'"' if self.format == OutputFormat::Csv => quoted = !quoted,
+                &RECORD_DELIMITER if !quoted => return Some(index),
+                _ => {}
+            }
+        }
+        None
+    }
+    fn parse_row(&self, line: &[u8]) -> Result<Option<SelectRow>, Error> {
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        if line.iter().all(u8::is_ascii_whitespace) {
+            return Ok(None);
+        }
+        match self.format {
+            OutputFormat::Json => serde_json::from_slice(line)
+                .map(|value| Some(SelectRow::Json(value)))
+                .map_err(|err| Error::other("select_object_content", err)),
+            OutputFormat::Csv => {
+                let line = std::str::from_utf8(line)
+                    .map_err(|err| Error::other("select_object_content", err))?;
+                Ok(Some(SelectRow::Csv(csv_fields(line, self.field_delimiter))))
+            }
+        }
+    }
+}
+
+fn csv_fields(line: &str, delimiter: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ch if ch == delimiter && !quoted => fields.push(mem::take(&mut field)),
+            ch => field.push(ch),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn csv_row(fields: &[&str]) -> Option<SelectRow> {
+        Some(SelectRow::Csv(
+            fields.iter().map(|field| field.to_string()).collect(),
+        ))
+    }
+
+    #[test]
+    fn joins_a_row_split_across_records_events() {
+        let mut rows = RowSplitter::new(OutputFormat::Csv, ',');
+        rows.pending.extend_from_slice(b"1,al");
+        assert_eq!(rows.next_row().unwrap(), None);
+        rows.pending.extend_from_slice(b"ice\n2,bob\n");
+        assert_eq!(rows.next_row().unwrap(), csv_row(&["1", "alice"]));
+        assert_eq!(rows.next_row().unwrap(), csv_row(&["2", "bob"]));
+        assert_eq!(rows.next_row().unwrap(), None);
+    }
+
+    #[test]
+    fn keeps_a_quoted_newline_in_its_field() {
+        let mut rows = RowSplitter::new(OutputFormat::Csv, ',');
+        rows.pending.extend_from_slice(b"1,\"two\nlines\"");
+        assert_eq!(rows.next_row().unwrap(), None);
+        rows.pending.extend_from_slice(b"\n");
+        assert_eq!(rows.next_row().unwrap(), csv_row(&["1", "two\nlines"]));
+    }
+
+    #[test]
+    fn unescapes_doubled_quotes() {
+        assert_eq!(
+            csv_fields("\"say \"\"hi\"\"\",\"a,b\",c", ','),
+            ["say \"hi\"", "a,b", "c"]
+        );
+        let mut rows = RowSplitter::new(OutputFormat::Csv, ';');
+        rows.pending.extend_from_slice(b"\"\"\"\";x\r\n");
+        assert_eq!(rows.next_row().unwrap(), csv_row(&["\"", "x"]));
+    }
+
+    #[test]
+    fn reads_a_last_row_without_a_delimiter() {
+        let mut rows = RowSplitter::new(OutputFormat::Json, ',');
+        rows.pending.extend_from_slice(b"{\"id\":1}\n\n{\"id\":2}");
+        assert_eq!(
+            rows.next_row().unwrap(),
+            Some(SelectRow::Json(json!({"id": 1})))
+        );
+        assert_eq!(rows.next_row().unwrap(), None);
+        assert_eq!(
+            rows.last_row().unwrap(),
+            Some(SelectRow::Json(json!({"id": 2})))
+        );
+        assert_eq!(rows.last_row().unwrap(), None);
+    }
+}
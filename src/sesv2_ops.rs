@@ -1,4 +1,9 @@
-use crate::{create_email_identities_pdf, create_email_pdf};
+use crate::{
+    create_email_identities_pdf, create_email_pdf,
+    error::{Error, SdkResultExt, Service},
+    output::{report, OutputSink, TerminalSink},
+    pagination::{collect_items, items_of_pages, ItemStream},
+};
 
 use self::SimpleOrTemplate::{Simple_, Template_};
 use aws_config::SdkConfig;
@@ -8,28 +13,39 @@ use dotenv::dotenv;
 use regex::Regex;
 use sesv2::{
     operation::send_email::builders::SendEmailFluentBuilder,
-    types::{Body, Content, Destination, EmailContent, EmailTemplateContent, Message, Template},
+    types::{
+        Body, Contact, ContactList, Content, Destination, EmailContent, EmailTemplateContent,
+        EmailTemplateMetadata, IdentityInfo, Message, Template,
+    },
     Client as SesClient,
 };
-use std::{
-    env::var,
-    fs::{self, File, OpenOptions},
-    io::Write,
-};
+use std::{env::var, fs, sync::Arc};
+use tokio_stream::StreamExt;
 
 /// The core structure for performing operations on [`SESv2`](https://docs.rs/aws-sdk-sesv2/latest/aws_sdk_sesv2/struct.Client.html) (Simple Email Service Version 2)
 /// clients eliminates the need for users of the API to provide credentials each
 /// time they use the service. Instead, these credentials are abstracted by this
 /// structure along with its inherent functions and methods.
-#[derive(Debug)]
-pub struct SesOps<'a> {
-    config: &'a SdkConfig,
+#[derive(Debug, Clone)]
+pub struct SesOps {
+    config: SdkConfig,
+    client: SesClient,
+    sink: Arc<dyn OutputSink>,
 }
 
-impl<'a> SesOps<'a> {
+impl SesOps {
     ///When calling this function, it builds the credentials and the SesOps struct.
-    pub fn build(config: &'a SdkConfig) -> Self {
-        Self { config: config }
+    ///The SESv2 client is created here once, so bulk sends reuse its connections.
+    pub fn build(config: &SdkConfig) -> Self {
+        Self {
+            config: config.clone(),
+            client: SesClient::new(config),
+            sink: Arc::new(TerminalSink),
+        }
+    }
+    pub fn with_sink(mut self, sink: Arc<dyn OutputSink>) -> Self {
+        self.sink = sink;
+        self
     }
 
     /// These are not retrieved from an AWS service. In other words, these values act as proxies for the actual data if you're familiar with these details
@@ -53,11 +69,14 @@ impl<'a> SesOps<'a> {
     }
     /// These operations are asynchronous functions, so be sure to await them;
     /// otherwise, no computation will occur at all
-    pub async fn create_contact_list_name(&self, list_name: &str, description: Option<String>) {
-        let client = SesClient::new(self.config);
+    pub async fn create_contact_list_name(
+        &self,
+        list_name: &str,
+        description: Option<String>,
+    ) -> Result<(), Error> {
         let available_list_names = self
             .list_contact_lists()
-            .await
+            .await?
             .into_iter()
             .map(|to_string| {
                 let mut add_space = to_string;
@@ -66,362 +85,392 @@ impl<'a> SesOps<'a> {
             })
             .collect::<String>();
         if available_list_names.is_empty() {
-            let client = client
+            self.client
                 .create_contact_list()
                 .contact_list_name(list_name)
-                .set_description(description);
-            let colored_error = "Error from create_contact_list_name()".red().bold();
-            client
+                .set_description(description)
                 .send()
                 .await
-                .map(|_| {
-                    let colored_list = list_name.green().bold();
-                    println!("The list named {colored_list} created sucessfully\n")
-                })
-                .expect(&colored_error);
+                .map_sdk_err(Service::SesV2, "create_contact_list")?;
+            let colored_list = list_name.green().bold();
+            report!(
+                self.sink,
+                "The list named {colored_list} created sucessfully\n"
+            )
         } else {
-            println!(
+            report!(
+                self.sink,
                 "{}\n",
                 "Possible reasons this operation may have failed are"
                     .red()
                     .bold()
             );
-            println!("1) You may not have the proper credentials or region to execute this operation.\nYou need the following permissions: '{}' and '{}'","ses:CreateContactList".yellow().bold(),"ses:ListContactLists".yellow().bold());
-            println!("{}\n","2) Only one contact list name per account or region can be created.\nHere is the contact list name in your account and region, if there is any".yellow().bold());
-            let lists = self.list_contact_lists().await;
+            report!(self.sink, "1) You may not have the proper credentials or region to execute this operation.\nYou need the following permissions: '{}' and '{}'","ses:CreateContactList".yellow().bold(),"ses:ListContactLists".yellow().bold());
+            report!(self.sink, "{}\n","2) Only one contact list name per account or region can be created.\nHere is the contact list name in your account and region, if there is any".yellow().bold());
+            let lists = self.list_contact_lists().await?;
             for list in lists {
-                println!("    {}", list.green().bold());
+                report!(self.sink, "    {}", list.green().bold());
             }
-            println!("");
-            println!(
+            report!(self.sink);
+            report!(
+                self.sink,
                 "{}",
                 "If you see anything, you must delete it before creating a new contact list name"
                     .yellow()
                     .bold()
             );
-            println!("{}\n","Please note that deleting a contact list name will also delete all the emails in that list".red().bold());
+            report!(self.sink, "{}\n","Please note that deleting a contact list name will also delete all the emails in that list".red().bold());
         }
+        Ok(())
     }
-    pub async fn list_contact_lists(&self) -> Vec<String> {
-        let client = SesClient::new(self.config);
-        let outputs = client
+    pub async fn list_contact_lists(&self) -> Result<Vec<String>, Error> {
+        let lists = collect_items(self.list_contact_lists_stream()).await?;
+        Ok(lists
+            .into_iter()
+            .filter_map(|contact_list| contact_list.contact_list_name)
+            .collect())
+    }
+    /// Lists the contact lists in the account and region, requesting further pages as the
+    /// stream is consumed
+    pub fn list_contact_lists_stream(&self) -> ItemStream<ContactList> {
+        let pages = self
+            .client
             .list_contact_lists()
+            .into_paginator()
             .send()
-            .await
-            .expect("Error while listing contact lists\n");
-        let mut list_names = Vec::new();
-        if let Some(lists) = outputs.contact_lists {
-            lists.into_iter().for_each(|contact_list| {
-                if let Some(name) = contact_list.contact_list_name {
-                    list_names.push(name);
-                }
-            })
-        }
-        list_names
+            .map(|page| page.map_sdk_err(Service::SesV2, "list_contact_lists"));
+        items_of_pages(pages, |page| page.contact_lists.unwrap_or_default())
     }
-    pub async fn list_email_identity(&self) -> Vec<String> {
-        let client = SesClient::new(self.config);
-        let outputs = client
-            .list_email_identities()
-            .send()
-            .await
-            .expect("Error while listing email Identities\n");
+    pub async fn list_email_identity(&self) -> Result<Vec<String>, Error> {
+        let identity_info = collect_items(self.list_email_identities_stream()).await?;
         let mut vec_of_identity_info = Vec::new();
-        let mut file = OpenOptions::new()
-            .create(true)
-            .read(true)
-            .write(true)
-            .open("EmailIdentyDetails.txt")
-            .expect("Error while creating file");
-        if let Some(identity_info) = outputs.email_identities {
-            identity_info.into_iter().for_each(|info| {
+        let file_name = "EmailIdentyDetails.txt";
+        let mut contents = String::new();
+        if !identity_info.is_empty() {
+            for info in identity_info {
                 if let Some(identity_type) = info.identity_type {
                     let type_ = identity_type.as_str().to_string();
                     let buf = format!("Identity Type: {type_}");
-                    file.write_all(buf.as_bytes()).unwrap();
+                    contents.push_str(&buf);
                     vec_of_identity_info.push(type_);
                 }
                 if let Some(identity_name) = info.identity_name {
                     let buf = format!("Identity Name: {identity_name}");
-                    file.write_all(buf.as_bytes()).unwrap();
+                    contents.push_str(&buf);
                     vec_of_identity_info.push(identity_name);
                 }
                 let sending_enabled = format!("{}", info.sending_enabled);
                 let buf = format!("Is Sending Enabled: {sending_enabled}");
-                file.write_all(buf.as_bytes()).unwrap();
+                contents.push_str(&buf);
                 vec_of_identity_info.push(sending_enabled);
                 if let Some(status) = info.verification_status {
                     let status = status.as_str().to_string();
                     let buf = format!("Verification Status: {status}\n\n");
-                    file.write_all(buf.as_bytes()).unwrap();
+                    contents.push_str(&buf);
                     vec_of_identity_info.push(status);
                 }
-            });
-            match File::open("EmailIdentyDetails.txt") {
-                Ok(_) => println!("Email Identity Details are written to a file called '{}'\n To view the emails, please check the current directory","EmailIdentyDetails.txt".green().bold()),
-                Err(_) => println!("{}\n", "Error while writing file".red().bold()),
+            }
+            match self.sink.write_artifact(file_name, contents.as_bytes()) {
+                Ok(location) => report!(self.sink, "Email Identity Details are written to a file called '{}'\n To view the emails, please check {location}",file_name.green().bold()),
+                Err(_) => report!(self.sink, "{}\n", "Error while writing file".red().bold()),
             }
         }
-        vec_of_identity_info
+        Ok(vec_of_identity_info)
+    }
+    /// Lists every email identity, both email addresses and domains, page by page
+    pub fn list_email_identities_stream(&self) -> ItemStream<IdentityInfo> {
+        let pages = self
+            .client
+            .list_email_identities()
+            .into_paginator()
+            .send()
+            .map(|page| page.map_sdk_err(Service::SesV2, "list_email_identities"));
+        items_of_pages(pages, |page| page.email_identities.unwrap_or_default())
     }
 
-    pub async fn delete_contact_list_name(&self, contact_list_name: &str) {
-        let client = SesClient::new(self.config);
-        if self.is_contact_list_name_exist(contact_list_name).await {
-            client
+    pub async fn delete_contact_list_name(&self, contact_list_name: &str) -> Result<(), Error> {
+        if self.is_contact_list_name_exist(contact_list_name).await? {
+            self.client
                 .delete_contact_list()
                 .contact_list_name(contact_list_name)
                 .send()
                 .await
-                .expect("Error while deleting contact list name\n");
-            println!(
+                .map_sdk_err(Service::SesV2, "delete_contact_list")?;
+            report!(
+                self.sink,
                 "The specified contact list name '{}' has been deleted successfully",
                 contact_list_name.green().bold()
             );
         } else {
-            println!(
+            report!(
+                self.sink,
                 "The contact list named '{}' doesn't exist\n",
                 contact_list_name.red().bold()
             );
-            println!(
+            report!(
+                self.sink,
                 "{}\n",
                 "The following lists are in your credentials and region"
                     .yellow()
                     .bold()
             );
-            let contact_lists = self.list_contact_lists().await;
+            let contact_lists = self.list_contact_lists().await?;
             for contact_list in contact_lists {
-                println!("    {}", contact_list);
+                report!(self.sink, "    {}", contact_list);
             }
-            println!("");
+            report!(self.sink);
         }
+        Ok(())
     }
 
-    pub async fn create_email_identity(&self, email: &str) {
-        let client = SesClient::new(self.config);
-        let available_email_identities = self.retrieve_emails_from_list_email_identities().await;
+    pub async fn create_email_identity(&self, email: &str) -> Result<(), Error> {
+        let available_email_identities = self.retrieve_emails_from_list_email_identities().await?;
         if !available_email_identities.contains(email) {
-            client
+            self.client
                 .create_email_identity()
                 .email_identity(email)
                 .send()
                 .await
-                .expect("Error while creating Email Identity\n");
+                .map_sdk_err(Service::SesV2, "create_email_identity")?;
             let colored_email = email.green().bold();
-            println!(
+            report!(
+                self.sink,
                 "The email verfication send to: {} if exist\n",
                 colored_email
             );
         } else {
-            println!("The email identity '{}' already exists, but an email verification has been sent to this email again\n",email.yellow().bold());
-            client
+            report!(self.sink, "The email identity '{}' already exists, but an email verification has been sent to this email again\n",email.yellow().bold());
+            self.client
                 .delete_email_identity()
                 .email_identity(email)
                 .send()
                 .await
-                .expect("Error while deleting Email Identity\n");
-            client
+                .map_sdk_err(Service::SesV2, "delete_email_identity")?;
+            self.client
                 .create_email_identity()
                 .email_identity(email)
                 .send()
                 .await
-                .expect("Error while creating Email Identity\n");
+                .map_sdk_err(Service::SesV2, "create_email_identity")?;
             let colored_email = email.green().bold();
-            println!("The email verfication send to: {}\n", colored_email);
+            report!(
+                self.sink,
+                "The email verfication send to: {}\n",
+                colored_email
+            );
         }
+        Ok(())
     }
-    pub async fn delete_email_identity(&self, identity: &str) {
-        let client = SesClient::new(self.config);
-        let available_email_identities = self.retrieve_emails_from_list_email_identities().await;
+    pub async fn delete_email_identity(&self, identity: &str) -> Result<(), Error> {
+        let available_email_identities = self.retrieve_emails_from_list_email_identities().await?;
         if available_email_identities.contains(identity) {
-            client
+            self.client
                 .delete_email_identity()
                 .email_identity(identity)
                 .send()
                 .await
-                .expect("Error while deleting Email Identity\n");
-            println!(
+                .map_sdk_err(Service::SesV2, "delete_email_identity")?;
+            report!(
+                self.sink,
                 "The provided email identity '{}' has been deleted\n",
                 identity.green().bold()
             );
         } else {
-            println!(
+            report!(
+                self.sink,
                 "The email idenitity '{}' does not exist",
                 identity.red().bold()
             );
-            println!(
+            report!(
+                self.sink,
                 "{}\n",
                 "We will create an email identity for this address by sending a verification email"
                     .yellow()
                     .bold()
             );
-            self.create_email_identity(identity).await;
+            self.create_email_identity(identity).await?;
         }
+        Ok(())
     }
-    pub async fn delete_contact(&self, email: &str, list_name: Option<&str>, write_info: bool) {
-        let client = SesClient::new(self.config);
+    pub async fn delete_contact(
+        &self,
+        email: &str,
+        list_name: Option<&str>,
+        write_info: bool,
+    ) -> Result<(), Error> {
         if self
             .is_contact_list_name_exist(list_name.unwrap_or(&self.get_list_name()))
-            .await
+            .await?
         {
-            let available_contacts = self.get_contacts_in_the_list(list_name).await;
+            let available_contacts = self.get_contacts_in_the_list(list_name).await?;
             if !available_contacts.is_empty() {
                 if available_contacts.contains(email) {
-                    client
+                    self.client
                         .delete_contact()
                         .contact_list_name(list_name.unwrap_or(&self.get_list_name()))
                         .email_address(email)
                         .send()
                         .await
-                        .expect("Error while deleting Email Contact\n");
+                        .map_sdk_err(Service::SesV2, "delete_contact")?;
                     if write_info {
-                        println!(
+                        report!(
+                            self.sink,
                             "The provided contact '{}' has been deleted successfully\n",
                             email.green().bold()
                         );
                     }
                 } else {
-                    println!(
+                    report!(
+                        self.sink,
                         "There is no contact named '{}' existing in the Contact List named '{}'",
                         email.red().bold(),
                         list_name.unwrap_or(&self.get_list_name()).yellow().bold()
                     );
-                    println!("{}\n","Please execute the 'Retrieve emails from the provided list' option to identify the emails in your list".yellow().bold());
+                    report!(self.sink, "{}\n","Please execute the 'Retrieve emails from the provided list' option to identify the emails in your list".yellow().bold());
                 }
             } else {
-                println!(
+                report!(
+                    self.sink,
                     "{}",
                     "There are no contact to delete because none are available"
                         .red()
                         .bold()
                 );
-                println!("{}\n","Please add emails using the 'Add an email to the list' option and then execute this task".yellow().bold());
+                report!(self.sink, "{}\n","Please add emails using the 'Add an email to the list' option and then execute this task".yellow().bold());
             }
         } else {
-            println!(
+            report!(
+                self.sink,
                 "The provided Contact List Name '{}' doesn't exist",
                 list_name.unwrap_or(&self.get_list_name()).red().bold()
             );
-            println!(
+            report!(
+                self.sink,
                 "{}\n",
                 "The contact list name below is available in your credentials or region if any"
                     .yellow()
                     .bold()
             );
-            let contact_lists = self.list_contact_lists().await;
+            let contact_lists = self.list_contact_lists().await?;
             for contact_list in contact_lists {
-                println!("    {}", contact_list.green().bold());
+                report!(self.sink, "    {}", contact_list.green().bold());
             }
-            println!("");
+            report!(self.sink);
         }
+        Ok(())
     }
-    pub async fn delete_contacts(&self, list_name: Option<&str>) {
+    pub async fn delete_contacts(&self, list_name: Option<&str>) -> Result<(), Error> {
         if self
             .is_contact_list_name_exist(list_name.unwrap_or(&self.get_list_name()))
-            .await
+            .await?
         {
-            let avaialble_contacts = self.get_contacts_in_the_list(list_name).await;
+            let avaialble_contacts = self.get_contacts_in_the_list(list_name).await?;
             if !avaialble_contacts.is_empty() {
                 for contact in avaialble_contacts.split(" ") {
                     if !contact.is_empty() {
-                        let client = SesClient::new(self.config);
-                        client
+                        self.client
                             .delete_contact()
                             .contact_list_name(list_name.unwrap_or(&self.get_list_name()))
                             .email_address(contact)
                             .send()
                             .await
-                            .expect("Error while deleting contacts\n");
+                            .map_sdk_err(Service::SesV2, "delete_contact")?;
                     }
                 }
-                println!(
+                report!(
+                    self.sink,
                     "All the contacts have been deleted from the contact list named '{}'",
                     list_name.unwrap_or(&self.get_list_name()).green().bold()
                 );
-                println!("{}\n","You can verify this by executing 'Retrieve emails from the provided list' where you should receive an empty text or PDF file if you see this message".yellow().bold());
+                report!(self.sink, "{}\n","You can verify this by executing 'Retrieve emails from the provided list' where you should receive an empty text or PDF file if you see this message".yellow().bold());
             } else {
-                println!(
+                report!(
+                    self.sink,
                     "{}",
                     "There are no contacts to delete because none are available"
                         .red()
                         .bold()
                 );
-                println!("{}\n","Please add emails using the 'Add an email to the list' option and then execute this task".yellow().bold());
+                report!(self.sink, "{}\n","Please add emails using the 'Add an email to the list' option and then execute this task".yellow().bold());
             }
         } else {
-            println!(
+            report!(
+                self.sink,
                 "The provided Contact List Name '{}' doesn't exist",
                 list_name.unwrap_or(&self.get_list_name()).red().bold()
             );
-            println!(
+            report!(
+                self.sink,
                 "{}\n",
                 "The contact list name below is available in your credentials or region if any"
                     .yellow()
                     .bold()
             );
-            let contact_lists = self.list_contact_lists().await;
+            let contact_lists = self.list_contact_lists().await?;
             for contact_list in contact_lists {
-                println!("    {}", contact_list.green().bold());
+                report!(self.sink, "    {}", contact_list.green().bold());
             }
-            println!("");
+            report!(self.sink);
         }
+        Ok(())
     }
 
-    pub async fn get_emails_given_list_name(&self, list_name: Option<&str>) -> Option<String> {
-        let client = SesClient::new(self.config);
-
+    pub async fn get_emails_given_list_name(
+        &self,
+        list_name: Option<&str>,
+    ) -> Result<Option<String>, Error> {
         if self
             .is_contact_list_name_exist(list_name.unwrap_or(&self.get_list_name().as_str()))
-            .await
+            .await?
         {
             let mut emails = String::new();
-            let list = client
-                .list_contacts()
-                .contact_list_name(list_name.unwrap_or(&self.get_list_name().as_str()))
-                .send()
-                .await
-                .map(|contacts| {
-                    let colored_list_name = list_name
-                        .unwrap_or(&self.get_list_name().as_str())
-                        .green()
-                        .bold();
-                    println!("List named {} is exist\n", colored_list_name);
-                    contacts
-                })
-                .expect("Error from retrieve_emails_from_provided_list\n");
-            let contacts = list.contacts().unwrap();
-            contacts.into_iter().for_each(|contact| {
+            let contacts = collect_items(
+                self.list_contacts_stream(list_name.unwrap_or(&self.get_list_name().as_str())),
+            )
+            .await?;
+            let colored_list_name = list_name
+                .unwrap_or(&self.get_list_name().as_str())
+                .green()
+                .bold();
+            report!(self.sink, "List named {} is exist\n", colored_list_name);
+            contacts.iter().for_each(|contact| {
                 let email = contact.email_address().unwrap_or_default();
                 emails.push_str(email);
             });
-            Some(emails)
+            Ok(Some(emails))
         } else {
-            println!(
+            report!(
+                self.sink,
                 "The provided Contact List Name '{}' doesn't exist",
                 list_name
                     .unwrap_or(self.get_list_name().as_str())
                     .red()
                     .bold()
             );
-            None
+            Ok(None)
         }
     }
-    pub async fn get_contacts_in_the_list(&self, list_name: Option<&str>) -> String {
-        let client = SesClient::new(self.config);
-        let output = client
+    pub async fn get_contacts_in_the_list(&self, list_name: Option<&str>) -> Result<String, Error> {
+        let contacts_ =
+            collect_items(self.list_contacts_stream(list_name.unwrap_or(&self.get_list_name())))
+                .await?;
+        let mut contacts = String::new();
+        contacts_.into_iter().for_each(|contact| {
+            contacts.push_str(contact.email_address.unwrap_or_default().as_str());
+            contacts.push(' ');
+        });
+        Ok(contacts)
+    }
+    /// Lists the contacts of the given contact list, fetching them page by page
+    pub fn list_contacts_stream(&self, list_name: &str) -> ItemStream<Contact> {
+        let pages = self
+            .client
             .list_contacts()
-            .contact_list_name(list_name.unwrap_or(&self.get_list_name()))
+            .contact_list_name(list_name)
+            .into_paginator()
             .send()
-            .await
-            .expect("Error while getting contact lists\n");
-        let mut contacts = String::new();
-        if let Some(contacts_) = output.contacts {
-            contacts_.into_iter().for_each(|contact| {
-                contacts.push_str(contact.email_address.unwrap_or_default().as_str());
-                contacts.push(' ');
-            });
-        }
-        contacts
+            .map(|page| page.map_sdk_err(Service::SesV2, "list_contacts"));
+        items_of_pages(pages, |page| page.contacts.unwrap_or_default())
     }
     /// This function utilizes a default list name if 'None' is passed as a parameter.
     /// It incorporates 'create_identity' internally to send a verification email.
@@ -431,54 +480,68 @@ impl<'a> SesOps<'a> {
         &self,
         email: &str,
         list_name: Option<&str>,
-    ) {
-        let client = SesClient::new(self.config);
+    ) -> Result<(), Error> {
         let default_list_name = match list_name {
             Some(list_name) => list_name.to_string(),
             None => self.get_list_name(),
         };
-        let available_contacts = self.get_contacts_in_the_list(list_name).await;
+        let available_contacts = self.get_contacts_in_the_list(list_name).await?;
         if !available_contacts.contains(email) {
-            let client = client
+            self.client
                 .create_contact()
                 .contact_list_name(&default_list_name)
-                .email_address(email);
-            let colored_error_outside = "Error from create_email_contact_with_verification"
-                .red()
-                .bold();
-
-            client
+                .email_address(email)
                 .send()
                 .await
-                .map(|_| async {
-                    let colored_email = email.green().bold();
-                    let colored_list_name = default_list_name.green().bold();
-                    println!(
-                        "The email address {colored_email} has been added to the contact list named: {}\n",
-                        colored_list_name
-                    );
-                    let email_identies = self.retrieve_emails_from_list_email_identities().await;
-                    if !email_identies.contains(email){
-                        self.create_email_identity(email).await;
-                    }else {
-                        println!("The email '{}' already has an email identity",email.yellow().bold());
-                        println!("But we are sending a verification email again for this email\n");
-                        let client = SesClient::new(self.config);
-                        client.delete_email_identity().email_identity(email).send().await.expect("Error while deleting email identity\n");
-                        client.create_email_identity().email_identity(email).send().await.expect("Error while creating email identity\n");
-                        println!("The verification email has been sent to: {}",email.green().bold());
-                    }
-                })
-                .expect(&colored_error_outside)
-                .await;
+                .map_sdk_err(Service::SesV2, "create_contact")?;
+            let colored_email = email.green().bold();
+            let colored_list_name = default_list_name.green().bold();
+            report!(
+                self.sink,
+                "The email address {colored_email} has been added to the contact list named: {}\n",
+                colored_list_name
+            );
+            let email_identies = self.retrieve_emails_from_list_email_identities().await?;
+            if !email_identies.contains(email) {
+                self.create_email_identity(email).await?;
+            } else {
+                report!(
+                    self.sink,
+                    "The email '{}' already has an email identity",
+                    email.yellow().bold()
+                );
+                report!(
+                    self.sink,
+                    "But we are sending a verification email again for this email\n"
+                );
+                self.client
+                    .delete_email_identity()
+                    .email_identity(email)
+                    .send()
+                    .await
+                    .map_sdk_err(Service::SesV2, "delete_email_identity")?;
+                self.client
+                    .create_email_identity()
+                    .email_identity(email)
+                    .send()
+                    .await
+                    .map_sdk_err(Service::SesV2, "create_email_identity")?;
+                report!(
+                    self.sink,
+                    "The verification email has been sent to: {}",
+                    email.green().bold()
+                );
+            }
         } else {
-            println!(
+            report!(
+                self.sink,
                 "The email contact '{}' already exists in the given list '{}'",
                 email.yellow().bold(),
                 default_list_name.yellow().bold()
             );
-            println!("{}\n","Use the 'Create Email Identity' option to send a verification email to this address if that's what you want".yellow().bold());
+            report!(self.sink, "{}\n","Use the 'Create Email Identity' option to send a verification email to this address if that's what you want".yellow().bold());
         }
+        Ok(())
     }
 
     /// Sometimes, we may not want to verify it immediately; instead, we simply want
@@ -488,63 +551,60 @@ impl<'a> SesOps<'a> {
         &self,
         email: &str,
         list_name: Option<&str>,
-    ) {
-        let client = SesClient::new(self.config);
-
+    ) -> Result<(), Error> {
         let default_list_name = match list_name {
             Some(list_name) => list_name.to_string(),
             None => self.get_list_name(),
         };
-        let contacts = self.get_contacts_in_the_list(list_name).await;
+        let contacts = self.get_contacts_in_the_list(list_name).await?;
         if !contacts.contains(email) {
-            let client = client
+            self.client
                 .create_contact()
                 .contact_list_name(&default_list_name)
-                .email_address(email);
-
-            let colored_error = "Error from create_email_contact_without_verification\n"
-                .red()
-                .bold();
-
-            client.send().await.expect(&colored_error);
+                .email_address(email)
+                .send()
+                .await
+                .map_sdk_err(Service::SesV2, "create_contact")?;
 
             let colored_email = email.green().bold();
             let colored_list_name = default_list_name.green().bold();
-            println!("The email address {colored_email} has been added to the contact list named: {colored_list_name}\n");
-            println!("You must pass the email '{}' to the 'Create Email Identity' option before sending an email to this address\n",email.yellow().bold());
+            report!(self.sink, "The email address {colored_email} has been added to the contact list named: {colored_list_name}\n");
+            report!(self.sink, "You must pass the email '{}' to the 'Create Email Identity' option before sending an email to this address\n",email.yellow().bold());
         } else {
-            println!(
+            report!(
+                self.sink,
                 "The email contact '{}' already exists in the given list '{}'",
                 email.yellow().bold(),
                 default_list_name.yellow().bold()
             );
-            println!("{}\n","Use the 'Create Email Identity' option to send a verification email to this address if that's what you want".yellow().bold());
+            report!(self.sink, "{}\n","Use the 'Create Email Identity' option to send a verification email to this address if that's what you want".yellow().bold());
         }
+        Ok(())
     }
     /// Returns Some of true or false if the identity is exist otherwise returns None.
-    pub async fn is_email_verfied(&self, email: &str) -> Option<bool> {
-        let client = SesClient::new(self.config);
-        let email_identies = self.retrieve_emails_from_list_email_identities().await;
+    pub async fn is_email_verfied(&self, email: &str) -> Result<Option<bool>, Error> {
+        let email_identies = self.retrieve_emails_from_list_email_identities().await?;
         if email_identies.contains(email) {
-            let client = client
+            let output = self
+                .client
                 .get_email_identity()
                 .email_identity(email)
                 .send()
                 .await
-                .expect("Error while Creating Email Identity\n");
-            if client.verified_for_sending_status() {
-                Some(true)
+                .map_sdk_err(Service::SesV2, "get_email_identity")?;
+            if output.verified_for_sending_status() {
+                Ok(Some(true))
             } else {
-                Some(false)
+                Ok(Some(false))
             }
         } else {
-            None
+            Ok(None)
         }
     }
-    pub async fn is_contact_list_name_exist(&self, contact_list_name: &str) -> bool {
+    pub async fn is_contact_list_name_exist(&self, contact_list_name: &str) -> Result<bool, Error> {
         let available_list_names = self
             .list_contact_lists()
-            .await
+            .await?
             .into_iter()
             .map(|to_string| {
                 let mut add_space = to_string;
@@ -553,9 +613,9 @@ impl<'a> SesOps<'a> {
             })
             .collect::<String>();
         if available_list_names.contains(contact_list_name) {
-            true
+            Ok(true)
         } else {
-            false
+            Ok(false)
         }
     }
     /// This helper function retrieves the emails from the provided contact list name,
@@ -563,87 +623,66 @@ impl<'a> SesOps<'a> {
     pub async fn retrieve_emails_from_provided_list(
         &self,
         list_name: Option<&str>,
-    ) -> Option<Vec<String>> {
-        let client = SesClient::new(self.config);
-
+    ) -> Result<Option<Vec<String>>, Error> {
         let default_list_name = match list_name {
             Some(list_name) => list_name.to_string(),
             None => self.get_list_name(),
         };
-        if self.is_contact_list_name_exist(&default_list_name).await {
+        if self.is_contact_list_name_exist(&default_list_name).await? {
             let mut emails = Vec::new();
-            let list = client
-                .list_contacts()
-                .contact_list_name(&default_list_name)
-                .send()
-                .await
-                .map(|contacts| {
-                    let colored_list_name = default_list_name.green().bold();
-                    println!("List named {} is exist\n", colored_list_name);
-                    contacts
-                })
-                .expect("Error from retrieve_emails_from_provided_list\n");
-            let contacts = list.contacts().unwrap();
+            let contacts = collect_items(self.list_contacts_stream(&default_list_name)).await?;
+            let colored_list_name = default_list_name.green().bold();
+            report!(self.sink, "List named {} is exist\n", colored_list_name);
             contacts
                 .into_iter()
                 .map(|contact| contact.email_address().unwrap_or_default().into())
                 .for_each(|email| {
                     emails.push(email);
                 });
-            Some(emails)
+            Ok(Some(emails))
         } else {
-            println!(
+            report!(
+                self.sink,
                 "The provided list name '{}' doesn't exist",
                 default_list_name.red().bold()
             );
-            let available_list_names = self.list_contact_lists().await;
-            println!(
+            let available_list_names = self.list_contact_lists().await?;
+            report!(
+                self.sink,
                 "{}\n",
                 "Only the contact list names below are available on your credentials or region"
                     .yellow()
                     .bold()
             );
             for contact_list_name in available_list_names {
-                println!("    {}", contact_list_name.bright_green().bold());
+                report!(self.sink, "    {}", contact_list_name.bright_green().bold());
             }
-            println!("");
-            None
+            report!(self.sink);
+            Ok(None)
         }
     }
-    pub async fn retrieve_emails_from_list_email_identities(&self) -> String {
-        let client = SesClient::new(self.config);
-        let outputs = client
-            .list_email_identities()
-            .send()
-            .await
-            .expect("Error while getting emails from list email identities api\n");
+    pub async fn retrieve_emails_from_list_email_identities(&self) -> Result<String, Error> {
+        let identityinfo = collect_items(self.list_email_identities_stream()).await?;
         let mut string_of_email_identies = String::new();
-        if let Some(identityinfo) = outputs.email_identities {
-            identityinfo.into_iter().for_each(|details| {
-                if let Some(identity_name) = details.identity_name {
-                    string_of_email_identies.push_str(&identity_name);
-                    string_of_email_identies.push_str(" ");
-                }
-            });
-        }
-        string_of_email_identies
+        identityinfo.into_iter().for_each(|details| {
+            if let Some(identity_name) = details.identity_name {
+                string_of_email_identies.push_str(&identity_name);
+                string_of_email_identies.push_str(" ");
+            }
+        });
+        Ok(string_of_email_identies)
     }
-    /// Retrieve the emails from the provided contact list name and save them to the
-    /// current directory for future use.
+    /// Retrieve the emails from the provided contact list name and save them through the
+    /// output sink for future use.
     pub async fn writing_email_addresses_from_provided_list_as_text_pdf(
         &self,
         list_name: Option<&str>,
-    ) {
-        let emails = self.retrieve_emails_from_provided_list(list_name).await;
+    ) -> Result<(), Error> {
+        let emails = self.retrieve_emails_from_provided_list(list_name).await?;
         match emails {
             Some(emails) => {
-                let email_identities = self.retrieve_emails_from_list_email_identities().await;
-                let mut file = OpenOptions::new()
-                    .create(true)
-                    .write(true)
-                    .read(true)
-                    .open("./emails.txt")
-                    .unwrap();
+                let email_identities = self.retrieve_emails_from_list_email_identities().await?;
+                let mut contents = String::new();
                 let headers = vec![
                     "Identity Type",
                     "Identity Name",
@@ -651,34 +690,34 @@ impl<'a> SesOps<'a> {
                     "Verification Status",
                 ];
                 let mut vector_of_email_with_status = Vec::new();
-                writeln!(file, "Emails\n").unwrap();
+                contents.push_str("Emails\n\n");
                 for email in emails {
-                    writeln!(file, "{email}\n").unwrap();
+                    contents.push_str(&format!("{email}\n\n"));
                     if email_identities.contains(&email) {
-                        let client = SesClient::new(self.config);
-                        let info = client
+                        let info = self
+                            .client
                             .get_email_identity()
                             .email_identity(&email)
                             .send()
                             .await
-                            .expect("Error while getting email identity\n");
+                            .map_sdk_err(Service::SesV2, "get_email_identity")?;
 
                         if let Some(identity_type) = info.identity_type {
                             let type_ = identity_type.as_str().to_string();
                             let buf = format!("Identity Type: {type_}");
-                            file.write_all(buf.as_bytes()).unwrap();
+                            contents.push_str(&buf);
                             vector_of_email_with_status.push(type_);
                         }
                         vector_of_email_with_status.push(email);
                         let sending_enabled = format!("{}", info.verified_for_sending_status);
                         let buf = format!("Is Sending Enabled: {sending_enabled}");
-                        file.write_all(buf.as_bytes()).unwrap();
+                        contents.push_str(&buf);
                         vector_of_email_with_status.push(sending_enabled);
 
                         if let Some(status) = info.verification_status {
                             let status = status.as_str().to_string();
                             let buf = format!("Verification Status: {status}\n\n");
-                            file.write_all(buf.as_bytes()).unwrap();
+                            contents.push_str(&buf);
                             vector_of_email_with_status.push(status);
                         }
                     } else {
@@ -688,12 +727,12 @@ impl<'a> SesOps<'a> {
                         vector_of_email_with_status.push("No Identity Exists".into());
                     }
                 }
-                match File::open("emails.txt") {
-                    Ok(_) => println!(
-                        "{}\n",
-                        "Emails are written to a file called 'emails.txt'\n To view the emails, please check the current directory".green().bold()
-                    ),
-                    Err(_) => println!("{}\n", "Error while writing file".red().bold()),
+                match self.sink.write_artifact("emails.txt", contents.as_bytes()) {
+                    Ok(location) => {
+                        let msg = format!("Emails are written to a file called 'emails.txt'\n To view the emails, please check {location}");
+                        report!(self.sink, "{}\n", msg.green().bold())
+                    }
+                    Err(_) => report!(self.sink, "{}\n", "Error while writing file".red().bold()),
                 }
 
                 let get_list_name =
@@ -709,43 +748,54 @@ impl<'a> SesOps<'a> {
                     vector_of_email_with_status,
                     list_name,
                     region_name,
-                );
+                    self.sink.as_ref(),
+                )?;
             }
             None => {}
         }
+        Ok(())
     }
-    pub async fn writing_email_identies_details_as_text_pdf(&self) {
+    pub async fn writing_email_identies_details_as_text_pdf(&self) -> Result<(), Error> {
         let headers = vec![
             "Identity Type",
             "Identity Name",
             "Is Sending Enabled",
             "Verification Status",
         ];
-        let identities = self.list_email_identity().await;
+        let identities = self.list_email_identity().await?;
         let region_name = self
             .config
             .region()
             .map(|region| region.as_ref())
             .unwrap_or("No Region is found");
-        create_email_identities_pdf(&headers, identities, region_name);
+        create_email_identities_pdf(&headers, identities, region_name, self.sink.as_ref())?;
+        Ok(())
     }
 
     /// This only works when production access is enabled, i.e., in a paid AWS service, instead of a trial version that has not been tested.
-    pub async fn send_custom_verification(&self, email: &str, template_name: &str) {
-        let client = SesClient::new(self.config);
-
-        let send = client
+    pub async fn send_custom_verification(
+        &self,
+        email: &str,
+        template_name: &str,
+    ) -> Result<(), Error> {
+        let send = self
+            .client
             .send_custom_verification_email()
             .email_address(email)
             .template_name(template_name);
         match send.send().await {
             Ok(_) => {
                 let colored_email = email.green().bold();
-                println!("Mail verfication is send to : {colored_email}\n")
+                report!(self.sink, "Mail verfication is send to : {colored_email}\n");
+                Ok(())
             }
-            Err(_) => {
+            Err(err) => {
                 let colored_email = email.red().bold();
-                println!("Error while sending Verfication to : {colored_email}\n")
+                report!(
+                    self.sink,
+                    "Error while sending Verfication to : {colored_email}\n"
+                );
+                Err(err).map_sdk_err(Service::SesV2, "send_custom_verification_email")
             }
         }
     }
@@ -755,68 +805,76 @@ impl<'a> SesOps<'a> {
         subject: &str,
         template: &str,
         text: Option<String>,
-    ) {
-        let client = SesClient::new(self.config);
-        if !self.is_email_template_exist(template_name).await {
+    ) -> Result<(), Error> {
+        if !self.is_email_template_exist(template_name).await? {
             let email_template_builder = EmailTemplateContent::builder()
                 .subject(subject)
                 .html(template)
                 .set_text(text)
                 .build();
-            let build = client
+            let build = self
+                .client
                 .create_email_template()
                 .template_content(email_template_builder)
                 .template_name(template_name);
 
-            let colored_error = "Error from create_email_template\n".red().bold();
             build
                 .send()
                 .await
-                .map(|_| {
-                    let colored_tempname = template_name.green().bold();
-                    println!(
-                        "The email template named '{}' has been created\n",
-                        colored_tempname,
-                    )
-                })
-                .expect(&colored_error);
+                .map_sdk_err(Service::SesV2, "create_email_template")?;
+            let colored_tempname = template_name.green().bold();
+            report!(
+                self.sink,
+                "The email template named '{}' has been created\n",
+                colored_tempname,
+            )
         } else {
-            println!("Template '{}' already exists", template_name.red().bold());
-            println!("{}", "Try using different template name".yellow().bold());
-            println!(
+            report!(
+                self.sink,
+                "Template '{}' already exists",
+                template_name.red().bold()
+            );
+            report!(
+                self.sink,
+                "{}",
+                "Try using different template name".yellow().bold()
+            );
+            report!(
+                self.sink,
                 "{}\n",
                 "Below are the available template names in your credentials or region"
                     .yellow()
                     .bold()
             );
-            let templates = self.list_email_templates().await;
+            let templates = self.list_email_templates().await?;
             for template_name in templates {
-                println!("    {}", template_name.green().bold());
+                report!(self.sink, "    {}", template_name.green().bold());
             }
-            println!("");
+            report!(self.sink);
         }
+        Ok(())
     }
-    pub async fn list_email_templates(&self) -> Vec<String> {
-        let client = SesClient::new(self.config);
-        let outputs = client
+    pub async fn list_email_templates(&self) -> Result<Vec<String>, Error> {
+        let template_meta_data = collect_items(self.list_email_templates_stream()).await?;
+        Ok(template_meta_data
+            .into_iter()
+            .filter_map(|template_detail| template_detail.template_name)
+            .collect())
+    }
+    /// Lists the metadata of every email template, page by page
+    pub fn list_email_templates_stream(&self) -> ItemStream<EmailTemplateMetadata> {
+        let pages = self
+            .client
             .list_email_templates()
+            .into_paginator()
             .send()
-            .await
-            .expect("Error while getting Email Templates\n");
-        let mut templates_names = Vec::new();
-        if let Some(template_meta_data) = outputs.templates_metadata {
-            template_meta_data.into_iter().for_each(|template_detail| {
-                if let Some(temp_name) = template_detail.template_name {
-                    templates_names.push(temp_name);
-                }
-            });
-        }
-        templates_names
+            .map(|page| page.map_sdk_err(Service::SesV2, "list_email_templates"));
+        items_of_pages(pages, |page| page.templates_metadata.unwrap_or_default())
     }
-    pub async fn is_email_template_exist(&self, template_name: &str) -> bool {
+    pub async fn is_email_template_exist(&self, template_name: &str) -> Result<bool, Error> {
         let email_templates = self
             .list_email_templates()
-            .await
+            .await?
             .into_iter()
             .map(|to_string| {
                 let mut add_space = to_string;
@@ -825,38 +883,40 @@ impl<'a> SesOps<'a> {
             })
             .collect::<String>();
         if email_templates.contains(template_name) {
-            true
+            Ok(true)
         } else {
-            false
+            Ok(false)
         }
     }
-    pub async fn delete_template(&self, template_name: &str) {
-        let client = SesClient::new(self.config);
-        if self.is_email_template_exist(template_name).await {
-            client
+    pub async fn delete_template(&self, template_name: &str) -> Result<(), Error> {
+        if self.is_email_template_exist(template_name).await? {
+            self.client
                 .delete_email_template()
                 .template_name(template_name)
                 .send()
                 .await
-                .expect("Error While deleting Template\n");
-            println!("The template associated with the specified template name '{}' has been deleted successfully",template_name.green().bold());
+                .map_sdk_err(Service::SesV2, "delete_email_template")?;
+            report!(self.sink, "The template associated with the specified template name '{}' has been deleted successfully",template_name.green().bold());
         } else {
-            println!(
+            report!(
+                self.sink,
                 "The template named '{}' doesn't exist",
                 template_name.red().bold()
             );
-            println!(
+            report!(
+                self.sink,
                 "{}\n",
                 "Here are the available template names in your credentials or region"
                     .yellow()
                     .bold()
             );
-            let templates = self.list_email_templates().await;
+            let templates = self.list_email_templates().await?;
             for template_name in templates {
-                println!("    {}", template_name.green().bold());
+                report!(self.sink, "    {}", template_name.green().bold());
             }
-            println!("");
+            report!(self.sink);
         }
+        Ok(())
     }
     pub async fn update_template(
         &self,
@@ -864,9 +924,8 @@ impl<'a> SesOps<'a> {
         subject: &str,
         html: &str,
         text: Option<String>,
-    ) {
-        if self.is_email_template_exist(template_name).await {
-            let client = SesClient::new(self.config);
+    ) -> Result<(), Error> {
+        if self.is_email_template_exist(template_name).await? {
             let text_str = text
                 .as_ref()
                 .map(|to_string| to_string.to_string())
@@ -876,60 +935,58 @@ impl<'a> SesOps<'a> {
                 .set_text(text)
                 .html(html)
                 .build();
-            client
+            self.client
                 .update_email_template()
                 .template_name(template_name)
                 .template_content(template_builder)
                 .send()
                 .await
-                .expect("Error while Updating Template\n");
+                .map_sdk_err(Service::SesV2, "update_email_template")?;
             match (subject.is_empty(),text_str.is_empty()) {
-            (false,false) => println!("{}\n","The template has been successfully updated with the provided subject, HTML body, and text body".green().bold()),
-            (true,false) => println!("{}\n","The template has been successfully updated with the provided HTML body, and text body".green().bold()),
-            (false,true) => println!("{}\n","The template has been successfully updated with the provided subject, HTML body".green().bold()),
-            (true,true) => println!("{}\n","The template has been successfully updated with the provided HTML body".green().bold())
+            (false,false) => report!(self.sink, "{}\n","The template has been successfully updated with the provided subject, HTML body, and text body".green().bold()),
+            (true,false) => report!(self.sink, "{}\n","The template has been successfully updated with the provided HTML body, and text body".green().bold()),
+            (false,true) => report!(self.sink, "{}\n","The template has been successfully updated with the provided subject, HTML body".green().bold()),
+            (true,true) => report!(self.sink, "{}\n","The template has been successfully updated with the provided HTML body".green().bold())
         }
         } else {
-            println!(
+            report!(
+                self.sink,
                 "The template named '{}' doesn't exist",
                 template_name.red().bold()
             );
-            println!(
+            report!(
+                self.sink,
                 "{}\n",
                 "Here are the available template names in your credentials or region"
                     .yellow()
                     .bold()
             );
-            let templates = self.list_email_templates().await;
+            let templates = self.list_email_templates().await?;
             for template_name in templates {
-                println!("    {}", template_name.green().bold());
+                report!(self.sink, "    {}", template_name.green().bold());
             }
-            println!("");
+            report!(self.sink);
         }
+        Ok(())
     }
     pub async fn get_template_subject_html_and_text(
         &self,
         template_name: &str,
         write_info: bool,
-    ) -> Option<(String, String, String)> {
-        if self.is_email_template_exist(template_name).await {
+    ) -> Result<Option<(String, String, String)>, Error> {
+        if self.is_email_template_exist(template_name).await? {
             let mut subject = String::new();
             let mut html = String::new();
             let mut text = String::new();
-            let client = SesClient::new(self.config);
-            let outputs = client
+            let outputs = self
+                .client
                 .get_email_template()
                 .template_name(template_name)
                 .send()
                 .await
-                .expect("Error While Getting Template\n");
+                .map_sdk_err(Service::SesV2, "get_email_template")?;
             let file_name = format!("EmailTemplateOf{template_name}.html");
-            let mut email_template = OpenOptions::new()
-                .create(true)
-                .read(true)
-                .write(true)
-                .open(&file_name)
-                .expect("Error while creating file for Email Template Content\n");
+            let mut email_template = String::new();
             if let Some(content) = outputs.template_content {
                 if let Some(subject_) = content.subject {
                     subject.push_str(&subject_);
@@ -937,7 +994,7 @@ impl<'a> SesOps<'a> {
                         let buf = format!(
                             r#"<h1 style="text-align: center;">Subject Part</h1><br><br><p style="text-align: center;padding-left: 100px;padding-right: 100px;">{subject_}</p><br><br>"#
                         );
-                        email_template.write_all(buf.as_bytes()).unwrap();
+                        email_template.push_str(&buf);
                     }
                 }
                 if let Some(html_) = content.html {
@@ -946,7 +1003,7 @@ impl<'a> SesOps<'a> {
                         let buf = format!(
                             r#"<h1 style="text-align: center;">Html Part</h1><br><br>{html_}<br><br>"#
                         );
-                        email_template.write_all(buf.as_bytes()).unwrap();
+                        email_template.push_str(&buf);
                     }
                 }
                 if let Some(text_) = content.text {
@@ -955,36 +1012,36 @@ impl<'a> SesOps<'a> {
                         let buf = format!(
                             r#"<h1 style="text-align: center;">Text Part</h1><br><br><p style="padding-left: 100px;padding-right: 100px;">{text_}</p><br><br><br><br><br>"#
                         );
-                        email_template.write_all(buf.as_bytes()).unwrap();
+                        email_template.push_str(&buf);
                     }
                 }
             }
             if write_info {
-                match File::open(&file_name) {
-                Ok(_) => println!("The email template associated with the template name '{}' has been successfully created in the current directory with the file name '{}{}.{}'\n",template_name.green().bold(),"EmailTemplateOf".green().bold(),template_name.green().bold(),"html".green().bold()),
-                Err(_) => println!("Error While writing Email Template\n")
+                match self.sink.write_artifact(&file_name, email_template.as_bytes()) {
+                Ok(location) => report!(self.sink, "The email template associated with the template name '{}' has been successfully created in {location} with the file name '{}{}.{}'\n",template_name.green().bold(),"EmailTemplateOf".green().bold(),template_name.green().bold(),"html".green().bold()),
+                Err(_) => report!(self.sink, "Error While writing Email Template\n")
             }
-            } else {
-                std::fs::remove_file(&file_name).expect("This won't fail unless in Rare cases\n");
             }
-            Some((subject, html, text))
+            Ok(Some((subject, html, text)))
         } else {
-            println!(
+            report!(
+                self.sink,
                 "The template named '{}' doesn't exist",
                 template_name.red().bold()
             );
-            println!(
+            report!(
+                self.sink,
                 "{}\n",
                 "Here are the available template names in your credentials or region"
                     .yellow()
                     .bold()
             );
-            let templates = self.list_email_templates().await;
+            let templates = self.list_email_templates().await?;
             for template_name in templates {
-                println!("    {}", template_name.green().bold());
+                report!(self.sink, "    {}", template_name.green().bold());
             }
-            println!("");
-            None
+            report!(self.sink);
+            Ok(None)
         }
     }
     pub fn get_template_variables_of_subject_and_html_body(
@@ -1010,28 +1067,29 @@ impl<'a> SesOps<'a> {
         &self,
         template_name: Option<&str>,
         template_data_path: &str,
-    ) {
+    ) -> Result<(), Error> {
         let json_keys_pattern = regex::Regex::new(r#""(\w+)"\s*:"#);
-        let template_josn_data = fs::read_to_string(template_data_path)
-            .expect("Error while opening the template data path you specified\n");
+        let template_josn_data = fs::read_to_string(template_data_path).map_err(|err| {
+            Error::io("match_template_data_with_template", template_data_path, err)
+        })?;
         match json_keys_pattern {
             Ok(regex) => {
                 let keys = regex
                     .find_iter(&template_josn_data)
                     .map(|to_str| {
                         let remove_quotes = to_str.as_str().to_string();
-                        // println!("{}\n",remove_quotes);
+                        // report!(self.sink, "{}\n",remove_quotes);
                         let matches: &[_] = &['"', ':', ' '];
                         remove_quotes.trim_matches(matches).to_string()
                     })
                     .collect::<Vec<String>>();
-                //println!("Template Data Json Keys:\n{}\n", keys.join("\n"));
+                //report!(self.sink, "Template Data Json Keys:\n{}\n", keys.join("\n"));
                 let get_template = self
                     .get_template_subject_html_and_text(
                         template_name.unwrap_or(&self.get_template_name()),
                         false,
                     )
-                    .await;
+                    .await?;
                 match get_template {
                     Some((subject, html, _)) => {
                         let (subject_variables, html_variables) =
@@ -1055,24 +1113,26 @@ impl<'a> SesOps<'a> {
                         let template_variables = subject_variables + &html_variables;
                         for variable in keys {
                             if template_variables.contains(&variable) {
-                                println!("The template data variable {} in the specified JSON document matches the template variable of template {}\n", variable.green().bold(),template_name.unwrap_or(&self.get_template_name()).green().bold());
+                                report!(self.sink, "The template data variable {} in the specified JSON document matches the template variable of template {}\n", variable.green().bold(),template_name.unwrap_or(&self.get_template_name()).green().bold());
                             } else {
-                                println!("The variable in the template {} in the given JSON document does not match the variable in the template named {}\n",variable.red().bold(),template_name.unwrap_or(&self.get_template_name()).red().bold());
+                                report!(self.sink, "The variable in the template {} in the given JSON document does not match the variable in the template named {}\n",variable.red().bold(),template_name.unwrap_or(&self.get_template_name()).red().bold());
                             }
                         }
-                        println!("");
-                        println!("{}\n","This option will not match the spaces around the keys. Execute the 'Get Email Template Variables' option to see the template variables".yellow().bold());
+                        report!(self.sink);
+                        report!(self.sink, "{}\n","This option will not match the spaces around the keys. Execute the 'Get Email Template Variables' option to see the template variables".yellow().bold());
                     }
                     None => {}
                 }
             }
-            Err(_) => println!(
+            Err(_) => report!(
+                self.sink,
                 "{}\n",
                 "Error while parsing the template json you specified"
                     .red()
                     .bold()
             ),
         }
+        Ok(())
     }
     /// Create a helper function for sending single emails, allowing other parts of the code or users to customize it for sending bulk emails
     pub async fn send_mono_email(
@@ -1080,8 +1140,7 @@ impl<'a> SesOps<'a> {
         email: &str,
         simple_or_template: SimpleOrTemplate,
         from_address: Option<&str>,
-    ) -> Result<SendEmailFluentBuilder, String> {
-        let client = SesClient::new(self.config);
+    ) -> Result<SendEmailFluentBuilder, Error> {
         //let email_identies = self.retrieve_emails_from_list_email_identities().await;
 
         /*
@@ -1094,12 +1153,12 @@ impl<'a> SesOps<'a> {
                         let default_from_address = self.get_from_address();
                         let from_address = from_address.unwrap_or(&default_from_address);
                         match simple_or_template {
-                            Simple_(simple) => Ok(client
+                            Simple_(simple) => Ok(self.client
                                 .send_email()
                                 .content(simple)
                                 .from_email_address(from_address)
                                 .destination(destination)),
-                            Template_(template) => Ok(client
+                            Template_(template) => Ok(self.client
                                 .send_email()
                                 .content(template)
                                 .from_email_address(from_address)
@@ -1109,13 +1168,13 @@ impl<'a> SesOps<'a> {
 
                      else {
                         let why_failed = format!("The email ---{}--- has not been verified; we have re-sent the verification email",email);
-                        client
+                        self.client
                             .delete_email_identity()
                             .email_identity(email)
                             .send()
                             .await
                             .expect("Error while deleting email identity\n");
-                        client
+                        self.client
                             .create_email_identity()
                             .email_identity(email)
                             .send()
@@ -1138,12 +1197,14 @@ impl<'a> SesOps<'a> {
         let default_from_address = self.get_from_address();
         let from_address = from_address.unwrap_or(&default_from_address);
         match simple_or_template {
-            Simple_(simple) => Ok(client
+            Simple_(simple) => Ok(self
+                .client
                 .send_email()
                 .content(simple)
                 .from_email_address(from_address)
                 .destination(destination)),
-            Template_(template) => Ok(client
+            Template_(template) => Ok(self
+                .client
                 .send_email()
                 .content(template)
                 .from_email_address(from_address)
@@ -1155,17 +1216,17 @@ impl<'a> SesOps<'a> {
     /// emails to the addresses in a list, all without introducing any code smells on
     /// the caller's side and doesn't take any parameters. This is inlcuded for your reference
     /// Here is the [`template`](https://tinyurl.com/4ssuz7fy) I've used for this operation.
-    pub async fn send_bulk_templated_emails(&self) {
+    pub async fn send_bulk_templated_emails(&self) -> Result<(), Error> {
         let emails = self
             .retrieve_emails_from_provided_list(Some(&self.get_list_name()))
-            .await;
+            .await?;
         match emails {
             Some(emails) => {
-                let email_identies = self.retrieve_emails_from_list_email_identities().await;
+                let email_identies = self.retrieve_emails_from_list_email_identities().await?;
                 let load_json = include_str!("./assets/template_data.json").to_string();
                 'go: for email in emails.iter() {
                     if email_identies.contains(email) {
-                        let is_email_verified = self.is_email_verfied(&email).await;
+                        let is_email_verified = self.is_email_verfied(&email).await?;
                         match is_email_verified {
                             Some(status) => {
                                 if status {
@@ -1177,52 +1238,48 @@ impl<'a> SesOps<'a> {
                                         &data,
                                     )
                                     .build();
-                                    match self
-                                        .send_mono_email(
-                                            email,
-                                            Template_(template),
-                                            Some(&self.get_from_address()),
-                                        )
-                                        .await
-                                    {
-                                        Ok(email_builder) => {
-                                            email_builder
+                                    self.send_mono_email(
+                                        email,
+                                        Template_(template),
+                                        Some(&self.get_from_address()),
+                                    )
+                                    .await?
                                     .send()
                                     .await
-                                    .expect("Error while executing Send_bulk_templated_emails\n");
-                                            let colored_email = email.green().bold();
-                                            let colored_template_data = data.green().bold();
-                                            println!("The template mail is send to: {colored_email} \nand the template data is: {colored_template_data}\n");
-                                        }
-                                        Err(msg) => println!("{}", msg),
-                                    }
+                                    .map_sdk_err(Service::SesV2, "send_email")?;
+                                    let colored_email = email.green().bold();
+                                    let colored_template_data = data.green().bold();
+                                    report!(self.sink, "The template mail is send to: {colored_email} \nand the template data is: {colored_template_data}\n");
                                 } else {
-                                    println!("The email address '{}' in the list hasn't been verified, yet it continues to send templated emails to other verified email addresses in the list\n",email.bright_red().bold());
+                                    report!(self.sink, "The email address '{}' in the list hasn't been verified, yet it continues to send templated emails to other verified email addresses in the list\n",email.bright_red().bold());
                                     continue 'go;
                                 }
                             }
                             None => {
-                                println!(
+                                report!(
+                                    self.sink,
                                     "The email identity for '{}' does not exist",
                                     email.red().bold()
                                 );
-                                println!("{}\n","Please use the 'Create Email Identity' option or function to establish an identity for this email".yellow().bold());
+                                report!(self.sink, "{}\n","Please use the 'Create Email Identity' option or function to establish an identity for this email".yellow().bold());
                             }
                         }
                     } else {
-                        println!("The email address '{}' in this list doesn't have an identity; therefore, it creates an identity by executing the 'Create Email Identity' option on your behalf\n",email.yellow().bold());
-                        self.create_email_identity(email).await;
+                        report!(self.sink, "The email address '{}' in this list doesn't have an identity; therefore, it creates an identity by executing the 'Create Email Identity' option on your behalf\n",email.yellow().bold());
+                        self.create_email_identity(email).await?;
                     }
                 }
-                println!("{}","If you have any red-colored emails above, the operation won't be executed for those emails, but it will be executed for the other emails, where the templated mail has already been sent".yellow().bold());
+                report!(self.sink, "{}","If you have any red-colored emails above, the operation won't be executed for those emails, but it will be executed for the other emails, where the templated mail has already been sent".yellow().bold());
             }
             None => {
-                println!(
+                report!(
+                    self.sink,
                     "The provided list name '{}' doesn't exist",
                     self.get_list_name().red().bold()
                 );
             }
         }
+        Ok(())
     }
 
     /// This method accept type of `SimpleMail` with content of [`EmailContent`](https://docs.rs/aws-sdk-sesv2/latest/aws_sdk_sesv2/types/struct.EmailContent.html)
@@ -1231,72 +1288,63 @@ impl<'a> SesOps<'a> {
         data: SimpleMail,
         from_address: Option<&str>,
         list_name: Option<&str>,
-    ) {
-        let colored_error = "Error from send_multi_email_with_simple function"
-            .red()
-            .bold();
-
-        let emails = self.retrieve_emails_from_provided_list(list_name).await;
+    ) -> Result<(), Error> {
+        let emails = self.retrieve_emails_from_provided_list(list_name).await?;
         match emails {
             Some(emails) => {
                 let email_content = data.build();
-                let email_identies = self.retrieve_emails_from_list_email_identities().await;
+                let email_identies = self.retrieve_emails_from_list_email_identities().await?;
                 'go: for email in emails.into_iter() {
                     if email_identies.contains(&email) {
-                        let is_email_verified = self.is_email_verfied(&email).await;
+                        let is_email_verified = self.is_email_verfied(&email).await?;
                         match is_email_verified {
                             Some(status) => {
                                 if status {
                                     let email_content_ = email_content.clone();
-                                    match self
-                                        .send_mono_email(
-                                            &email,
-                                            Simple_(email_content_),
-                                            from_address,
-                                        )
-                                        .await
-                                    {
-                                        Ok(email_builder) => {
-                                            email_builder
-                                        .send()
-                                        .await
-                                        .map(|_| {
-                                            let colored_email = email.green().bold();
-                                            println!(
-                                                "Simple Email Content is send to {colored_email} successfully\n"
-                                            )
-                                        })
-                                        .expect(&colored_error);
-                                        }
-                                        Err(msg) => println!("{}", msg),
-                                    }
+                                    self.send_mono_email(
+                                        &email,
+                                        Simple_(email_content_),
+                                        from_address,
+                                    )
+                                    .await?
+                                    .send()
+                                    .await
+                                    .map_sdk_err(Service::SesV2, "send_email")?;
+                                    let colored_email = email.green().bold();
+                                    report!(
+                                        self.sink,
+                                        "Simple Email Content is send to {colored_email} successfully\n"
+                                    );
                                 } else {
-                                    println!("The email address '{}' in the list hasn't been verified, yet it continues to send Simple Emails to other verified email addresses in the list\n",email.bright_red().bold());
+                                    report!(self.sink, "The email address '{}' in the list hasn't been verified, yet it continues to send Simple Emails to other verified email addresses in the list\n",email.bright_red().bold());
                                     continue 'go;
                                 }
                             }
                             None => {
-                                println!(
+                                report!(
+                                    self.sink,
                                     "The email identity for '{}' does not exist",
                                     email.red().bold()
                                 );
-                                println!("{}\n","Please use the 'Create Email Identity' option or function to establish an identity for this email".yellow().bold());
+                                report!(self.sink, "{}\n","Please use the 'Create Email Identity' option or function to establish an identity for this email".yellow().bold());
                             }
                         }
                     } else {
-                        println!("The email address '{}' in this list doesn't have an identity; therefore, it creates an identity by executing the 'Create Email Identity' option on your behalf\n",email.yellow().bold());
-                        self.create_email_identity(&email).await;
+                        report!(self.sink, "The email address '{}' in this list doesn't have an identity; therefore, it creates an identity by executing the 'Create Email Identity' option on your behalf\n",email.yellow().bold());
+                        self.create_email_identity(&email).await?;
                     }
                 }
-                println!("{}\n","If you have any red-colored emails above, the operation won't be executed for those emails, but it will be executed for the other emails, where the Simple Mail has already been sent".yellow().bold());
+                report!(self.sink, "{}\n","If you have any red-colored emails above, the operation won't be executed for those emails, but it will be executed for the other emails, where the Simple Mail has already been sent".yellow().bold());
             }
             None => {
-                println!(
+                report!(
+                    self.sink,
                     "The provided list name '{}' doesn't exist",
                     self.get_list_name().red().bold()
                 );
             }
         }
+        Ok(())
     }
 }
 
@@ -1,138 +1,162 @@
-use std::{fs::OpenOptions, io::Write};
+use std::sync::Arc;
 
+use crate::{
+    error::{Error, SdkResultExt, Service},
+    output::{report, OutputSink, TerminalSink},
+    pagination::{collect_items, items_of_pages},
+};
 use aws_config::SdkConfig;
 use aws_sdk_sns::Client as SnsClient;
 use colored::Colorize;
+use tokio_stream::StreamExt;
 
-pub struct SnsOps<'a> {
-    config: &'a SdkConfig,
+#[derive(Debug, Clone)]
+pub struct SnsOps {
+    client: SnsClient,
+    sink: Arc<dyn OutputSink>,
 }
-impl<'a> SnsOps<'a> {
-    pub fn build(config: &'a SdkConfig) -> Self {
-        Self { config }
+impl SnsOps {
+    pub fn build(config: &SdkConfig) -> Self {
+        Self {
+            client: SnsClient::new(config),
+            sink: Arc::new(TerminalSink),
+        }
     }
-    pub async fn create_sandbox_phone_number(&self, phone_number: &str) {
-        let client = SnsClient::new(self.config);
-
-        client
+    pub fn with_sink(mut self, sink: Arc<dyn OutputSink>) -> Self {
+        self.sink = sink;
+        self
+    }
+    pub async fn create_sandbox_phone_number(&self, phone_number: &str) -> Result<(), Error> {
+        self.client
             .create_sms_sandbox_phone_number()
             .phone_number(phone_number)
             .send()
             .await
-            .expect("Error while sending Message\n");
-        println!("{}\n", "Phone Number has been added".green().bold());
+            .map_sdk_err(Service::Sns, "create_sms_sandbox_phone_number")?;
+        report!(
+            self.sink,
+            "{}\n",
+            "Phone Number has been added".green().bold()
+        );
+        Ok(())
     }
-    pub async fn list_sms_sandbox_numbers(&self) -> String {
-        let client = SnsClient::new(self.config);
-
-        let output = client
+    pub async fn list_sms_sandbox_numbers(&self) -> Result<String, Error> {
+        let pages = self
+            .client
             .list_sms_sandbox_phone_numbers()
+            .into_paginator()
             .send()
-            .await
-            .expect("Error while listing sms sanbox numbers\n");
+            .map(|page| page.map_sdk_err(Service::Sns, "list_sms_sandbox_phone_numbers"));
+        let phone_numbers_info = collect_items(items_of_pages(pages, |page| {
+            page.phone_numbers.unwrap_or_default()
+        }))
+        .await?;
         let mut phonenumber_with_status = Vec::new();
-        if let Some(phone_numbers_info) = output.phone_numbers {
-            phone_numbers_info.into_iter().for_each(|info| {
-                if let (Some(phone_number), Some(status)) = (info.phone_number, info.status) {
-                    let mut number_with_status = phone_number;
-                    number_with_status.push_str("    ");
-                    number_with_status.push_str(status.as_str());
-                    phonenumber_with_status.push(number_with_status);
-                }
-            });
-        }
-        phonenumber_with_status.join("\n")
+        phone_numbers_info.into_iter().for_each(|info| {
+            if let (Some(phone_number), Some(status)) = (info.phone_number, info.status) {
+                let mut number_with_status = phone_number;
+                number_with_status.push_str("    ");
+                number_with_status.push_str(status.as_str());
+                phonenumber_with_status.push(number_with_status);
+            }
+        });
+        Ok(phonenumber_with_status.join("\n"))
     }
-    pub async fn verify_phone_number(&self, phone_number: &str, otp: &str) {
-        let client = SnsClient::new(self.config);
-
-        client
+    pub async fn verify_phone_number(&self, phone_number: &str, otp: &str) -> Result<(), Error> {
+        self.client
             .verify_sms_sandbox_phone_number()
             .phone_number(phone_number)
             .one_time_password(otp)
             .send()
             .await
-            .expect("Error while verifying Phone Number");
-        println!("{}\n", "SMS has been verified successfully".green().bold());
+            .map_sdk_err(Service::Sns, "verify_sms_sandbox_phone_number")?;
+        report!(
+            self.sink,
+            "{}\n",
+            "SMS has been verified successfully".green().bold()
+        );
+        Ok(())
     }
-    pub async fn create_topic(&self, topic_name: &str) {
-        let client = SnsClient::new(self.config);
-
-        let output = client
+    pub async fn create_topic(&self, topic_name: &str) -> Result<(), Error> {
+        let output = self
+            .client
             .create_topic()
             .name(topic_name)
             .send()
             .await
-            .expect("Error while creating topic\n");
-        println!("{}\n", "The topic was created successfully".green().bold());
+            .map_sdk_err(Service::Sns, "create_topic")?;
+        report!(
+            self.sink,
+            "{}\n",
+            "The topic was created successfully".green().bold()
+        );
         if let Some(output_) = output.topic_arn {
             let arn = output_.green().bold();
-            println!("The Amazon Resource Name (ARN) for the SNS topic is: {arn}\n");
-            let mut file = OpenOptions::new()
-                .create(true)
-                .read(true)
-                .write(true)
-                .open("sns_topic_arn.txt")
-                .expect("Error while creating file");
-            let buf = format!("The Amazon Resource Name (ARN) for the SNS topic is: {arn}\n");
-            match file.write_all(buf.as_bytes()) {
-                Ok(_) => println!(
-                    "{}\n",
-                    "The ARN has been written to the current directory."
-                        .green()
-                        .bold()
-                ),
-                Err(_) => println!("Error while writing data"),
+            report!(
+                self.sink,
+                "The Amazon Resource Name (ARN) for the SNS topic is: {arn}\n"
+            );
+            let buf = format!("The Amazon Resource Name (ARN) for the SNS topic is: {output_}\n");
+            match self
+                .sink
+                .write_artifact("sns_topic_arn.txt", buf.as_bytes())
+            {
+                Ok(location) => {
+                    let msg = format!("The ARN has been written to {location}.");
+                    report!(self.sink, "{}\n", msg.green().bold())
+                }
+                Err(_) => report!(self.sink, "Error while writing data"),
             };
         }
+        Ok(())
     }
-    pub async fn subscription(&self, topic_arn: &str, protocol: &str, phone_number: &str) {
-        let client = SnsClient::new(self.config);
-
-        let output = client
+    pub async fn subscription(
+        &self,
+        topic_arn: &str,
+        protocol: &str,
+        phone_number: &str,
+    ) -> Result<(), Error> {
+        let output = self
+            .client
             .subscribe()
             .topic_arn(topic_arn)
             .endpoint(phone_number)
             .protocol(protocol)
             .send()
             .await
-            .expect("Error while subscribing\n");
+            .map_sdk_err(Service::Sns, "subscribe")?;
         if let Some(subscription_arn) = output.subscription_arn {
             let colored_arn = subscription_arn.green().bold();
-            println!("Subscription ARN: {colored_arn}\n");
-            let mut file = OpenOptions::new()
-                .create(true)
-                .read(true)
-                .write(true)
-                .open("sns_topic_arn.txt")
-                .expect("Error while creating file");
+            report!(self.sink, "Subscription ARN: {colored_arn}\n");
             let buf =
                 format!("The Amazon Resource Name for the Subscription is: {subscription_arn}\n");
-            match file.write_all(buf.as_bytes()) {
-                Ok(_) => println!(
-                    "{}\n",
-                    "The ARN is written to the current directory."
-                        .green()
-                        .bold()
-                ),
-                Err(_) => println!("Error while writing data"),
+            match self
+                .sink
+                .write_artifact("sns_topic_arn.txt", buf.as_bytes())
+            {
+                Ok(location) => {
+                    let msg = format!("The ARN is written to {location}.");
+                    report!(self.sink, "{}\n", msg.green().bold())
+                }
+                Err(_) => report!(self.sink, "Error while writing data"),
             }
         }
+        Ok(())
     }
-    pub async fn publish(&self, message: &str, topic_arn: &str) {
-        let client = SnsClient::new(self.config);
-
-        client
+    pub async fn publish(&self, message: &str, topic_arn: &str) -> Result<(), Error> {
+        self.client
             .publish()
             .topic_arn(topic_arn)
             .message(message)
             .subject("Testing")
             .send()
             .await
-            .expect("Error while sending messages to topic");
-        println!(
+            .map_sdk_err(Service::Sns, "publish")?;
+        report!(
+            self.sink,
             "{}\n",
             "Messages have been sent successfully....".green().bold()
         );
+        Ok(())
     }
 }
@@ -0,0 +1,372 @@
+use aws_sdk_s3::types::{
+    Condition, ErrorDocument, IndexDocument, Redirect, RoutingRule, WebsiteConfiguration,
+};
+use serde_json::json;
+
+use crate::s3_ops::{glob_match, SyncReport};
+
+//Regions whose website endpoints still use the older `s3-website-<region>` form
+const DASH_ENDPOINT_REGIONS: &[&str] = &[
+    "us-east-1",
+    "us-west-1",
+    "us-west-2",
+    "ap-southeast-1",
+    "ap-southeast-2",
+    "ap-northeast-1",
+    "eu-west-1",
+    "sa-east-1",
+    "us-gov-west-1",
+];
+const HTML_CACHE_CONTROL: &str = "no-cache";
+const IMMUTABLE_CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+const DEFAULT_CACHE_CONTROL: &str = "public, max-age=3600";
+//Fingerprints shorter than this are too easily confused with words
+const MIN_HASH_LEN: usize = 8;
+
+/// A routing rule of the website, applied in the order the rules were added.
+#[derive(Debug, Clone)]
+pub struct RedirectRule {
+    key_prefix: Option<String>,
+    error_code: Option<u16>,
+    replace_prefix: Option<String>,
+    replace_key: Option<String>,
+    host_name: Option<String>,
+    status: Option<u16>,
+}
+
+impl RedirectRule {
+    /// Redirects the keys starting with `from` to the same keys starting with `to` instead,
+    /// such as `docs/v1/` to `docs/v2/`.
+    pub fn prefix(from: &str, to: &str) -> Self {
+        Self {
+            key_prefix: Some(from.to_string()),
+            replace_prefix: Some(to.to_string()),
+            ..Self::empty()
+        }
+    }
+    /// Redirects the requests that would fail with an HTTP error code, such as 404, to a key.
+    pub fn on_error(code: u16, key: &str) -> Self {
+        Self {
+            error_code: Some(code),
+            replace_key: Some(key.to_string()),
+            ..Self::empty()
+        }
+    }
+    /// Sends the redirect to another host, such as the site's new domain.
+    pub fn host_name(mut self, host_name: &str) -> Self {
+        self.host_name = Some(host_name.to_string());
+        self
+    }
+    /// The HTTP status of the redirect; S3 answers with 301 otherwise.
+    pub fn status(mut self, status: u16) -> Self {
+        self.status = Some(status);
+        self
+    }
+    fn empty() -> Self {
+        Self {
+            key_prefix: None,
+            error_code: None,
+            replace_prefix: None,
+            replace_key: None,
+            host_name: None,
+            status: None,
+        }
+    }
+    fn to_sdk(&self) -> RoutingRule {
+        let condition = Condition::builder()
+            .set_key_prefix_equals(self.key_prefix.clone())
+            .set_http_error_code_returned_equals(self.error_code.map(|code| code.to_string()))
+            .build();
+        let redirect = Redirect::builder()
+            .set_replace_key_prefix_with(self.replace_prefix.clone())
+            .set_replace_key_with(self.replace_key.clone())
+            .set_host_name(self.host_name.clone())
+            .set_http_redirect_code(self.status.map(|status| status.to_string()))
+            .build();
+        RoutingRule::builder()
+            .condition(condition)
+            .redirect(redirect)
+            .build()
+    }
+}
+
+/// How [`S3Ops::deploy_static_site`](crate::S3Ops::deploy_static_site) sets up the bucket.
+///
+/// Files are uploaded with `Cache-Control` headers that suit a site that fingerprints its
+/// assets: HTML pages, `index.html` included, are revalidated on every request, assets with
+/// a content hash in their name (`app.3f9a1c2e.js`, `index-Bx7dk2Qa.css`) are cached for a
+/// year, and everything else for an hour.
+#[derive(Debug, Clone)]
+pub struct SiteOptions {
+    index_document: String,
+    error_document: Option<String>,
+    single_page_app: bool,
+    redirects: Vec<RedirectRule>,
+    cache_control: String,
+    immutable: Vec<String>,
+    delete_stale: bool,
+    adopt_existing: bool,
+}
+
+impl Default for SiteOptions {
+    fn default() -> Self {
+        Self {
+            index_document: "index.html".to_string(),
+            error_document: None,
+            single_page_app: false,
+            redirects: Vec::new(),
+            cache_control: DEFAULT_CACHE_CONTROL.to_string(),
+            immutable: Vec::new(),
+            delete_stale: true,
+            adopt_existing: false,
+        }
+    }
+}
+
+impl SiteOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// The page served for the root and for every "directory", `index.html` unless set.
+    pub fn index_document(mut self, name: &str) -> Self {
+        self.index_document = name.to_string();
+        self
+    }
+    /// The page served with 4xx errors, such as `404.html`.
+    pub fn error_document(mut self, key: &str) -> Self {
+        self.error_document = Some(key.to_string());
+        self
+    }
+    /// Serves the index document for paths that match no file, so a client-side router can
+    /// handle them. S3 still answers those requests with a 404 status, which a CDN in front
+    /// of the bucket can rewrite to 200.
+    pub fn single_page_app(mut self, enabled: bool) -> Self {
+        self.single_page_app = enabled;
+        self
+    }
+    pub fn redirect(mut self, rule: RedirectRule) -> Self {
+        self.redirects.push(rule);
+        self
+    }
+    /// The `Cache-Control` of the files that are neither HTML nor fingerprinted.
+    pub fn cache_control(mut self, cache_control: &str) -> Self {
+        self.cache_control = cache_control.to_string();
+        self
+    }
+    /// Caches the files matching a pattern, such as `assets/*`, for a year as if they were
+    /// fingerprinted. Patterns use `*` and `?` like [`SyncOptions`](crate::SyncOptions).
+    pub fn immutable(mut self, pattern: &str) -> Self {
+        self.immutable.push(pattern.to_string());
+        self
+    }
+    /// Deletes the objects that aren't in the local directory any more, which is on unless
+    /// turned off.
+    pub fn delete_stale(mut self, delete: bool) -> Self {
+        self.delete_stale = delete;
+        self
+    }
+    /// Deploys to a bucket that holds objects but isn't a website yet. It's refused
+    /// otherwise, because the deployment makes every object public and deletes those the
+    /// site doesn't have.
+    pub fn adopt_existing(mut self, adopt: bool) -> Self {
+        self.adopt_existing = adopt;
+        self
+    }
+    pub(crate) fn index(&self) -> &str {
+        &self.index_document
+    }
+    pub(crate) fn deletes_stale(&self) -> bool {
+        self.delete_stale
+    }
+    pub(crate) fn adopts_existing(&self) -> bool {
+        self.adopt_existing
+    }
+    pub(crate) fn website_configuration(&self) -> WebsiteConfiguration {
+        let error_document = match self.single_page_app {
+            true => Some(self.index_document.clone()),
+            false => self.error_document.clone(),
+        };
+        let routing_rules = self.redirects.iter().map(RedirectRule::to_sdk).collect();
+        WebsiteConfiguration::builder()
+            .index_document(
+                IndexDocument::builder()
+                    .suffix(self.index_document.clone())
+                    .build(),
+            )
+            .set_error_document(error_document.map(|key| ErrorDocument::builder().key(key).build()))
+            .set_routing_rules((!self.redirects.is_empty()).then_some(routing_rules))
+            .build()
+    }
+    /// The `Cache-Control` header of a file, by its path relative to the site's root.
+    pub(crate) fn cache_control_of(&self, relative_path: &str) -> &str {
+        let name = relative_path.rsplit('/').next().unwrap_or(relative_path);
+        if name.ends_with(".html") || name.ends_with(".htm") {
+            HTML_CACHE_CONTROL
+        } else if is_fingerprinted(name)
+            || self
+                .immutable
+                .iter()
+                .any(|pattern| glob_match(pattern, relative_path))
+        {
+            IMMUTABLE_CACHE_CONTROL
+        } else {
+            &self.cache_control
+        }
+    }
+}
+
+/// The outcome of [`S3Ops::deploy_static_site`](crate::S3Ops::deploy_static_site).
+#[derive(Debug, Clone)]
+pub struct SiteDeployment {
+    endpoint: String,
+    files: SyncReport,
+}
+
+impl SiteDeployment {
+    pub(crate) fn new(endpoint: String, files: SyncReport) -> Self {
+        Self { endpoint, files }
+    }
+    /// The URL the bucket's website is served at, such as
+    /// `http://docs.example.com.s3-website.eu-central-1.amazonaws.com`.
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+    /// The files uploaded and the objects deleted.
+    pub fn files(&self) -> &SyncReport {
+        &self.files
+    }
+}
+
+/// A policy that lets anyone read the bucket's objects. The guard rail is what it leaves
+/// out: nobody gets to list the bucket or change anything in it.
+pub(crate) fn public_read_policy(bucket_name: &str) -> String {
+    json!({
+        "Version": "2012-10-17",
+        "Statement": [{
+            "Sid": "PublicReadGetObject",
+            "Effect": "Allow",
+            "Principal": "*",
+            "Action": "s3:GetObject",
+            "Resource": format!("arn:aws:s3:::{bucket_name}/*"),
+        }],
+    })
+    .to_string()
+}
+
+pub(crate) fn website_endpoint(bucket_name: &str, region: &str) -> String {
+    let domain = match region.starts_with("cn-") {
+        true => "amazonaws.com.cn",
+        false => "amazonaws.com",
+    };
+    let separator = match DASH_ENDPOINT_REGIONS.contains(&region) {
+        true => '-',
+        false => '.',
+    };
+    format!("http://{bucket_name}.s3-website{separator}{region}.{domain}")
+}
+
+//Bundlers put the hash after the name, separated by `.` or `-`: `app.3f9a1c2e.js`,
+//`index-Bx7dk2Qa.css`, `chunk.5b1e.f00d4c3a.js`
+fn is_fingerprinted(name: &str) -> bool {
+    let stem = name.rsplit_once('.').map_or(name, |(stem, _)| stem);
+    stem.split(['.', '-']).skip(1).any(looks_like_hash)
+}
+
+fn looks_like_hash(segment: &str) -> bool {
+    if segment.len() < MIN_HASH_LEN || !segment.chars().all(|ch| ch.is_ascii_alphanumeric()) {
+        return false;
+    }
+    //Hex and base64 digests mix digits and letters, unlike dates and CamelCase names such
+    //as `Button-PrimaryAction.js`. A digest that happens to lack digits is only cached for
+    //less long
+    segment.chars().any(|ch| ch.is_ascii_digit())
+        && segment.chars().any(|ch| ch.is_ascii_alphabetic())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognizes_fingerprinted_names() {
+        assert!(is_fingerprinted("app.3f9a1c2e.js"));
+        assert!(is_fingerprinted("index-Bx7dk2Qa.css"));
+        assert!(is_fingerprinted("chunk.5b1e.f00d4c3a.js"));
+        assert!(!is_fingerprinted("Button-PrimaryAction.js"));
+        assert!(!is_fingerprinted("Header-NavBar.css"));
+        assert!(!is_fingerprinted("report-20231001.pdf"));
+        assert!(!is_fingerprinted("app.3f9a.js"));
+        assert!(!is_fingerprinted("app.js"));
+    }
+
+    #[test]
+    fn picks_the_cache_control_of_a_file() {
+        let options = SiteOptions::new().immutable("fonts/*");
+        assert_eq!(options.cache_control_of("index.html"), HTML_CACHE_CONTROL);
+        assert_eq!(
+            options.cache_control_of("docs/page.htm"),
+            HTML_CACHE_CONTROL
+        );
+        assert_eq!(
+            options.cache_control_of("assets/app.3f9a1c2e.js"),
+            IMMUTABLE_CACHE_CONTROL
+        );
+        assert_eq!(
+            options.cache_control_of("fonts/inter.woff2"),
+            IMMUTABLE_CACHE_CONTROL
+        );
+        assert_eq!(
+            options.cache_control_of("components/Header-NavBar.css"),
+            DEFAULT_CACHE_CONTROL
+        );
+        let options = options.cache_control("public, max-age=60");
+        assert_eq!(options.cache_control_of("logo.png"), "public, max-age=60");
+    }
+
+    #[test]
+    fn builds_website_endpoints() {
+        assert_eq!(
+            website_endpoint("docs", "us-east-1"),
+            "http://docs.s3-website-us-east-1.amazonaws.com"
+        );
+        assert_eq!(
+            website_endpoint("docs", "eu-central-1"),
+            "http://docs.s3-website.eu-central-1.amazonaws.com"
+        );
+        assert_eq!(
+            website_endpoint("docs", "cn-north-1"),
+            "http://docs.s3-website.cn-north-1.amazonaws.com.cn"
+        );
+    }
+
+    #[test]
+    fn serves_the_index_for_errors_of_a_single_page_app() {
+        let options = SiteOptions::new().error_document("404.html");
+        let config = options.website_configuration();
+        assert_eq!(
+            config.index_document().and_then(|index| index.suffix()),
+            Some("index.html")
+        );
+        assert_eq!(
+            config.error_document().and_then(|error| error.key()),
+            Some("404.html")
+        );
+        assert!(config.routing_rules().is_none());
+        let config = options
+            .single_page_app(true)
+            .redirect(RedirectRule::prefix("v1/", "v2/"))
+            .website_configuration();
+        assert_eq!(
+            config.error_document().and_then(|error| error.key()),
+            Some("index.html")
+        );
+        let rules = config.routing_rules().unwrap_or_default();
+        assert_eq!(rules.len(), 1);
+        assert_eq!(
+            rules[0]
+                .condition()
+                .and_then(|condition| condition.key_prefix_equals()),
+            Some("v1/")
+        );
+    }
+}
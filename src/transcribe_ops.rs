@@ -1,3 +1,9 @@
+use std::sync::Arc;
+
+use crate::{
+    error::{Error, SdkResultExt, Service},
+    output::{report, OutputSink, TerminalSink},
+};
 use aws_config::SdkConfig;
 use aws_sdk_transcribe::{
     primitives::DateTimeFormat,
@@ -6,12 +12,21 @@ use aws_sdk_transcribe::{
 };
 use colored::Colorize;
 
-pub struct TranscribeOps<'a> {
-    config: &'a SdkConfig,
+#[derive(Debug, Clone)]
+pub struct TranscribeOps {
+    client: TranscribeClient,
+    sink: Arc<dyn OutputSink>,
 }
-impl<'a> TranscribeOps<'a> {
-    pub fn build(config: &'a SdkConfig) -> Self {
-        Self { config }
+impl TranscribeOps {
+    pub fn build(config: &SdkConfig) -> Self {
+        Self {
+            client: TranscribeClient::new(config),
+            sink: Arc::new(TerminalSink),
+        }
+    }
+    pub fn with_sink(mut self, sink: Arc<dyn OutputSink>) -> Self {
+        self.sink = sink;
+        self
     }
     pub async fn start_transcribe_task(
         &self,
@@ -19,8 +34,7 @@ impl<'a> TranscribeOps<'a> {
         s3_media_uri: &str,
         media_format: &str,
         job_name: &str,
-    ) {
-        let client = TranscribeClient::new(self.config);
+    ) -> Result<(), Error> {
         let media_builder = Media::builder().media_file_uri(s3_media_uri).build();
         let media_format_builder = MediaFormat::from(media_format);
         let srt_format = SubtitleFormat::Srt;
@@ -30,7 +44,7 @@ impl<'a> TranscribeOps<'a> {
             .set_formats(Some(vec_of_formats))
             .output_start_index(1)
             .build();
-        client
+        self.client
             .start_transcription_job()
             .output_bucket_name(bucket_name)
             .output_key("transcribe_outputs/")
@@ -41,33 +55,39 @@ impl<'a> TranscribeOps<'a> {
             .transcription_job_name(job_name)
             .send()
             .await
-            .expect("Error while starting transcribing task\n");
-        println!(
+            .map_sdk_err(Service::Transcribe, "start_transcription_job")?;
+        report!(
+            self.sink,
             "The key prefix is configured as {} for the bucket name: {}\n",
             "'transcribe_outputs/'".green().bold(),
             bucket_name.green().bold()
         );
-        println!(
+        report!(
+            self.sink,
             "{}\n",
             "Both SRT and VTT subtitles are included in the request"
                 .yellow()
                 .bold()
         );
-        println!(
+        report!(
+            self.sink,
             "The job name {},is used to retrieve the transcribed results in the '{}'\n",
             job_name.green().bold(),
             "Get Transcription Job".yellow().bold()
         );
+        Ok(())
     }
-    pub async fn get_transcribe_results(&self, job_name: &str) -> Option<TranscriptionOutput> {
-        let client = TranscribeClient::new(self.config);
-
-        let output = client
+    pub async fn get_transcribe_results(
+        &self,
+        job_name: &str,
+    ) -> Result<Option<TranscriptionOutput>, Error> {
+        let output = self
+            .client
             .get_transcription_job()
             .transcription_job_name(job_name)
             .send()
             .await
-            .expect("Error while getting transcribe results\n");
+            .map_sdk_err(Service::Transcribe, "get_transcription_job")?;
 
         let mut transcription_job_output: Option<TranscriptionOutput> = None;
 
@@ -75,7 +95,7 @@ impl<'a> TranscribeOps<'a> {
             let wrap_type = TranscriptionOutput::wrap(results);
             transcription_job_output = Some(wrap_type);
         }
-        transcription_job_output
+        Ok(transcription_job_output)
     }
 }
 pub struct TranscriptionOutput(TranscriptionJob);
@@ -141,70 +161,75 @@ impl TranscriptionOutput {
         self.0.failure_reason.take()
     }
     //Help message in the bucket url : get the bukcket name then put s3://bucket_name/paste key name
-    pub fn print_transcription_info_as_text(&mut self) {
+    /// Prints the job details through the given sink, e.g. [`TerminalSink`]
+    pub fn print_transcription_info_as_text(&mut self, sink: &dyn OutputSink) {
         if let Some(name) = self.0.transcription_job_name.take() {
-            println!("Transcription Job Name: {}", name.green().bold());
+            report!(sink, "Transcription Job Name: {}", name.green().bold());
         }
         if let Some(status) = self.job_status() {
-            println!("Transcription Job Status: {}", status.green().bold());
+            report!(sink, "Transcription Job Status: {}", status.green().bold());
         }
 
         if let Some(code) = self.lang_code() {
-            println!("Language_code: {}", code.green().bold());
+            report!(sink, "Language_code: {}", code.green().bold());
         }
         if let Some(media_format_) = self.media_format() {
-            println!("Media Format: {}", media_format_.green().bold());
+            report!(sink, "Media Format: {}", media_format_.green().bold());
         }
         if let Some(media_) = self.media() {
-            println!("Media Information Inlcuded In the Request:\n");
+            report!(sink, "Media Information Inlcuded In the Request:\n");
             if let Some(media_uri) = media_.0 {
-                println!("Media_file Uri: {}", media_uri.green().bold());
+                report!(sink, "Media_file Uri: {}", media_uri.green().bold());
             }
 
             if let Some(redact) = media_.1 {
-                println!("Redacted Media File Uri: {}", redact.green().bold());
+                report!(sink, "Redacted Media File Uri: {}", redact.green().bold());
             }
         }
 
         if let Some(uris) = self.transcript_uri() {
             if let Some(trans_uri) = uris.0 {
-                println!("Transcript File Uri: {}", trans_uri.green().bold());
+                report!(sink, "Transcript File Uri: {}", trans_uri.green().bold());
             }
             if let Some(redact) = uris.1 {
-                println!("Redacted Transcript File Uri: {}", redact.green().bold());
+                report!(
+                    sink,
+                    "Redacted Transcript File Uri: {}",
+                    redact.green().bold()
+                );
             }
         }
         if let Some(stime) = self.start_time() {
-            println!("Start Time: {}", stime.green().bold());
+            report!(sink, "Start Time: {}", stime.green().bold());
         }
         if let Some(cre_time) = self.creation_time() {
-            println!("Creation Time: {}", cre_time.green().bold());
+            report!(sink, "Creation Time: {}", cre_time.green().bold());
         }
         if let Some(com_time) = self.completion_time() {
-            println!("Completion Time: {}", com_time.green().bold());
+            report!(sink, "Completion Time: {}", com_time.green().bold());
         }
 
         if let Some(fail_reason) = self.failure_reason() {
-            println!("Failure Reason: {}", fail_reason.green().bold());
+            report!(sink, "Failure Reason: {}", fail_reason.green().bold());
         }
 
         if let Some(sub_output) = self.0.subtitles.take() {
             if let Some(formats) = sub_output.formats {
                 formats.into_iter().for_each(|format| {
-                    println!("Subtitle Format: {}", format.as_str().green().bold());
+                    report!(sink, "Subtitle Format: {}", format.as_str().green().bold());
                 })
             }
             if let Some(sub_uris) = sub_output.subtitle_file_uris {
                 sub_uris.into_iter().for_each(|uri| {
-                    println!("Subtitle Uri: {}", uri.green().bold());
+                    report!(sink, "Subtitle Uri: {}", uri.green().bold());
                 });
             };
             if let Some(index) = sub_output.output_start_index {
-                println!("Starting Index: {}", index.to_string().green().bold());
+                report!(sink, "Starting Index: {}", index.to_string().green().bold());
             }
-            println!("");
-            println!("{}\n","The bucket URL cannot be accessed until it is made public or only accessible through the web console".yellow().bold());
-            println!("{}\n","To achieve this, please execute the 'Modify Object Visibility' option in the S3 operations menu to make the object public".yellow().bold());
+            report!(sink);
+            report!(sink, "{}\n","The bucket URL cannot be accessed until it is made public or only accessible through the web console".yellow().bold());
+            report!(sink, "{}\n","To achieve this, please execute the 'Modify Object Visibility' option in the S3 operations menu to make the object public".yellow().bold());
         }
     }
 }
@@ -2,100 +2,122 @@ use aws_config::SdkConfig;
 use aws_credential_types::provider::ProvideCredentials;
 use aws_sdk_translate::{
     primitives::{Blob, DateTimeFormat},
-    types::{Document, InputDataConfig, OutputDataConfig},
+    types::{Document, InputDataConfig, OutputDataConfig, TextTranslationJobProperties},
     Client as TranslateClient,
 };
 use colored::Colorize;
 use std::{
-    fs::{self, File, OpenOptions},
-    io::{Read, Write},
+    fs::{self, File},
+    io::Read,
+    sync::Arc,
 };
+use tokio_stream::StreamExt;
 
-use crate::{create_translated_text_pdf, create_translation_language_details_pdf};
-pub struct TranslateOps<'a> {
-    config: &'a SdkConfig,
+use crate::{
+    create_translated_text_pdf, create_translation_language_details_pdf,
+    error::{Error, SdkResultExt, Service},
+    output::{report, OutputSink, TerminalSink},
+    pagination::{collect_items, items_of_pages, ItemStream},
+};
+#[derive(Debug, Clone)]
+pub struct TranslateOps {
+    config: SdkConfig,
+    client: TranslateClient,
+    sink: Arc<dyn OutputSink>,
 }
-impl<'a> TranslateOps<'a> {
-    pub fn build(config: &'a SdkConfig) -> Self {
-        Self { config }
+impl TranslateOps {
+    pub fn build(config: &SdkConfig) -> Self {
+        Self {
+            config: config.clone(),
+            client: TranslateClient::new(config),
+            sink: Arc::new(TerminalSink),
+        }
     }
-    pub async fn list_languages(&self, write_info: bool) -> (Vec<String>, Vec<String>) {
-        let client = TranslateClient::new(self.config);
-        let outputs = client
+    pub fn with_sink(mut self, sink: Arc<dyn OutputSink>) -> Self {
+        self.sink = sink;
+        self
+    }
+    pub async fn list_languages(
+        &self,
+        write_info: bool,
+    ) -> Result<(Vec<String>, Vec<String>), Error> {
+        let pages = self
+            .client
             .list_languages()
+            .into_paginator()
             .send()
-            .await
-            .expect("Error while Listing Languages\n");
+            .map(|page| page.map_sdk_err(Service::Translate, "list_languages"));
+        let language = collect_items(items_of_pages(pages, |page| {
+            page.languages.unwrap_or_default()
+        }))
+        .await?;
         let mut lang_names = Vec::new();
         let mut lang_codes = Vec::new();
-        let mut file = OpenOptions::new()
-            .create(true)
-            .read(true)
-            .write(true)
-            .open("Supported_Languages_Details.txt")
-            .expect("Error while creating file\n");
-        if let Some(language) = outputs.languages {
-            language.into_iter().for_each(|details| {
-                if let Some(lang_name) = details.language_name {
-                    let buf = format!("Language Name: {}\n", lang_name);
-                    lang_names.push(lang_name);
-                    file.write_all(buf.as_bytes()).unwrap();
-                }
-                if let Some(lang_code) = details.language_code {
-                    let buf = format!("Language Code: {}\n\n", lang_code);
-                    lang_codes.push(lang_code);
-                    file.write_all(buf.as_bytes()).unwrap();
-                }
-            });
+        let mut contents = String::new();
+        for details in language {
+            if let Some(lang_name) = details.language_name {
+                let buf = format!("Language Name: {}\n", lang_name);
+                lang_names.push(lang_name);
+                contents.push_str(&buf);
+            }
+            if let Some(lang_code) = details.language_code {
+                let buf = format!("Language Code: {}\n\n", lang_code);
+                lang_codes.push(lang_code);
+                contents.push_str(&buf);
+            }
         }
         if write_info {
-            match File::open("Supported_Languages_Details.txt") {
-                Ok(_) => println!("The supported language details have been saved to the current directory with the filename '{}'\n","Supported_Languages_Details.txt".green().bold()),
-                Err(_) => println!("{}\n","Error while writing Data".red().bold())
+            match self
+                .sink
+                .write_artifact("Supported_Languages_Details.txt", contents.as_bytes())
+            {
+                Ok(location) => report!(self.sink, "The supported language details have been saved to {location} with the filename '{}'\n","Supported_Languages_Details.txt".green().bold()),
+                Err(_) => report!(self.sink, "{}\n","Error while writing Data".red().bold())
             }
-            create_translation_language_details_pdf(lang_names.clone(), lang_codes.clone());
+            create_translation_language_details_pdf(
+                lang_names.clone(),
+                lang_codes.clone(),
+                self.sink.as_ref(),
+            )?;
         }
 
-        (lang_names, lang_codes)
+        Ok((lang_names, lang_codes))
     }
-    pub async fn translate_text(&self, text_path: &str, target_lang_code: &str) {
-        let client = TranslateClient::new(self.config);
-        let text_data =
-            fs::read_to_string(text_path).expect("Error opening the file you specified\n");
-        let outputs = client
+    pub async fn translate_text(
+        &self,
+        text_path: &str,
+        target_lang_code: &str,
+    ) -> Result<(), Error> {
+        let text_data = fs::read_to_string(text_path)
+            .map_err(|err| Error::io("translate_text", text_path, err))?;
+        let outputs = self
+            .client
             .translate_text()
             .source_language_code("auto")
             .text(text_data)
             .target_language_code(target_lang_code)
             .send()
             .await
-            .expect("Error while Translate Text\n");
+            .map_sdk_err(Service::Translate, "translate_text")?;
         if let Some(translated_text) = outputs.translated_text {
-            let mut file = OpenOptions::new()
-                .create(true)
-                .read(true)
-                .write(true)
-                .open("TranslatedText.txt")
-                .expect("Error while creating file\n");
             let buf = format!(
                 "The translation for the provided text:\n{}",
                 translated_text
             );
-            file.write_all(buf.as_bytes()).unwrap();
-            match File::open("TranslatedText.txt") {
-                Ok(_) => println!("The translated text for the given input is saved in the current directory under the filename '{}'\n","TranslatedText.txt".green().bold()),
-                Err(_) => println!("{}\n","Error while opening the file".red().bold()),
+            match self.sink.write_artifact("TranslatedText.txt", buf.as_bytes()) {
+                Ok(location) => report!(self.sink, "The translated text for the given input is saved in {location} under the filename '{}'\n","TranslatedText.txt".green().bold()),
+                Err(_) => report!(self.sink, "{}\n","Error while writing the file".red().bold()),
             }
-            create_translated_text_pdf(translated_text);
+            create_translated_text_pdf(translated_text, self.sink.as_ref())?;
         }
+        Ok(())
     }
     pub async fn translate_document(
         &self,
         document_type: &str,
         document_path: &str,
         target_lang_code: &str,
-    ) {
-        let client = TranslateClient::new(self.config);
+    ) -> Result<(), Error> {
         let document_type_ = match document_type {
             "html" | "HTML" | "Html" => Some("text/html"),
             "plain" | "Plain" | "PLAIN" => Some("text/plain"),
@@ -106,23 +128,24 @@ impl<'a> TranslateOps<'a> {
         };
         match document_type_ {
             Some(type_) => {
-                let mut file =
-                    File::open(document_path).expect("Error opening the file path specified\n");
+                let read_err = |err| Error::io("translate_document", document_path, err);
+                let mut file = File::open(document_path).map_err(read_err)?;
                 let mut vec_of_bytes = Vec::new();
-                file.read_to_end(&mut vec_of_bytes).unwrap();
+                file.read_to_end(&mut vec_of_bytes).map_err(read_err)?;
                 let build_blob = Blob::new(vec_of_bytes);
                 let document_build = Document::builder()
                     .content(build_blob)
                     .content_type(type_)
                     .build();
-                let outputs = client
+                let outputs = self
+                    .client
                     .translate_document()
                     .source_language_code("auto")
                     .document(document_build)
                     .target_language_code(target_lang_code)
                     .send()
                     .await
-                    .expect("Error while Translating Document\n");
+                    .map_sdk_err(Service::Translate, "translate_document")?;
                 if let Some(translated_document) = outputs.translated_document {
                     if let Some(content) = translated_document.content {
                         let file_name = match document_type {
@@ -133,18 +156,14 @@ impl<'a> TranslateOps<'a> {
                         };
                         match file_name {
                             Some(file_name) => {
-                                let mut file = OpenOptions::new()
-                                    .create(true)
-                                    .read(true)
-                                    .write(true)
-                                    .open(&file_name)
-                                    .expect("Error while creating file\n");
                                 let translated_content = content.into_inner();
-                                file.write_all(&translated_content).unwrap();
-                                match File::open(&file_name) {
-                    Ok(_) => println!("The translated text for the given input is saved in the current directory under the filename '{}'\n",file_name.green().bold()),
-                    Err(_) => println!("{}\n","Error while opening the file".red().bold()),
-                }
+                                let location = self
+                                    .sink
+                                    .write_artifact(&file_name, &translated_content)
+                                    .map_err(|err| {
+                                        Error::io("translate_document", file_name.as_str(), err)
+                                    })?;
+                                report!(self.sink, "The translated text for the given input is saved in {location} under the filename '{}'\n",file_name.green().bold());
                             }
                             None => {}
                         }
@@ -152,16 +171,26 @@ impl<'a> TranslateOps<'a> {
                 }
             }
             None => {
-                println!(
+                report!(
+                    self.sink,
                     "Unsupported Document Type: {}\n",
                     document_type.red().bold()
                 );
-                println!("{}", "Supported Document Types are,".yellow().bold());
-                println!("{}", "1) html or HTML or Html".yellow().bold());
-                println!("{}", "2) plain or Plain or PLAIN".yellow().bold());
-                println!("{}\n", "3) word or WORD or Word".yellow().bold());
+                report!(
+                    self.sink,
+                    "{}",
+                    "Supported Document Types are,".yellow().bold()
+                );
+                report!(self.sink, "{}", "1) html or HTML or Html".yellow().bold());
+                report!(
+                    self.sink,
+                    "{}",
+                    "2) plain or Plain or PLAIN".yellow().bold()
+                );
+                report!(self.sink, "{}\n", "3) word or WORD or Word".yellow().bold());
             }
         }
+        Ok(())
     }
     pub async fn start_text_translation_job(
         &self,
@@ -171,8 +200,7 @@ impl<'a> TranslateOps<'a> {
         document_type: &str,
         output_s3_uri: &str,
         role_arn: &str,
-    ) {
-        let client = TranslateClient::new(self.config);
+    ) -> Result<(), Error> {
         let document_type_ = match document_type {
             "html" | "HTML" | "Html" => Some("text/html"),
             "plain" | "Plain" | "PLAIN" => Some("text/plain"),
@@ -193,9 +221,18 @@ impl<'a> TranslateOps<'a> {
                 let client_token = match std::env::var("aws_access_key_id") {
                     Ok(access_key) => access_key,
                     Err(_) => {
-                        println!("The '{}' environment variable is not found, so I am attempting to retrieve the access key from the credentials file","aws_access_key_id\n".yellow().bold());
-                        let shared_credential = self.config.credentials_provider().unwrap();
-                        let credentials = shared_credential.provide_credentials().await.unwrap();
+                        report!(self.sink, "The '{}' environment variable is not found, so I am attempting to retrieve the access key from the credentials file","aws_access_key_id\n".yellow().bold());
+                        let shared_credential =
+                            self.config
+                                .credentials_provider()
+                                .ok_or(Error::invalid_input(
+                                    "start_text_translation_job",
+                                    "no credentials provider is configured",
+                                ))?;
+                        let credentials = shared_credential
+                            .provide_credentials()
+                            .await
+                            .map_err(|err| Error::other("start_text_translation_job", err))?;
                         credentials.access_key_id().to_string()
                     }
                 };
@@ -204,7 +241,8 @@ impl<'a> TranslateOps<'a> {
                     .content_type(document_type)
                     .build();
                 let output_config = OutputDataConfig::builder().s3_uri(output_s3_uri).build();
-                let outputs = client
+                let outputs = self
+                    .client
                     .start_text_translation_job()
                     .client_token(client_token)
                     .data_access_role_arn(role_arn)
@@ -215,101 +253,114 @@ impl<'a> TranslateOps<'a> {
                     .job_name(job_name)
                     .send()
                     .await
-                    .expect("Error while Starting Text Transcription Job\n");
+                    .map_sdk_err(Service::Translate, "start_text_translation_job")?;
                 let file_name = format!("TranscriptionJobIDFor{}.txt", job_name);
-                let mut file = OpenOptions::new()
-                    .create(true)
-                    .read(true)
-                    .write(true)
-                    .open(&file_name)
-                    .expect("Error while creating file\n");
                 if let Some(job_id) = outputs.job_id {
-                    println!("The Job ID: {}", job_id.green().bold());
+                    report!(self.sink, "The Job ID: {}", job_id.green().bold());
                     let buf = format!("Transcription Job ID for the Job Name: {}", job_id);
-                    file.write_all(buf.as_bytes()).unwrap();
-                    match File::open(&file_name) {
-                        Ok(_) => {
-                            println!("The job ID has been written to a file named '{}' in the current directory for the job named '{}'",file_name.green().bold(),job_name.green().bold());
-                            println!("{}\n","The job ID is necessary to retrieve details about the job in other options".yellow().bold());
+                    match self.sink.write_artifact(&file_name, buf.as_bytes()) {
+                        Ok(location) => {
+                            report!(self.sink, "The job ID has been written to a file named '{}' in {location} for the job named '{}'",file_name.green().bold(),job_name.green().bold());
+                            report!(self.sink, "{}\n","The job ID is necessary to retrieve details about the job in other options".yellow().bold());
                         }
-                        Err(_) => println!("{}\n", "Error while writing the job id".red().bold()),
+                        Err(_) => report!(
+                            self.sink,
+                            "{}\n",
+                            "Error while writing the job id".red().bold()
+                        ),
                     }
                 }
                 if let Some(job_status) = outputs.job_status {
                     let status = job_status.as_str();
-                    println!(
+                    report!(
+                        self.sink,
                         "The Current Status of Transcipion Job Is: {}",
                         status.green().bold()
                     );
                 }
-                println!("{}\n","To check the status and obtain additional information about the transcription job, use the ---Describe Text Translation Job--- option".yellow().bold());
+                report!(self.sink, "{}\n","To check the status and obtain additional information about the transcription job, use the ---Describe Text Translation Job--- option".yellow().bold());
             }
             None => {
-                println!(
+                report!(
+                    self.sink,
                     "Unsupported Document Type: {}\n",
                     document_type.red().bold()
                 );
-                println!("{}\n", "Supported Document Types are".yellow().bold());
-                println!("{}", "1) html or HTML or Html".yellow().bold());
-                println!("{}", "2) plain or Plain or PLAIN".yellow().bold());
-                println!("{}", "3) word or WORD or Word".yellow().bold());
-                println!("{}", "4) ppt or PPT or Ppt".yellow().bold());
-                println!("{}", "5) xlsx or XLSX or Xlsx ".yellow().bold());
-                println!("{}\n", "6) xlf or XLF or Xlf".yellow().bold());
+                report!(
+                    self.sink,
+                    "{}\n",
+                    "Supported Document Types are".yellow().bold()
+                );
+                report!(self.sink, "{}", "1) html or HTML or Html".yellow().bold());
+                report!(
+                    self.sink,
+                    "{}",
+                    "2) plain or Plain or PLAIN".yellow().bold()
+                );
+                report!(self.sink, "{}", "3) word or WORD or Word".yellow().bold());
+                report!(self.sink, "{}", "4) ppt or PPT or Ppt".yellow().bold());
+                report!(self.sink, "{}", "5) xlsx or XLSX or Xlsx ".yellow().bold());
+                report!(self.sink, "{}\n", "6) xlf or XLF or Xlf".yellow().bold());
             }
         };
+        Ok(())
     }
 
-    pub async fn describe_text_translation_job(&self, job_id: &str) {
-        let client = TranslateClient::new(self.config);
-        let outputs = client
+    pub async fn describe_text_translation_job(&self, job_id: &str) -> Result<(), Error> {
+        let outputs = self
+            .client
             .describe_text_translation_job()
             .job_id(job_id)
             .send()
             .await
-            .expect("Error While Describing Text Translation Job\n");
+            .map_sdk_err(Service::Translate, "describe_text_translation_job")?;
         if let Some(details) = outputs.text_translation_job_properties {
             if let Some(data_role_access_arn) = details.data_access_role_arn {
-                println!(
+                report!(
+                    self.sink,
                     "Data Access Role Amazon Resource Name(ARN): {}",
                     data_role_access_arn.green().bold()
                 );
             }
             if let Some(job_name) = details.job_name {
-                println!("Job Name: {}", job_name.green().bold());
+                report!(self.sink, "Job Name: {}", job_name.green().bold());
             }
             if let Some(job_id) = details.job_id {
-                println!("Job ID: {}", job_id.green().bold());
+                report!(self.sink, "Job ID: {}", job_id.green().bold());
             }
             if let Some(source_language_code) = details.source_language_code {
-                println!(
+                report!(
+                    self.sink,
                     "Source Language Code: {}",
                     source_language_code.green().bold()
                 );
-                println!("{}\n","The source language of the document is automatically identified using the ;DetectDominantLanguages' API of the Comprehend service".yellow().bold());
+                report!(self.sink, "{}\n","The source language of the document is automatically identified using the ;DetectDominantLanguages' API of the Comprehend service".yellow().bold());
             }
             if let Some(target_languages_codes) = details.target_language_codes {
-                println!("{}\n", "Target Language Codes".yellow().bold());
+                report!(self.sink, "{}\n", "Target Language Codes".yellow().bold());
                 target_languages_codes.into_iter().for_each(|lang_code| {
-                    println!("Language Code: {}", lang_code.green().bold());
+                    report!(self.sink, "Language Code: {}", lang_code.green().bold());
                 });
-                println!("{}\n","Execute the 'Get Language Info' option to determine the language associated with the provided language codes".yellow().bold());
+                report!(self.sink, "{}\n","Execute the 'Get Language Info' option to determine the language associated with the provided language codes".yellow().bold());
             }
             if let Some(job_details) = details.job_details {
                 if let Some(document_count) = job_details.input_documents_count {
-                    println!(
+                    report!(
+                        self.sink,
                         "Input Document Count: {}",
                         document_count.to_string().green().bold()
                     );
                 }
                 if let Some(success_counts) = job_details.translated_documents_count {
-                    println!(
+                    report!(
+                        self.sink,
                         "Document Success Count: {}",
                         success_counts.to_string().green().bold()
                     );
                 }
                 if let Some(error_counts) = job_details.documents_with_errors_count {
-                    println!(
+                    report!(
+                        self.sink,
                         "Document Error Count: {}",
                         error_counts.to_string().green().bold()
                     );
@@ -317,121 +368,132 @@ impl<'a> TranslateOps<'a> {
             }
             if let Some(output_config) = details.output_data_config {
                 if let Some(s3_uri) = output_config.s3_uri {
-                    println!("Output S3 Uri: {}", s3_uri.green().bold());
+                    report!(self.sink, "Output S3 Uri: {}", s3_uri.green().bold());
                 }
             }
             if let Some(submit_time) = details.submitted_time {
                 let submittime = submit_time.fmt(DateTimeFormat::HttpDate).ok();
                 if let Some(time) = submittime {
-                    println!("Submitted Date and Time: {}", time.green().bold());
+                    report!(
+                        self.sink,
+                        "Submitted Date and Time: {}",
+                        time.green().bold()
+                    );
                 }
             }
             if let Some(end_time) = details.end_time {
                 let convert_time = end_time.fmt(DateTimeFormat::HttpDate).ok();
                 if let Some(time) = convert_time {
-                    println!("Finished Date and Time: {}", time.green().bold());
+                    report!(self.sink, "Finished Date and Time: {}", time.green().bold());
                 }
             }
             if let Some(job_status) = details.job_status {
                 let status = job_status.as_str();
-                println!("Job Status: {}", status.green().bold());
+                report!(self.sink, "Job Status: {}", status.green().bold());
             }
             if let Some(message) = details.message {
-                println!("Message: {}", message.green().bold());
+                report!(self.sink, "Message: {}", message.green().bold());
             }
-            println!("");
+            report!(self.sink);
         }
+        Ok(())
     }
-    pub async fn list_translation_jobs(&self) {
-        let client = TranslateClient::new(self.config);
-        let outputs = client
-            .list_text_translation_jobs()
-            .send()
-            .await
-            .expect("Error while listing Translation Jobs\n");
-        let mut file = OpenOptions::new()
-            .create(true)
-            .read(true)
-            .write(true)
-            .open("ListTranslationJobsDetails.txt")
-            .expect("Error while creating file\n");
-        if let Some(transription_job_properties) = outputs.text_translation_job_properties_list {
-            transription_job_properties.into_iter().for_each(|details| {
-                if let Some(data_role_access_arn) = details.data_access_role_arn {
-                    let buf = format!("Data Access Role Arn: {}\n", data_role_access_arn);
-                    file.write_all(buf.as_bytes()).unwrap();
-                }
-                if let Some(job_name) = details.job_name {
-                    let buf = format!("Job Name: {}\n", job_name);
-                    file.write_all(buf.as_bytes()).unwrap();
-                }
-                if let Some(job_id) = details.job_id {
-                    let buf = format!("Job ID: {}\n", job_id);
-                    file.write_all(buf.as_bytes()).unwrap();
-                }
-                if let Some(submit_time) = details.submitted_time {
-                    let convert_time = submit_time.fmt(DateTimeFormat::HttpDate).ok();
-                    if let Some(time) = convert_time {
-                        let buf = format!("Submitted Time: {}\n", time);
-                        file.write_all(buf.as_bytes()).unwrap();
-                    }
-                }
-                if let Some(end_time) = details.end_time {
-                    let convert_time = end_time.fmt(DateTimeFormat::HttpDate).ok();
-                    if let Some(time) = convert_time {
-                        let buf = format!("Finished Time: {}\n", time);
-                        file.write_all(buf.as_bytes()).unwrap();
-                    }
+    pub async fn list_translation_jobs(&self) -> Result<(), Error> {
+        let transription_job_properties =
+            collect_items(self.list_translation_jobs_stream()).await?;
+        let mut contents = String::new();
+        for details in transription_job_properties {
+            if let Some(data_role_access_arn) = details.data_access_role_arn {
+                let buf = format!("Data Access Role Arn: {}\n", data_role_access_arn);
+                contents.push_str(&buf);
+            }
+            if let Some(job_name) = details.job_name {
+                let buf = format!("Job Name: {}\n", job_name);
+                contents.push_str(&buf);
+            }
+            if let Some(job_id) = details.job_id {
+                let buf = format!("Job ID: {}\n", job_id);
+                contents.push_str(&buf);
+            }
+            if let Some(submit_time) = details.submitted_time {
+                let convert_time = submit_time.fmt(DateTimeFormat::HttpDate).ok();
+                if let Some(time) = convert_time {
+                    let buf = format!("Submitted Time: {}\n", time);
+                    contents.push_str(&buf);
                 }
-                if let Some(source_language_code) = details.source_language_code {
-                    let buf = format!("Source Language Code: {}\n", source_language_code);
-                    file.write_all(buf.as_bytes()).unwrap();
+            }
+            if let Some(end_time) = details.end_time {
+                let convert_time = end_time.fmt(DateTimeFormat::HttpDate).ok();
+                if let Some(time) = convert_time {
+                    let buf = format!("Finished Time: {}\n", time);
+                    contents.push_str(&buf);
                 }
-                if let Some(target_languages_codes) = details.target_language_codes {
-                    file.write_all("Target Language Codes:\n".as_bytes())
-                        .unwrap();
-                    target_languages_codes.into_iter().for_each(|lang_code| {
-                        file.write_all(lang_code.as_bytes()).unwrap();
-                        file.write_all("\n".as_bytes()).unwrap();
-                    });
+            }
+            if let Some(source_language_code) = details.source_language_code {
+                let buf = format!("Source Language Code: {}\n", source_language_code);
+                contents.push_str(&buf);
+            }
+            if let Some(target_languages_codes) = details.target_language_codes {
+                contents.push_str("Target Language Codes:\n");
+                for lang_code in target_languages_codes {
+                    contents.push_str(&lang_code);
+                    contents.push('\n');
                 }
-                if let Some(output_config) = details.output_data_config {
-                    if let Some(s3_uri) = output_config.s3_uri {
-                        let buf = format!("Output S3 Uri: {}\n", s3_uri);
-                        file.write_all(buf.as_bytes()).unwrap();
-                    }
+            }
+            if let Some(output_config) = details.output_data_config {
+                if let Some(s3_uri) = output_config.s3_uri {
+                    let buf = format!("Output S3 Uri: {}\n", s3_uri);
+                    contents.push_str(&buf);
                 }
-                if let Some(job_details) = details.job_details {
-                    if let Some(document_count) = job_details.input_documents_count {
-                        let buf = format!("Input Document Counts: {}\n", document_count);
-                        file.write_all(buf.as_bytes()).unwrap();
-                    }
-                    if let Some(success_counts) = job_details.translated_documents_count {
-                        let buf = format!("Document Success Count: {}\n", success_counts);
-                        file.write_all(buf.as_bytes()).unwrap();
-                    }
-                    if let Some(error_counts) = job_details.documents_with_errors_count {
-                        let buf = format!("Document Error Count: {}\n", error_counts);
-                        file.write_all(buf.as_bytes()).unwrap();
-                    }
+            }
+            if let Some(job_details) = details.job_details {
+                if let Some(document_count) = job_details.input_documents_count {
+                    let buf = format!("Input Document Counts: {}\n", document_count);
+                    contents.push_str(&buf);
                 }
-                if let Some(job_status) = details.job_status {
-                    let status = job_status.as_str();
-                    let buf = format!("Job Status: {}\n", status);
-                    file.write_all(buf.as_bytes()).unwrap();
+                if let Some(success_counts) = job_details.translated_documents_count {
+                    let buf = format!("Document Success Count: {}\n", success_counts);
+                    contents.push_str(&buf);
                 }
-                if let Some(message) = details.message {
-                    let buf = format!("Job Message: {}\n\n\n", message);
-                    file.write_all(buf.as_bytes()).unwrap();
+                if let Some(error_counts) = job_details.documents_with_errors_count {
+                    let buf = format!("Document Error Count: {}\n", error_counts);
+                    contents.push_str(&buf);
                 }
-            });
+            }
+            if let Some(job_status) = details.job_status {
+                let status = job_status.as_str();
+                let buf = format!("Job Status: {}\n", status);
+                contents.push_str(&buf);
+            }
+            if let Some(message) = details.message {
+                let buf = format!("Job Message: {}\n\n\n", message);
+                contents.push_str(&buf);
+            }
         }
-        match File::open("ListTranslationJobsDetails.txt") {
-            Ok(_) => {
-                println!("The details of the translation job list have been written to the current directory with the filename '{}'\n","ListTranslationJobsDetails.txt".green().bold());
-                println!("{}\n","If you want to access specific job information without leaving the terminal, execute the ---Describe Text Translation Job--- option to learn more about the job details".yellow().bold());
+        match self
+            .sink
+            .write_artifact("ListTranslationJobsDetails.txt", contents.as_bytes())
+        {
+            Ok(location) => {
+                report!(self.sink, "The details of the translation job list have been written to {location} with the filename '{}'\n","ListTranslationJobsDetails.txt".green().bold());
+                report!(self.sink, "{}\n","If you want to access specific job information without leaving the terminal, execute the ---Describe Text Translation Job--- option to learn more about the job details".yellow().bold());
             }
-            Err(_) => println!("{}\n", "Error writing File".red().bold()),
+            Err(_) => report!(self.sink, "{}\n", "Error writing File".red().bold()),
         }
+        Ok(())
+    }
+    /// Lists every text translation job in the account and region, requesting further pages
+    /// as the stream is consumed
+    pub fn list_translation_jobs_stream(&self) -> ItemStream<TextTranslationJobProperties> {
+        let pages = self
+            .client
+            .list_text_translation_jobs()
+            .into_paginator()
+            .send()
+            .map(|page| page.map_sdk_err(Service::Translate, "list_text_translation_jobs"));
+        items_of_pages(pages, |page| {
+            page.text_translation_job_properties_list
+                .unwrap_or_default()
+        })
     }
 }